@@ -0,0 +1,26 @@
+//! 由 Rust 配置结构生成 app.config.json、它的 JSON Schema 与前端 TypeScript 类型。
+//!
+//! ```bash
+//! cargo run --example config_schema
+//! ```
+//!
+//! 提交的文件是否过期由 `config::schema` 的单元测试检查。
+
+use app_lib::config_schema;
+use std::path::Path;
+
+fn main() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    for (relative, content) in config_schema::outputs() {
+        let path = root.join(relative);
+        if std::fs::read_to_string(&path).unwrap_or_default() == content {
+            continue;
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).expect("failed to create output dir");
+        }
+        std::fs::write(&path, content).expect("failed to write output");
+        println!("✅ Generated {}", path.display());
+    }
+}
@@ -0,0 +1,266 @@
+//! 登录状态机：在 Rust 侧记录登录状态并决定显示登录窗口还是主窗口。
+//! 前端在登录、登出、会话过期时调用 update_auth_state，每次状态变化广播 auth:state-changed
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// 登录状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthStatus {
+    LoggedOut,
+    LoggingIn,
+    Authenticated,
+    SessionExpired,
+}
+
+/// 引起状态变化的事件（由前端上报）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthEvent {
+    /// 提交登录（含注册后自动登录）
+    LoginStarted,
+    LoginSucceeded,
+    LoginFailed,
+    /// 手动登出，或在登录过程中取消
+    Logout,
+    /// 请求返回 401
+    SessionExpired,
+}
+
+/// 状态转换表，不允许的转换返回 None
+pub fn transition(from: AuthStatus, event: AuthEvent) -> Option<AuthStatus> {
+    use AuthEvent as E;
+    use AuthStatus as S;
+    match (from, event) {
+        (S::LoggedOut | S::SessionExpired, E::LoginStarted) => Some(S::LoggingIn),
+        (S::LoggingIn, E::LoginSucceeded) => Some(S::Authenticated),
+        (S::LoggingIn, E::LoginFailed) => Some(S::LoggedOut),
+        (S::LoggingIn | S::Authenticated | S::SessionExpired, E::Logout) => Some(S::LoggedOut),
+        (S::Authenticated, E::SessionExpired) => Some(S::SessionExpired),
+        _ => None,
+    }
+}
+
+/// 只有已登录时显示主窗口，其他状态都显示登录窗口
+fn shows_main_window(status: AuthStatus) -> bool {
+    status == AuthStatus::Authenticated
+}
+
+/// auth:state-changed 事件载荷
+#[derive(Debug, Clone, Serialize)]
+struct AuthStateChanged {
+    from: AuthStatus,
+    to: AuthStatus,
+    event: AuthEvent,
+}
+
+/// 当前登录状态（Tauri managed state）
+pub struct AppState {
+    status: Mutex<AuthStatus>,
+}
+
+impl AppState {
+    /// 有保存的令牌时视为已登录（过期由之后的 401 转为 session_expired）
+    pub fn new(has_token: bool) -> Self {
+        let status = if has_token {
+            AuthStatus::Authenticated
+        } else {
+            AuthStatus::LoggedOut
+        };
+        Self {
+            status: Mutex::new(status),
+        }
+    }
+
+    pub fn status(&self) -> AuthStatus {
+        *self.status.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 当前状态；未初始化时按未登录处理
+pub fn status<R: Runtime>(app: &AppHandle<R>) -> AuthStatus {
+    app.try_state::<AppState>()
+        .map_or(AuthStatus::LoggedOut, |state| state.status())
+}
+
+pub fn is_authenticated<R: Runtime>(app: &AppHandle<R>) -> bool {
+    status(app) == AuthStatus::Authenticated
+}
+
+/// 显示主窗口前检查：未登录时返回错误
+pub fn require_authenticated<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    if shows_main_window(status(app)) {
+        Ok(())
+    } else {
+        Err("Not logged in".to_string())
+    }
+}
+
+/// 按当前状态显示登录窗口或主窗口（启动时调用）
+pub fn sync_windows(app: &AppHandle) -> Result<(), String> {
+    if shows_main_window(status(app)) {
+        crate::window::show_main(app)
+    } else {
+        crate::window::show_login(app)
+    }
+}
+
+/// 执行一次状态转换：广播 auth:state-changed，需要时切换窗口
+pub fn apply(app: &AppHandle, event: AuthEvent) -> Result<AuthStatus, String> {
+    let state = app
+        .try_state::<AppState>()
+        .ok_or_else(|| "Auth state not initialized".to_string())?;
+    let (from, to) = {
+        let mut status = state.status.lock().unwrap_or_else(|e| e.into_inner());
+        let from = *status;
+        let to = transition(from, event)
+            .ok_or_else(|| format!("Invalid auth transition: {:?} on {:?}", event, from))?;
+        *status = to;
+        (from, to)
+    };
+    log::info!("Auth state: {:?} -> {:?} ({:?})", from, to, event);
+
+    if let Err(e) = app.emit("auth:state-changed", AuthStateChanged { from, to, event }) {
+        log::warn!("Failed to emit auth:state-changed: {}", e);
+    }
+    if shows_main_window(from) != shows_main_window(to) {
+        sync_windows(app)?;
+    }
+    Ok(to)
+}
+
+#[tauri::command]
+pub fn get_auth_state(app: AppHandle) -> AuthStatus {
+    status(&app)
+}
+
+/// 上报登录事件，返回转换后的状态；不允许的转换返回错误且状态不变
+#[tauri::command]
+pub fn update_auth_state(app: AppHandle, event: AuthEvent) -> Result<AuthStatus, String> {
+    apply(&app, event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use AuthEvent as E;
+    use AuthStatus as S;
+
+    const STATUSES: [AuthStatus; 4] = [
+        S::LoggedOut,
+        S::LoggingIn,
+        S::Authenticated,
+        S::SessionExpired,
+    ];
+    const EVENTS: [AuthEvent; 5] = [
+        E::LoginStarted,
+        E::LoginSucceeded,
+        E::LoginFailed,
+        E::Logout,
+        E::SessionExpired,
+    ];
+
+    /// 新增状态或事件时编译失败，提醒同步更新上面的列表和下面的表
+    #[allow(dead_code)]
+    fn covers_every_variant(status: AuthStatus, event: AuthEvent) {
+        match status {
+            S::LoggedOut | S::LoggingIn | S::Authenticated | S::SessionExpired => {}
+        }
+        match event {
+            E::LoginStarted
+            | E::LoginSucceeded
+            | E::LoginFailed
+            | E::Logout
+            | E::SessionExpired => {}
+        }
+    }
+
+    /// 完整的转换表：每个 (状态, 事件) 组合的期望结果，None 表示拒绝
+    fn expected(from: AuthStatus, event: AuthEvent) -> Option<AuthStatus> {
+        let table = [
+            (S::LoggedOut, E::LoginStarted, Some(S::LoggingIn)),
+            (S::LoggedOut, E::LoginSucceeded, None),
+            (S::LoggedOut, E::LoginFailed, None),
+            (S::LoggedOut, E::Logout, None),
+            (S::LoggedOut, E::SessionExpired, None),
+            (S::LoggingIn, E::LoginStarted, None),
+            (S::LoggingIn, E::LoginSucceeded, Some(S::Authenticated)),
+            (S::LoggingIn, E::LoginFailed, Some(S::LoggedOut)),
+            (S::LoggingIn, E::Logout, Some(S::LoggedOut)),
+            (S::LoggingIn, E::SessionExpired, None),
+            (S::Authenticated, E::LoginStarted, None),
+            (S::Authenticated, E::LoginSucceeded, None),
+            (S::Authenticated, E::LoginFailed, None),
+            (S::Authenticated, E::Logout, Some(S::LoggedOut)),
+            (S::Authenticated, E::SessionExpired, Some(S::SessionExpired)),
+            (S::SessionExpired, E::LoginStarted, Some(S::LoggingIn)),
+            (S::SessionExpired, E::LoginSucceeded, None),
+            (S::SessionExpired, E::LoginFailed, None),
+            (S::SessionExpired, E::Logout, Some(S::LoggedOut)),
+            (S::SessionExpired, E::SessionExpired, None),
+        ];
+        let matching: Vec<_> = table
+            .iter()
+            .filter(|(status, e, _)| *status == from && *e == event)
+            .collect();
+        assert_eq!(
+            matching.len(),
+            1,
+            "table must list {:?} + {:?} exactly once",
+            from,
+            event
+        );
+        matching[0].2
+    }
+
+    #[test]
+    fn every_transition_matches_the_table() {
+        for from in STATUSES {
+            for event in EVENTS {
+                assert_eq!(
+                    transition(from, event),
+                    expected(from, event),
+                    "{:?} + {:?}",
+                    from,
+                    event
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn only_authenticated_shows_the_main_window() {
+        for status in STATUSES {
+            assert_eq!(
+                shows_main_window(status),
+                status == S::Authenticated,
+                "{:?}",
+                status
+            );
+        }
+    }
+
+    #[test]
+    fn saved_token_starts_authenticated() {
+        assert_eq!(AppState::new(true).status(), S::Authenticated);
+        assert_eq!(AppState::new(false).status(), S::LoggedOut);
+    }
+
+    #[test]
+    fn main_window_is_refused_unless_authenticated() {
+        // open_window / toggle_window 打开主窗口时经过同一检查
+        let app = tauri::test::mock_app();
+        assert!(require_authenticated(app.handle()).is_err());
+        app.manage(AppState::new(false));
+        assert_eq!(
+            require_authenticated(app.handle()),
+            Err("Not logged in".to_string())
+        );
+
+        let app = tauri::test::mock_app();
+        app.manage(AppState::new(true));
+        assert_eq!(require_authenticated(app.handle()), Ok(()));
+    }
+}
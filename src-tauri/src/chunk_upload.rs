@@ -0,0 +1,488 @@
+//! 大文件分片上传：init 创建会话，逐片上传（每片带 SHA-256 校验），complete 合并。
+//! 已被服务端确认的分片记录在 ChunkSession 中，由上传队列保存到磁盘，
+//! 崩溃、休眠或断网后从下一片继续；重试用尽的网络错误返回 Interrupted，任务可以继续
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri_plugin_http::reqwest;
+
+use crate::config::{FloatBallUploadConfig, ServerProfile};
+use crate::upload::{self, UploadError};
+
+/// 第一次重试前的等待时间，之后每次翻倍
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// 一次分片上传的会话
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkSession {
+    /// init 返回的会话 id
+    pub upload_id: String,
+    pub chunk_size: u64,
+    pub total_chunks: u64,
+    /// 下一个要发送的分片序号，之前的分片都已被服务端确认
+    pub next_chunk: u64,
+    /// 开始上传时文件的修改时间（Unix 毫秒），文件变化后重新开始
+    pub modified: i64,
+}
+
+impl ChunkSession {
+    /// 已被服务端确认的字节数
+    pub fn confirmed(&self, size: u64) -> u64 {
+        (self.next_chunk * self.chunk_size).min(size)
+    }
+}
+
+/// 超过 chunk.threshold 的文件分片上传
+pub fn is_chunked(config: &FloatBallUploadConfig, size: u64) -> bool {
+    config.chunk.threshold > 0 && size > config.chunk.threshold
+}
+
+/// 分片上传的文件按 chunk.max_file_size 检查，其他文件按 max_file_size 检查
+pub fn check_size(config: &FloatBallUploadConfig, name: &str, size: u64) -> Result<(), String> {
+    if !is_chunked(config, size) {
+        return upload::check_size(config, name, size);
+    }
+    if size > config.chunk.max_file_size {
+        return Err(format!(
+            "{} 超过分片上传大小限制（{:.1} GB）",
+            name,
+            config.chunk.max_file_size as f64 / 1024.0 / 1024.0 / 1024.0
+        ));
+    }
+    Ok(())
+}
+
+/// 读取一个分片并计算 SHA-256（十六进制）
+fn read_chunk(path: &Path, offset: u64, len: u64) -> std::io::Result<(Vec<u8>, String)> {
+    let mut file = std::fs::File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut bytes = vec![0; len as usize];
+    file.read_exact(&mut bytes)?;
+    let checksum = format!("{:x}", Sha256::digest(&bytes));
+    Ok((bytes, checksum))
+}
+
+enum ChunkError {
+    /// 服务端不再认识该会话（过期或已清理），需要重新 init
+    SessionLost(String),
+    Upload(UploadError),
+}
+
+/// 一个文件的分片上传请求
+pub struct ChunkUpload<'a> {
+    pub client: &'a reqwest::Client,
+    pub server: &'a ServerProfile,
+    pub config: &'a FloatBallUploadConfig,
+    pub token: Option<&'a str>,
+    pub path: &'a PathBuf,
+    pub name: &'a str,
+    pub size: u64,
+    /// 随 init 提交的上传参数
+    pub fields: Vec<(&'static str, String)>,
+}
+
+impl ChunkUpload<'_> {
+    fn url(&self, api_url: &str) -> String {
+        upload::upload_url(self.server, api_url)
+    }
+
+    fn post(&self, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.post(url);
+        match self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn post_json(&self, api_url: &str, body: Value) -> Result<reqwest::Response, UploadError> {
+        self.post(&self.url(api_url))
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| UploadError::network(self.name, e))
+    }
+
+    /// 创建会话
+    async fn init(&self) -> Result<ChunkSession, UploadError> {
+        let chunk_size = self.config.chunk.chunk_size;
+        let total_chunks = self.size.div_ceil(chunk_size);
+        let mut body = Map::new();
+        body.insert("file_name".to_string(), json!(self.name));
+        body.insert("file_size".to_string(), json!(self.size));
+        body.insert("mime_type".to_string(), json!(upload::mime_type(self.name)));
+        body.insert("chunk_size".to_string(), json!(chunk_size));
+        body.insert("total_chunks".to_string(), json!(total_chunks));
+        for (key, value) in &self.fields {
+            body.insert(key.to_string(), json!(value));
+        }
+
+        let response = self.post_json(&self.config.chunk.init_url, Value::Object(body)).await?;
+        let data = upload::read_data(response, self.name).await?;
+        let upload_id = data
+            .get("upload_id")
+            .and_then(Value::as_str)
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| UploadError::Rejected(format!("{}: 响应中没有 upload_id", self.name)))?;
+        Ok(ChunkSession {
+            upload_id: upload_id.to_string(),
+            chunk_size,
+            total_chunks,
+            next_chunk: 0,
+            modified: upload::modified_ms(self.path),
+        })
+    }
+
+    async fn send_chunk(
+        &self,
+        session: &ChunkSession,
+        index: u64,
+        bytes: &[u8],
+        checksum: &str,
+    ) -> Result<(), ChunkError> {
+        let boundary = upload::multipart_boundary();
+        let fields = [
+            ("upload_id", session.upload_id.clone()),
+            ("chunk_index", index.to_string()),
+            ("chunk_size", bytes.len().to_string()),
+            ("checksum", checksum.to_string()),
+        ];
+        let mut body = upload::multipart_head(&boundary, self.name, &fields);
+        body.extend_from_slice(bytes);
+        body.extend_from_slice(&upload::multipart_tail(&boundary));
+
+        let response = self
+            .post(&self.url(&self.config.chunk.chunk_url))
+            .header(
+                "Content-Type",
+                format!("multipart/form-data; boundary={}", boundary),
+            )
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| ChunkError::Upload(UploadError::network(self.name, e)))?;
+        let status = response.status().as_u16();
+        let result = upload::read_data(response, self.name).await.map(|_| ());
+        match result {
+            Err(e) if status == 404 || status == 410 => Err(ChunkError::SessionLost(e.into_message())),
+            other => other.map_err(ChunkError::Upload),
+        }
+    }
+
+    async fn complete(&self, session: &ChunkSession) -> Result<String, UploadError> {
+        let body = json!({
+            "upload_id": session.upload_id,
+            "file_name": self.name,
+            "total_chunks": session.total_chunks,
+        });
+        let response = self.post_json(&self.config.chunk.complete_url, body).await?;
+        upload::read_url(response, self.name).await
+    }
+
+    /// 上传文件，返回文件地址。saved 为之前保存的会话，文件未变化时从它的下一片继续；
+    /// 每确认一片调用一次 on_progress，会话失效时以 None 调用并返回 Interrupted（继续时重新开始）
+    pub async fn run(
+        &self,
+        saved: Option<ChunkSession>,
+        mut on_progress: impl FnMut(Option<&ChunkSession>),
+    ) -> Result<String, UploadError> {
+        let modified = upload::modified_ms(self.path);
+        let resumable = saved.filter(|session| {
+            session.modified == modified
+                && session.total_chunks == self.size.div_ceil(session.chunk_size)
+        });
+        let mut session = match resumable {
+            Some(session) => {
+                log::info!(
+                    "Resuming {} from chunk {}/{}",
+                    self.name,
+                    session.next_chunk,
+                    session.total_chunks
+                );
+                session
+            }
+            None => self.init().await?,
+        };
+        on_progress(Some(&session));
+
+        let retries = self.config.chunk.retries;
+        while session.next_chunk < session.total_chunks {
+            let index = session.next_chunk;
+            let offset = index * session.chunk_size;
+            let len = session.chunk_size.min(self.size - offset);
+            let path = self.path.clone();
+            let (bytes, checksum) =
+                tauri::async_runtime::spawn_blocking(move || read_chunk(&path, offset, len))
+                    .await
+                    .map_err(|e| UploadError::Rejected(e.to_string()))?
+                    .map_err(|e| UploadError::Rejected(format!("{}: {}", self.name, e)))?;
+
+            let mut attempt = 0;
+            loop {
+                match self.send_chunk(&session, index, &bytes, &checksum).await {
+                    Ok(()) => break,
+                    Err(ChunkError::SessionLost(e)) => {
+                        on_progress(None);
+                        return Err(UploadError::Interrupted(e));
+                    }
+                    Err(ChunkError::Upload(e)) if attempt < retries => {
+                        let delay = RETRY_DELAY * 2u32.pow(attempt);
+                        log::warn!("Chunk {} failed, retrying in {:?}: {}", index, delay, e.message());
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    }
+                    Err(ChunkError::Upload(e)) => return Err(e),
+                }
+            }
+            session.next_chunk += 1;
+            on_progress(Some(&session));
+        }
+
+        self.complete(&session).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfigData;
+    use crate::test_server::{Response, TestServer};
+    use std::sync::{Arc, Mutex};
+
+    /// 按 4 字节分片为 3 片
+    const CONTENT: &[u8] = b"0123456789";
+    const CHUNK_URL: &str = "/api/upload/chunk";
+
+    /// 注入到分片服务中的故障，测试过程中可以修改
+    #[derive(Default)]
+    struct Faults {
+        /// init 返回的状态码
+        init_status: Option<u16>,
+        /// 不响应这一片，直接断开连接
+        drop_chunk: Option<u64>,
+        /// 接下来按校验失败拒绝的分片请求数
+        reject_chunks: u32,
+        /// 分片接口返回的状态码（会话失效）
+        chunk_status: Option<u16>,
+    }
+
+    /// 按 init、分片、complete 的协议响应；分片的 checksum 与内容不符时返回 400
+    fn chunk_server(faults: Arc<Mutex<Faults>>) -> TestServer {
+        TestServer::start(move |request| {
+            let mut faults = faults.lock().unwrap();
+            if request.path.ends_with("/init") {
+                return Some(match faults.init_status {
+                    Some(status) => Response::error(status, "init failed"),
+                    None => Response::ok(json!({ "upload_id": "s1" })),
+                });
+            }
+            if request.path.ends_with("/complete") {
+                return Some(Response::ok(json!({ "url": "https://files.test/big.bin" })));
+            }
+            let index: u64 = request.field("chunk_index")?.parse().ok()?;
+            if faults.drop_chunk == Some(index) {
+                return None;
+            }
+            if let Some(status) = faults.chunk_status {
+                return Some(Response::error(status, "upload session not found"));
+            }
+            let checksum = format!("{:x}", Sha256::digest(request.file()?));
+            if request.field("checksum") != Some(checksum) || faults.reject_chunks > 0 {
+                faults.reject_chunks = faults.reject_chunks.saturating_sub(1);
+                return Some(Response::error(400, "checksum mismatch"));
+            }
+            Some(Response::ok(json!({})))
+        })
+    }
+
+    struct Fixture {
+        _dir: tempfile::TempDir,
+        path: PathBuf,
+        server: ServerProfile,
+        config: FloatBallUploadConfig,
+        client: reqwest::Client,
+    }
+
+    impl Fixture {
+        fn new(server: &TestServer) -> Self {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("big.bin");
+            std::fs::write(&path, CONTENT).unwrap();
+            let defaults = AppConfigData::default();
+            let mut profile = defaults.servers.profiles[0].clone();
+            profile.base_url = server.url.clone();
+            let mut config = defaults.float_ball.upload;
+            config.chunk.threshold = 1;
+            config.chunk.chunk_size = 4;
+            config.chunk.retries = 0;
+            Self {
+                _dir: dir,
+                path,
+                server: profile,
+                config,
+                client: reqwest::Client::new(),
+            }
+        }
+
+        /// 运行一次上传，返回结果和每次 on_progress 收到的会话
+        fn run(&self, saved: Option<ChunkSession>) -> (Result<String, UploadError>, Vec<Option<ChunkSession>>) {
+            let upload = ChunkUpload {
+                client: &self.client,
+                server: &self.server,
+                config: &self.config,
+                token: None,
+                path: &self.path,
+                name: "big.bin",
+                size: CONTENT.len() as u64,
+                fields: vec![("folder_id", "7".to_string())],
+            };
+            let mut progress = Vec::new();
+            let result = tauri::async_runtime::block_on(
+                upload.run(saved, |session| progress.push(session.cloned())),
+            );
+            (result, progress)
+        }
+
+        fn session(&self, next_chunk: u64) -> ChunkSession {
+            ChunkSession {
+                upload_id: "s1".to_string(),
+                chunk_size: 4,
+                total_chunks: 3,
+                next_chunk,
+                modified: upload::modified_ms(&self.path),
+            }
+        }
+    }
+
+    fn chunk_indexes(server: &TestServer) -> Vec<u64> {
+        server
+            .requests_to(CHUNK_URL)
+            .iter()
+            .filter_map(|request| request.field("chunk_index")?.parse().ok())
+            .collect()
+    }
+
+    #[test]
+    fn every_chunk_is_sent_with_its_checksum_then_completed() {
+        let server = chunk_server(Arc::default());
+        let fixture = Fixture::new(&server);
+        let (result, progress) = fixture.run(None);
+        assert_eq!(result, Ok("https://files.test/big.bin".to_string()));
+
+        let init = server.requests_to("/init")[0].json();
+        assert_eq!(init["file_size"], 10);
+        assert_eq!(init["total_chunks"], 3);
+        assert_eq!(init["folder_id"], "7");
+        let chunks: Vec<Vec<u8>> = server
+            .requests_to(CHUNK_URL)
+            .iter()
+            .map(|request| request.file().unwrap().to_vec())
+            .collect();
+        assert_eq!(chunks, [b"0123".to_vec(), b"4567".to_vec(), b"89".to_vec()]);
+        let complete = server.requests_to("/complete")[0].json();
+        assert_eq!(complete["upload_id"], "s1");
+        assert_eq!(complete["total_chunks"], 3);
+
+        let confirmed: Vec<u64> = progress.iter().map(|session| session.as_ref().unwrap().next_chunk).collect();
+        assert_eq!(confirmed, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn saved_session_resumes_from_next_chunk() {
+        let server = chunk_server(Arc::default());
+        let fixture = Fixture::new(&server);
+        let (result, _) = fixture.run(Some(fixture.session(2)));
+        assert!(result.is_ok());
+        assert!(server.requests_to("/init").is_empty());
+        assert_eq!(chunk_indexes(&server), [2]);
+        assert_eq!(server.requests_to(CHUNK_URL)[0].file(), Some(&b"89"[..]));
+    }
+
+    #[test]
+    fn saved_session_of_a_modified_file_starts_over() {
+        let server = chunk_server(Arc::default());
+        let fixture = Fixture::new(&server);
+        let mut saved = fixture.session(2);
+        saved.modified -= 1;
+        let (result, _) = fixture.run(Some(saved));
+        assert!(result.is_ok());
+        assert_eq!(server.requests_to("/init").len(), 1);
+        assert_eq!(chunk_indexes(&server), [0, 1, 2]);
+    }
+
+    #[test]
+    fn rejected_checksum_is_retried_then_reported_as_rejected() {
+        let faults = Arc::new(Mutex::new(Faults {
+            reject_chunks: 1,
+            ..Default::default()
+        }));
+        let server = chunk_server(faults.clone());
+        let mut fixture = Fixture::new(&server);
+        fixture.config.chunk.retries = 1;
+        let (result, _) = fixture.run(None);
+        assert!(result.is_ok());
+        assert_eq!(chunk_indexes(&server), [0, 0, 1, 2]);
+
+        faults.lock().unwrap().reject_chunks = u32::MAX;
+        fixture.config.chunk.retries = 0;
+        let (result, progress) = fixture.run(Some(fixture.session(1)));
+        match result {
+            Err(UploadError::Rejected(e)) => assert!(e.contains("checksum mismatch"), "{}", e),
+            other => panic!("expected a rejection, got {:?}", other),
+        }
+        // 已确认的分片保留
+        assert_eq!(progress.last(), Some(&Some(fixture.session(1))));
+    }
+
+    #[test]
+    fn lost_session_is_cleared_and_resumable() {
+        for status in [404, 410] {
+            let server = chunk_server(Arc::new(Mutex::new(Faults {
+                chunk_status: Some(status),
+                ..Default::default()
+            })));
+            let fixture = Fixture::new(&server);
+            let (result, progress) = fixture.run(Some(fixture.session(1)));
+            assert!(matches!(result, Err(UploadError::Interrupted(_))), "{}: {:?}", status, result);
+            assert_eq!(progress.last(), Some(&None), "{}", status);
+        }
+    }
+
+    #[test]
+    fn dropped_connection_keeps_confirmed_chunks() {
+        let faults = Arc::new(Mutex::new(Faults {
+            drop_chunk: Some(1),
+            ..Default::default()
+        }));
+        let server = chunk_server(faults.clone());
+        let fixture = Fixture::new(&server);
+        let (result, progress) = fixture.run(None);
+        assert!(matches!(result, Err(UploadError::Interrupted(_))), "{:?}", result);
+        let saved = progress.last().cloned().flatten().unwrap();
+        assert_eq!(saved.next_chunk, 1);
+
+        faults.lock().unwrap().drop_chunk = None;
+        let (result, _) = fixture.run(Some(saved));
+        assert!(result.is_ok());
+        assert_eq!(server.requests_to("/init").len(), 1);
+        assert_eq!(chunk_indexes(&server), [0, 1, 1, 2]);
+    }
+
+    #[test]
+    fn server_errors_are_interrupted_and_client_errors_rejected() {
+        for (status, interrupted) in [(503, true), (429, true), (403, false), (400, false)] {
+            let server = chunk_server(Arc::new(Mutex::new(Faults {
+                init_status: Some(status),
+                ..Default::default()
+            })));
+            let fixture = Fixture::new(&server);
+            let (result, _) = fixture.run(None);
+            assert_eq!(matches!(result, Err(UploadError::Interrupted(_))), interrupted, "{}", status);
+            assert!(result.is_err(), "{}", status);
+        }
+    }
+}
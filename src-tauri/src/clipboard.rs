@@ -0,0 +1,273 @@
+//! 系统剪贴板：通过各平台自带的命令行工具读写，不依赖窗口
+//! （macOS: pbcopy；Windows: PowerShell；Linux: wl-clipboard / xclip / xsel）
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 剪贴板中可上传的内容
+#[derive(Debug)]
+pub enum ClipboardContent {
+    /// 复制的文件
+    Files(Vec<PathBuf>),
+    /// 截图等图片数据（PNG）
+    Image(Vec<u8>),
+    Empty,
+}
+
+/// 按顺序尝试的写入命令（程序名 + 参数），第一个能启动的生效
+fn write_commands() -> Vec<(&'static str, Vec<&'static str>)> {
+    if cfg!(target_os = "macos") {
+        vec![("pbcopy", vec![])]
+    } else if cfg!(target_os = "windows") {
+        vec![(
+            "powershell",
+            vec![
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                "[Console]::InputEncoding = [Text.Encoding]::UTF8; Set-Clipboard -Value ([Console]::In.ReadToEnd())",
+            ],
+        )]
+    } else {
+        let mut commands = Vec::new();
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.push(("wl-copy", vec![]));
+        }
+        commands.push(("xclip", vec!["-selection", "clipboard"]));
+        commands.push(("xsel", vec!["--clipboard", "--input"]));
+        commands
+    }
+}
+
+/// 把文本写入剪贴板
+pub fn write_text(text: &str) -> Result<(), String> {
+    let mut last_error = String::from("no clipboard tool available");
+    for (program, args) in write_commands() {
+        let mut child = match Command::new(program)
+            .args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                last_error = format!("{}: {}", program, e);
+                continue;
+            }
+        };
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(text.as_bytes())
+                .map_err(|e| format!("Failed to write to {}: {}", program, e))?;
+        }
+        let status = child.wait().map_err(|e| format!("{}: {}", program, e))?;
+        if status.success() {
+            return Ok(());
+        }
+        last_error = format!("{} exited with {}", program, status);
+    }
+    Err(format!("Failed to write clipboard: {}", last_error))
+}
+
+/// 运行命令并返回 stdout；命令不存在或执行失败时返回 None
+fn output(program: &str, args: &[&str]) -> Option<Vec<u8>> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output.status.success().then_some(output.stdout)
+}
+
+/// 读取剪贴板：优先复制的文件，其次图片
+pub fn read() -> Result<ClipboardContent, String> {
+    if cfg!(target_os = "macos") {
+        read_macos()
+    } else if cfg!(target_os = "windows") {
+        read_windows()
+    } else {
+        read_linux()
+    }
+}
+
+fn read_linux() -> Result<ClipboardContent, String> {
+    // (列出类型的参数, 按类型读取的参数前缀)
+    let (program, list_args, read_args): (&str, Vec<&str>, Vec<&str>) =
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            ("wl-paste", vec!["--list-types"], vec!["--type"])
+        } else {
+            (
+                "xclip",
+                vec!["-selection", "clipboard", "-t", "TARGETS", "-o"],
+                vec!["-selection", "clipboard", "-o", "-t"],
+            )
+        };
+    let types = output(program, &list_args)
+        .ok_or_else(|| format!("{} is not available", program))?;
+    let types = String::from_utf8_lossy(&types);
+    let has = |t: &str| types.lines().any(|line| line.trim() == t);
+    let read_type = |t: &str| {
+        let mut args = read_args.clone();
+        args.push(t);
+        output(program, &args)
+    };
+
+    if has("text/uri-list") {
+        if let Some(list) = read_type("text/uri-list") {
+            let files = parse_uri_list(&String::from_utf8_lossy(&list));
+            if !files.is_empty() {
+                return Ok(ClipboardContent::Files(files));
+            }
+        }
+    }
+    if has("image/png") {
+        if let Some(png) = read_type("image/png").filter(|png| !png.is_empty()) {
+            return Ok(ClipboardContent::Image(png));
+        }
+    }
+    Ok(ClipboardContent::Empty)
+}
+
+fn read_macos() -> Result<ClipboardContent, String> {
+    if let Some(path) = output(
+        "osascript",
+        &["-e", "POSIX path of (the clipboard as «class furl»)"],
+    ) {
+        let path = String::from_utf8_lossy(&path).trim().to_string();
+        if !path.is_empty() {
+            return Ok(ClipboardContent::Files(vec![PathBuf::from(path)]));
+        }
+    }
+
+    let tmp = temp_png();
+    let script = format!(
+        "set png to (the clipboard as «class PNGf»)\n\
+         set f to open for access (POSIX file \"{}\") with write permission\n\
+         write png to f\n\
+         close access f",
+        tmp.display()
+    );
+    if output("osascript", &["-e", &script]).is_none() {
+        let _ = std::fs::remove_file(&tmp);
+        return Ok(ClipboardContent::Empty);
+    }
+    read_temp_png(tmp)
+}
+
+fn read_windows() -> Result<ClipboardContent, String> {
+    let tmp = temp_png();
+    let script = format!(
+        "[Console]::OutputEncoding = [Text.Encoding]::UTF8; \
+         Add-Type -AssemblyName System.Windows.Forms, System.Drawing; \
+         $files = [Windows.Forms.Clipboard]::GetFileDropList(); \
+         if ($files.Count -gt 0) {{ $files | ForEach-Object {{ 'FILE:' + $_ }} }} \
+         elseif ([Windows.Forms.Clipboard]::ContainsImage()) {{ \
+           [Windows.Forms.Clipboard]::GetImage().Save('{}', [Drawing.Imaging.ImageFormat]::Png); 'IMAGE' }}",
+        tmp.display().to_string().replace('\'', "''")
+    );
+    let stdout = output(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-STA", "-Command", &script],
+    )
+    .ok_or("Failed to read clipboard with PowerShell")?;
+    let stdout = String::from_utf8_lossy(&stdout);
+
+    let files: Vec<PathBuf> = stdout
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("FILE:"))
+        .map(PathBuf::from)
+        .collect();
+    if !files.is_empty() {
+        return Ok(ClipboardContent::Files(files));
+    }
+    if stdout.lines().any(|line| line.trim() == "IMAGE") {
+        return read_temp_png(tmp);
+    }
+    Ok(ClipboardContent::Empty)
+}
+
+fn temp_png() -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    std::env::temp_dir().join(format!("clipboard-{}-{}.png", std::process::id(), nanos))
+}
+
+fn read_temp_png(path: PathBuf) -> Result<ClipboardContent, String> {
+    let png = std::fs::read(&path).map_err(|e| format!("Failed to read clipboard image: {}", e));
+    let _ = std::fs::remove_file(&path);
+    let png = png?;
+    Ok(if png.is_empty() {
+        ClipboardContent::Empty
+    } else {
+        ClipboardContent::Image(png)
+    })
+}
+
+/// 解析 text/uri-list，只保留本地文件
+fn parse_uri_list(list: &str) -> Vec<PathBuf> {
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|uri| uri.strip_prefix("file://"))
+        // file://host/path 形式只接受本机
+        .map(|rest| rest.strip_prefix("localhost").unwrap_or(rest))
+        .filter(|path| path.starts_with('/'))
+        .map(|path| PathBuf::from(percent_decode(path)))
+        .collect()
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_local_file_uris_are_accepted() {
+        let list = "# copied files\r\nfile:///home/a.png\r\nfile://localhost/tmp/b.txt\r\n\
+                    file://nas/share/c.png\r\nhttps://example.com/d.png\r\n\r\n";
+        assert_eq!(
+            parse_uri_list(list),
+            [PathBuf::from("/home/a.png"), PathBuf::from("/tmp/b.txt")]
+        );
+    }
+
+    #[test]
+    fn escapes_are_decoded_and_invalid_ones_kept() {
+        assert_eq!(
+            parse_uri_list("file:///home/my%20pics/%E5%9B%BE.png"),
+            [PathBuf::from("/home/my pics/图.png")]
+        );
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("a%zzb%2"), "a%zzb%2");
+        assert_eq!(percent_decode("%41%2f"), "A/");
+    }
+}
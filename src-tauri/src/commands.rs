@@ -1,87 +1,60 @@
-use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
 
-/// 显示并聚焦主窗口
+use crate::{auth_state, window};
+
+/// 显示并聚焦主窗口；未登录时改为显示登录窗口并返回错误
 #[tauri::command]
 pub fn show_main_window(app: AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        window.show().map_err(|e| e.to_string())?;
-        window.set_focus().map_err(|e| e.to_string())?;
-        window.unminimize().map_err(|e| e.to_string())?;
-
-        // 开发模式下打开 DevTools
-        #[cfg(debug_assertions)]
-        {
-            use crate::config::AppConfigData;
-            let config = AppConfigData::load();
-            if config.dev.open_dev_tools {
-                window.open_devtools();
-                log::info!("DevTools opened for main window");
-            }
-        }
-
-        // 如果登录窗口存在，则隐藏
-        if let Some(login) = app.get_webview_window("login") {
-            let _ = login.hide();
-        }
-        Ok(())
-    } else {
-        Err("Main window not found".to_string())
+    if let Err(e) = auth_state::require_authenticated(&app) {
+        log::info!("show_main_window blocked, auth state: {:?}", auth_state::status(&app));
+        window::show_login(&app)?;
+        return Err(e);
     }
+    window::show_main(&app)
 }
 
 /// 显示并聚焦登录窗口，同时隐藏主窗口（如存在）
 #[tauri::command]
 pub fn show_login_window(app: AppHandle) -> Result<(), String> {
-    if let Some(login) = app.get_webview_window("login") {
-        login.show().map_err(|e| e.to_string())?;
-        login.set_focus().map_err(|e| e.to_string())?;
-
-        if let Some(main) = app.get_webview_window("main") {
-            let _ = main.hide();
-        }
-        Ok(())
-    } else {
-        // 如果登录窗口不存在，则创建一个
-        let login_url = if cfg!(debug_assertions) {
-            "http://localhost:5173/#/login"
-        } else {
-            "index.html#/login"
-        };
+    window::show_login(&app)
+}
 
-        let login = WebviewWindowBuilder::new(&app, "login", WebviewUrl::App(login_url.into()))
-            .title("PixelPunk - 登录")
-            .inner_size(900.0, 600.0)
-            .resizable(false)
-            .maximizable(false)
-            .minimizable(true)
-            .decorations(false)
-            .center()
-            .build()
-            .map_err(|e| e.to_string())?;
+/// 显示并聚焦窗口，注册的窗口不存在时自动创建；params 作为查询参数传给新窗口，
+/// 已存在的窗口通过 window:params 事件收到。主窗口和登录窗口按登录状态切换，未登录时打开主窗口返回错误
+#[tauri::command]
+pub fn open_window(
+    app: AppHandle,
+    label: String,
+    params: Option<Map<String, Value>>,
+) -> Result<(), String> {
+    match label.as_str() {
+        window::MAIN_WINDOW | window::LOGIN_WINDOW => window::show_window(&app, &label),
+        _ => window::open_window(&app, &label, &params.unwrap_or_default()).map(|_| ()),
+    }
+}
 
-        login.show().map_err(|e| e.to_string())?;
-        login.set_focus().map_err(|e| e.to_string())?;
+#[tauri::command]
+pub fn close_window(app: AppHandle, label: String) -> Result<(), String> {
+    window::close_window(&app, &label)
+}
 
-        if let Some(main) = app.get_webview_window("main") {
-            let _ = main.hide();
-        }
-        Ok(())
-    }
+/// 切换窗口显示状态，返回切换后是否可见
+#[tauri::command]
+pub fn toggle_window(
+    app: AppHandle,
+    label: String,
+    params: Option<Map<String, Value>>,
+) -> Result<bool, String> {
+    window::toggle_window(&app, &label, &params.unwrap_or_default())
 }
 
 #[tauri::command]
 pub fn toggle_float_ball(app: AppHandle, show: bool) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("float-ball") {
-        if show {
-            window.show().map_err(|e| e.to_string())?;
-            window.set_focus().map_err(|e| e.to_string())?;
-        } else {
-            window.hide().map_err(|e| e.to_string())?;
-        }
-        Ok(())
-    } else if show {
-        // 如果窗口不存在且需要显示，则创建窗口
-        create_float_ball_window(app)
+    if show {
+        window::open_window(&app, window::FLOAT_BALL_WINDOW, &Map::new()).map(|_| ())
+    } else if let Some(window) = app.get_webview_window(window::FLOAT_BALL_WINDOW) {
+        window.hide().map_err(|e| e.to_string())
     } else {
         Ok(())
     }
@@ -89,70 +62,14 @@ pub fn toggle_float_ball(app: AppHandle, show: bool) -> Result<(), String> {
 
 #[tauri::command]
 pub fn close_float_ball(app: AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("float-ball") {
-        window.close().map_err(|e| e.to_string())?;
-    }
-    Ok(())
+    window::close_window(&app, window::FLOAT_BALL_WINDOW)
 }
 
 #[tauri::command]
 pub fn is_float_ball_visible(app: AppHandle) -> Result<bool, String> {
-    if let Some(window) = app.get_webview_window("float-ball") {
+    if let Some(window) = app.get_webview_window(window::FLOAT_BALL_WINDOW) {
         window.is_visible().map_err(|e| e.to_string())
     } else {
         Ok(false)
     }
 }
-
-fn create_float_ball_window(app: AppHandle) -> Result<(), String> {
-    use crate::config::AppConfigData;
-
-    let config = AppConfigData::load();
-    let float_config = &config.float_ball;
-
-    // 计算悬浮球位置（右下角）
-    let (x, y) = if float_config.default_x == 0.0 && float_config.default_y == 0.0 {
-        let monitor = app.primary_monitor().ok().flatten();
-        if let Some(monitor) = monitor {
-            let size = monitor.size();
-            let scale = monitor.scale_factor();
-            // 转换为逻辑坐标（物理坐标 / scale factor）
-            let screen_width = size.width as f64 / scale;
-            let screen_height = size.height as f64 / scale;
-            let x = screen_width - float_config.width - float_config.margin;
-            let y = screen_height - float_config.height - float_config.margin;
-            (x, y)
-        } else {
-            (100.0, 100.0)
-        }
-    } else {
-        (float_config.default_x, float_config.default_y)
-    };
-
-    let float_url = if cfg!(debug_assertions) {
-        "http://localhost:5173/#/float-ball"
-    } else {
-        "index.html#/float-ball"
-    };
-
-    let window = WebviewWindowBuilder::new(&app, "float-ball", WebviewUrl::App(float_url.into()))
-        .title("Float Ball")
-        .inner_size(float_config.width, float_config.height)
-        .position(x, y)
-        .resizable(false)
-        .maximizable(false)
-        .minimizable(false)
-        .decorations(false)
-        .always_on_top(float_config.always_on_top)
-        .skip_taskbar(true)
-        .build()
-        .map_err(|e| e.to_string())?;
-
-    // 设置窗口透明（需在创建后立刻设置）
-    crate::macos::set_window_transparent(&window);
-
-    window.show().map_err(|e| e.to_string())?;
-
-    log::info!("Float ball window created");
-    Ok(())
-}
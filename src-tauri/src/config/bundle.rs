@@ -0,0 +1,305 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+use super::persist::{self, ConfigChange, ConfigWriteError, FieldError};
+use super::{apply_user_layer, layers, migrate, AppConfigData, LayeredConfig};
+
+/// 导出文件的格式标识
+const BUNDLE_FORMAT: &str = "pixelpunk-config-bundle";
+/// 导出文件结构版本（与配置的 schema_version 相互独立）
+const BUNDLE_VERSION: u32 = 1;
+
+/// 配置导出文件：版本头 + 校验和 + 要写入用户层的配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigBundle {
+    pub format: String,
+    pub version: u32,
+    /// 导出时的应用版本
+    pub app_version: String,
+    /// 导出时间（Unix 秒）
+    pub exported_at: u64,
+    /// 服务端地址中的凭据是否保留
+    pub includes_secrets: bool,
+    /// config 规范化序列化后的 SHA-256（十六进制）
+    pub checksum: String,
+    pub config: Value,
+}
+
+/// preview_config_import 的返回值
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub app_version: String,
+    pub exported_at: u64,
+    pub includes_secrets: bool,
+    /// 导入后生效配置的变化
+    pub changes: Vec<ConfigChange>,
+    /// 不合法的字段；非空时 import_config 会拒绝导入
+    pub errors: Vec<FieldError>,
+}
+
+/// import_config 的返回值
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportResult {
+    pub config: LayeredConfig,
+    /// 导入前用户配置文件的备份路径（原先没有用户配置时为空）
+    pub backup: Option<String>,
+}
+
+fn bundle_error(message: impl Into<String>) -> ConfigWriteError {
+    ConfigWriteError::Bundle {
+        message: message.into(),
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 按键排序后序列化再求 SHA-256，保证与字段顺序无关
+fn checksum(config: &Value) -> String {
+    fn canonical(value: &Value) -> Value {
+        match value {
+            Value::Object(map) => {
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                Value::Object(
+                    entries
+                        .into_iter()
+                        .map(|(k, v)| (k.clone(), canonical(v)))
+                        .collect::<Map<_, _>>(),
+                )
+            }
+            Value::Array(items) => Value::Array(items.iter().map(canonical).collect()),
+            other => other.clone(),
+        }
+    }
+    let text = serde_json::to_string(&canonical(config)).unwrap_or_default();
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// 去掉 URL 中的 `user:password@`
+fn strip_credentials(url: &str) -> String {
+    let Some(scheme_end) = url.find("://").map(|i| i + 3) else {
+        return url.to_string();
+    };
+    let rest = &url[scheme_end..];
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    match rest[..authority_end].rfind('@') {
+        Some(at) => format!("{}{}", &url[..scheme_end], &rest[at + 1..]),
+        None => url.to_string(),
+    }
+}
+
+fn strip_secrets(config: &mut Value) {
+    let Some(profiles) = config
+        .pointer_mut("/servers/profiles")
+        .and_then(Value::as_array_mut)
+    else {
+        return;
+    };
+    for profile in profiles {
+        if let Some(Value::String(url)) = profile.get_mut("base_url") {
+            *url = strip_credentials(url);
+        }
+    }
+}
+
+/// 去掉被策略锁定的字段（这些值由本机策略决定，不属于用户配置），并清理因此变空的对象
+fn strip_locked(config: &mut Value, locked: &[String]) {
+    fn remove(value: &mut Value, path: &[&str]) {
+        let Value::Object(map) = value else {
+            return;
+        };
+        match path {
+            [] => {}
+            [leaf] => {
+                map.remove(*leaf);
+            }
+            [key, rest @ ..] => {
+                if let Some(child) = map.get_mut(*key) {
+                    remove(child, rest);
+                    if child.as_object().is_some_and(Map::is_empty) {
+                        map.remove(*key);
+                    }
+                }
+            }
+        }
+    }
+    for key in locked {
+        remove(config, &key.split('.').collect::<Vec<_>>());
+    }
+}
+
+/// 导出文件中的配置：只取用户层，去掉锁定字段和 "$schema"，并标上当前 schema_version
+fn export_value(user: Value, locked: &[String], include_secrets: bool) -> Value {
+    let mut config = match user {
+        Value::Object(map) => Value::Object(map),
+        _ => Value::Object(Map::new()),
+    };
+    strip_locked(&mut config, locked);
+    if let Value::Object(map) = &mut config {
+        map.remove("$schema");
+        map.insert(
+            "schema_version".to_string(),
+            Value::from(migrate::CURRENT_SCHEMA_VERSION),
+        );
+    }
+    if !include_secrets {
+        strip_secrets(&mut config);
+    }
+    config
+}
+
+/// 读取导出文件并校验格式、版本和校验和，config 升级到当前 schema_version
+fn read_bundle(path: &str) -> Result<ConfigBundle, ConfigWriteError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| bundle_error(format!("Failed to read {}: {}", path, e)))?;
+    let mut bundle: ConfigBundle = serde_json::from_str(&text)
+        .map_err(|e| bundle_error(format!("Invalid config bundle {}: {}", path, e)))?;
+
+    if bundle.format != BUNDLE_FORMAT {
+        return Err(bundle_error(format!("Not a config bundle: format \"{}\"", bundle.format)));
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(bundle_error(format!(
+            "Config bundle version {} is newer than supported {}",
+            bundle.version, BUNDLE_VERSION
+        )));
+    }
+    if checksum(&bundle.config) != bundle.checksum {
+        return Err(bundle_error("Config bundle checksum mismatch, file is corrupted or was edited"));
+    }
+    migrate::migrate(&mut bundle.config).map_err(bundle_error)?;
+    Ok(bundle)
+}
+
+/// 只导出用户配置层；默认值、本机覆盖、环境变量和策略锁定的字段都不导出，
+/// 导入到另一台机器后仍由那台机器自己的各层决定
+#[tauri::command]
+pub fn export_config(path: String, include_secrets: bool) -> Result<ConfigBundle, ConfigWriteError> {
+    let current = layers::current();
+    let config = export_value(layers::user_layer(), &current.locked, include_secrets);
+
+    let bundle = ConfigBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        app_version: current.config.version,
+        exported_at: now_secs(),
+        includes_secrets: include_secrets,
+        checksum: checksum(&config),
+        config,
+    };
+    let value = serde_json::to_value(&bundle).map_err(|e| ConfigWriteError::io(e.to_string()))?;
+    persist::write_atomic(Path::new(&path), &value)?;
+    log::info!("Config exported to {}", path);
+    Ok(bundle)
+}
+
+/// 预览导入后的变化，不写入任何文件
+#[tauri::command]
+pub fn preview_config_import(path: String) -> Result<ImportPreview, ConfigWriteError> {
+    let bundle = read_bundle(&path)?;
+    let errors = persist::validate_user_layer(&bundle.config)
+        .err()
+        .unwrap_or_default();
+    let preview = layers::load_with_user_layer(bundle.config);
+    Ok(ImportPreview {
+        app_version: bundle.app_version,
+        exported_at: bundle.exported_at,
+        includes_secrets: bundle.includes_secrets,
+        changes: persist::diff(&AppConfigData::load(), &preview.config),
+        errors,
+    })
+}
+
+/// 备份当前用户配置后，用导出文件中的配置替换用户层
+#[tauri::command]
+pub fn import_config(app: AppHandle, path: String) -> Result<ImportResult, ConfigWriteError> {
+    let bundle = read_bundle(&path)?;
+    let paths =
+        layers::config_paths().ok_or_else(|| ConfigWriteError::io("config paths not initialized"))?;
+
+    let backup = if paths.user_file.exists() {
+        let name = format!("{}.import-{}.bak", layers::USER_CONFIG_FILE, now_secs());
+        let backup = paths.user_file.with_file_name(name);
+        std::fs::copy(&paths.user_file, &backup).map_err(|e| {
+            ConfigWriteError::io(format!("Failed to back up {}: {}", paths.user_file.display(), e))
+        })?;
+        log::info!("Config backed up to {}", backup.display());
+        Some(backup.display().to_string())
+    } else {
+        None
+    };
+
+    let config = apply_user_layer(&app, bundle.config)?;
+    log::info!("Config imported from {}", path);
+    Ok(ImportResult { config, backup })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn export_contains_only_the_user_layer_without_locked_keys() {
+        let user = json!({
+            "$schema": "./schemas/app.config.schema.json",
+            "schema_version": 3,
+            "app": { "quit_on_close": true },
+            "float_ball": { "upload": { "max_file_size": 1024, "concurrent": 5 } },
+            "updater": { "enabled": false },
+        });
+        let locked = vec![
+            "float_ball.upload.max_file_size".to_string(),
+            "updater.enabled".to_string(),
+            "servers.active".to_string(),
+        ];
+        assert_eq!(
+            export_value(user, &locked, true),
+            json!({
+                "schema_version": migrate::CURRENT_SCHEMA_VERSION,
+                "app": { "quit_on_close": true },
+                "float_ball": { "upload": { "concurrent": 5 } },
+            })
+        );
+    }
+
+    #[test]
+    fn export_of_an_empty_user_layer_only_has_the_version() {
+        assert_eq!(
+            export_value(Value::Null, &[], false),
+            json!({ "schema_version": migrate::CURRENT_SCHEMA_VERSION })
+        );
+    }
+
+    #[test]
+    fn credentials_are_stripped_unless_requested() {
+        let user = json!({
+            "servers": { "profiles": [{ "name": "a", "base_url": "https://me:pw@img.example.com/api" }] },
+        });
+        let url = |config: &Value| config["servers"]["profiles"][0]["base_url"].clone();
+        assert_eq!(
+            url(&export_value(user.clone(), &[], false)),
+            json!("https://img.example.com/api")
+        );
+        assert_eq!(
+            url(&export_value(user, &[], true)),
+            json!("https://me:pw@img.example.com/api")
+        );
+    }
+
+    #[test]
+    fn checksum_ignores_key_order() {
+        let a = json!({ "app": { "quit_on_close": true, "single_instance": false }, "schema_version": 4 });
+        let b = json!({ "schema_version": 4, "app": { "single_instance": false, "quit_on_close": true } });
+        assert_eq!(checksum(&a), checksum(&b));
+    }
+}
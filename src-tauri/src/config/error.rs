@@ -0,0 +1,244 @@
+use serde::Serialize;
+use std::fmt;
+
+use super::layers::ConfigLayer;
+
+/// 配置错误类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigErrorKind {
+    /// 文件无法读取
+    Io,
+    /// JSON 语法错误
+    Syntax,
+    /// 字段类型或取值不符合 AppConfigData
+    Data,
+    /// schema_version 无法升级
+    Version,
+}
+
+/// 加载配置时遇到的错误，尽量定位到文件、行列和 JSON 路径
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigError {
+    pub kind: ConfigErrorKind,
+    pub layer: ConfigLayer,
+    pub file: Option<String>,
+    /// 出错字段的 JSON 路径，如 `float_ball.upload.concurrent`
+    pub path: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}", file)?,
+            None => write!(f, "<{:?}>", self.layer)?,
+        }
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            write!(f, ":{}:{}", line, column)?;
+        }
+        if let Some(path) = &self.path {
+            write!(f, " ({})", path)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// 在 JSON 原文中查找 path 对应值的起始位置（1 起始的行、列）；
+/// path 格式与 serde_path_to_error 一致：`a.b[0].c`
+pub fn locate(text: &str, path: &str) -> Option<(usize, usize)> {
+    let target = parse_path(path);
+    let mut scanner = Scanner {
+        bytes: text.as_bytes(),
+        pos: 0,
+    };
+    let offset = scanner.find(&target, &mut Vec::new())?;
+    Some(line_column(text, offset))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+fn parse_path(path: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    for part in path.split('.').filter(|p| !p.is_empty()) {
+        let (key, rest) = match part.find('[') {
+            Some(i) => (&part[..i], &part[i..]),
+            None => (part, ""),
+        };
+        if !key.is_empty() {
+            segments.push(Segment::Key(key.to_string()));
+        }
+        for index in rest.split(['[', ']']).filter(|s| !s.is_empty()) {
+            if let Ok(index) = index.parse() {
+                segments.push(Segment::Index(index));
+            }
+        }
+    }
+    segments
+}
+
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map(|l| l.chars().count()).unwrap_or(0) + 1;
+    (line, column)
+}
+
+/// 只为定位而写的最小 JSON 扫描器，遇到语法错误直接放弃
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    /// 扫描一个值；若当前路径等于 target，返回值的起始偏移
+    fn find(&mut self, target: &[Segment], current: &mut Vec<Segment>) -> Option<usize> {
+        self.skip_ws();
+        let start = self.pos;
+        if current.as_slice() == target {
+            return Some(start);
+        }
+        // 当前路径不是目标的前缀时无需深入
+        let descend = target.starts_with(current);
+
+        match self.peek()? {
+            b'{' => {
+                self.pos += 1;
+                loop {
+                    self.skip_ws();
+                    match self.peek()? {
+                        b'}' => {
+                            self.pos += 1;
+                            return None;
+                        }
+                        b',' => {
+                            self.pos += 1;
+                            continue;
+                        }
+                        b'"' => {}
+                        _ => return None,
+                    }
+                    let key = self.string()?;
+                    self.skip_ws();
+                    if self.peek()? != b':' {
+                        return None;
+                    }
+                    self.pos += 1;
+                    if descend {
+                        current.push(Segment::Key(key));
+                        let found = self.find(target, current);
+                        current.pop();
+                        if found.is_some() {
+                            return found;
+                        }
+                    } else {
+                        self.skip_value()?;
+                    }
+                }
+            }
+            b'[' => {
+                self.pos += 1;
+                let mut index = 0;
+                loop {
+                    self.skip_ws();
+                    match self.peek()? {
+                        b']' => {
+                            self.pos += 1;
+                            return None;
+                        }
+                        b',' => {
+                            self.pos += 1;
+                            index += 1;
+                            continue;
+                        }
+                        _ => {}
+                    }
+                    if descend {
+                        current.push(Segment::Index(index));
+                        let found = self.find(target, current);
+                        current.pop();
+                        if found.is_some() {
+                            return found;
+                        }
+                    } else {
+                        self.skip_value()?;
+                    }
+                }
+            }
+            _ => {
+                self.skip_value()?;
+                None
+            }
+        }
+    }
+
+    fn skip_value(&mut self) -> Option<()> {
+        self.skip_ws();
+        match self.peek()? {
+            b'"' => self.string().map(|_| ()),
+            b'{' | b'[' => {
+                // 跳过嵌套结构时只需匹配括号，字符串内的括号单独处理
+                let mut depth = 0usize;
+                loop {
+                    match self.peek()? {
+                        b'"' => {
+                            self.string()?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                self.pos += 1;
+                                return Some(());
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.pos += 1;
+                }
+            }
+            _ => {
+                while let Some(b) = self.peek() {
+                    if matches!(b, b',' | b'}' | b']') || b.is_ascii_whitespace() {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                Some(())
+            }
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let start = self.pos + 1;
+        self.pos += 1;
+        loop {
+            match self.peek()? {
+                b'\\' => self.pos += 2,
+                b'"' => {
+                    let raw = std::str::from_utf8(&self.bytes[start..self.pos]).ok()?;
+                    self.pos += 1;
+                    return serde_json::from_str(&format!("\"{}\"", raw)).ok();
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+}
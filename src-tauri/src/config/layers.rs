@@ -0,0 +1,623 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+use super::error::{locate, ConfigError, ConfigErrorKind};
+use super::validate::{ConfigIssue, Severity};
+use super::{migrate, AppConfigData};
+
+/// 环境变量前缀，层级之间用 `__` 分隔，例如
+/// `PIXELPUNK_CONFIG__FLOAT_BALL__UPLOAD__CONCURRENT=5`
+pub const ENV_PREFIX: &str = "PIXELPUNK_CONFIG__";
+
+/// 用户配置文件名（位于平台配置目录）
+pub const USER_CONFIG_FILE: &str = "app.config.json";
+
+/// 管理员策略文件名（位于本机配置目录），其中的字段强制生效且不可修改
+pub const POLICY_FILE: &str = "policy.json";
+
+/// 配置来源，按优先级从低到高排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigLayer {
+    /// 编译时内置的默认值（AppConfigData::default()，app.config.json 由它生成）
+    Default,
+    /// 用户配置目录下的 app.config.json
+    User,
+    /// 本机覆盖文件（管理员下发）
+    Machine,
+    /// PIXELPUNK_CONFIG__* 环境变量
+    Env,
+    /// 本机策略文件（管理员下发，锁定其中的字段）
+    Policy,
+}
+
+/// 各层配置文件所在路径
+#[derive(Debug, Clone)]
+pub struct ConfigPaths {
+    pub user_file: PathBuf,
+    pub machine_file: PathBuf,
+    pub policy_file: PathBuf,
+}
+
+static CONFIG_PATHS: OnceLock<ConfigPaths> = OnceLock::new();
+
+/// 在 setup 中调用一次，记录用户配置目录
+pub fn init_paths(user_config_dir: &Path) {
+    let paths = ConfigPaths {
+        user_file: user_config_dir.join(USER_CONFIG_FILE),
+        machine_file: machine_config_dir().join(USER_CONFIG_FILE),
+        policy_file: machine_config_dir().join(POLICY_FILE),
+    };
+    log::info!(
+        "Config paths: user={}, machine={}, policy={}",
+        paths.user_file.display(),
+        paths.machine_file.display(),
+        paths.policy_file.display()
+    );
+    let _ = CONFIG_PATHS.set(paths);
+    // 丢弃路径确定前可能缓存的默认配置
+    *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+pub fn config_paths() -> Option<&'static ConfigPaths> {
+    CONFIG_PATHS.get()
+}
+
+/// 本机级配置目录（所有用户共享，通常只有管理员可写）
+pub fn machine_config_dir() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        let base = std::env::var_os("ProgramData").unwrap_or_else(|| "C:\\ProgramData".into());
+        PathBuf::from(base).join("PixelPunk")
+    }
+    #[cfg(target_os = "macos")]
+    {
+        PathBuf::from("/Library/Application Support/PixelPunk")
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        PathBuf::from("/etc/pixelpunk")
+    }
+}
+
+/// 分层合并后的配置，以及每个叶子字段的来源
+#[derive(Debug, Clone, Serialize)]
+pub struct LayeredConfig {
+    pub config: AppConfigData,
+    pub sources: BTreeMap<String, ConfigLayer>,
+    /// 被策略文件锁定的字段路径，写入时不允许修改
+    pub locked: Vec<String>,
+    /// 加载过程中遇到的错误；出错的配置段已回退为默认值
+    pub diagnostics: Vec<ConfigError>,
+    /// 语义校验发现的问题；对应的值已被钳制或回退
+    pub issues: Vec<ConfigIssue>,
+}
+
+/// 当前生效的配置，首次访问时加载，热重载时整体替换
+static CURRENT: RwLock<Option<LayeredConfig>> = RwLock::new(None);
+
+/// 返回当前生效的配置
+pub fn current() -> LayeredConfig {
+    if let Some(config) = CURRENT.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return config.clone();
+    }
+    let loaded = load_layered();
+    *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = Some(loaded.clone());
+    loaded
+}
+
+/// 替换当前配置，返回旧值
+pub fn replace_current(new: LayeredConfig) -> LayeredConfig {
+    let mut guard = CURRENT.write().unwrap_or_else(|e| e.into_inner());
+    let old = guard.take();
+    *guard = Some(new);
+    drop(guard);
+    old.unwrap_or_else(load_layered)
+}
+
+/// 依次合并默认值、用户文件、本机覆盖文件、环境变量和策略文件；
+/// 损坏的文件整层跳过，类型不符的配置段单独回退为默认值，错误记录在 diagnostics 中
+pub fn load_layered() -> LayeredConfig {
+    let loaded = merge_layers(config_paths(), None);
+    for error in &loaded.diagnostics {
+        log::error!("Config error: {}", error);
+    }
+    for issue in &loaded.issues {
+        match issue.severity {
+            Severity::Warning => log::warn!("Config warning: {}: {}", issue.path, issue.message),
+            Severity::Error => log::error!("Config invalid: {}: {}", issue.path, issue.message),
+        }
+    }
+    loaded
+}
+
+/// 与 load_layered 相同，但有任何错误都返回 Err（用于热重载，保留旧配置）
+pub fn try_load_layered() -> Result<LayeredConfig, Vec<ConfigError>> {
+    let loaded = merge_layers(config_paths(), None);
+    if loaded.diagnostics.is_empty() {
+        Ok(loaded)
+    } else {
+        Err(loaded.diagnostics)
+    }
+}
+
+/// 已读取的配置层：解析后的 JSON 及原文（用于定位行列）
+struct LayerFile {
+    layer: ConfigLayer,
+    file: String,
+    text: String,
+}
+
+/// 假设用户层替换为 user 时的合并结果（不读写用户文件），用于导入预览
+pub(super) fn load_with_user_layer(user: Value) -> LayeredConfig {
+    merge_layers(config_paths(), Some(user))
+}
+
+fn merge_layers(paths: Option<&ConfigPaths>, user_override: Option<Value>) -> LayeredConfig {
+    let mut diagnostics = Vec::new();
+    let mut files = Vec::new();
+    let mut sources = BTreeMap::new();
+
+    let mut merged = serde_json::to_value(AppConfigData::default()).unwrap_or(Value::Null);
+    record_leaves(&merged, "", ConfigLayer::Default, &mut sources);
+
+    if let Some(paths) = paths {
+        for (path, layer) in [
+            (&paths.user_file, ConfigLayer::User),
+            (&paths.machine_file, ConfigLayer::Machine),
+        ] {
+            let read = match (&user_override, layer) {
+                (Some(user), ConfigLayer::User) => {
+                    let text = serde_json::to_string_pretty(user).unwrap_or_default();
+                    Ok(Some((user.clone(), text)))
+                }
+                _ => read_layer_file(path, layer),
+            };
+            match read {
+                Ok(Some((value, text))) => {
+                    merge_into(&mut merged, value, "", layer, &mut sources);
+                    files.push(LayerFile {
+                        layer,
+                        file: path.display().to_string(),
+                        text,
+                    });
+                }
+                Ok(None) => {}
+                Err(e) => diagnostics.push(e),
+            }
+        }
+    }
+
+    merge_into(&mut merged, env_value(std::env::vars()), "", ConfigLayer::Env, &mut sources);
+
+    // 策略层最后合并，任何其他来源都不能覆盖
+    if let Some(paths) = paths {
+        match read_layer_file(&paths.policy_file, ConfigLayer::Policy) {
+            Ok(Some((value, text))) => {
+                merge_into(&mut merged, value, "", ConfigLayer::Policy, &mut sources);
+                files.push(LayerFile {
+                    layer: ConfigLayer::Policy,
+                    file: paths.policy_file.display().to_string(),
+                    text,
+                });
+            }
+            Ok(None) => {}
+            Err(e) => diagnostics.push(e),
+        }
+    }
+    let locked = sources
+        .iter()
+        .filter(|(key, layer)| {
+            **layer == ConfigLayer::Policy && !matches!(key.as_str(), "schema_version" | "$schema")
+        })
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    let (mut config, section_errors) = parse_config(&merged, AppConfigData::default());
+    for (path, message) in section_errors {
+        diagnostics.push(data_error(&path, message, &sources, &files));
+    }
+    let issues = config.sanitize(&default_config());
+
+    LayeredConfig {
+        config,
+        sources,
+        locked,
+        diagnostics,
+        issues,
+    }
+}
+
+/// 按配置段分别反序列化：某段类型不符时该段保留 base 中的值，其余段照常加载
+pub(super) fn parse_config(value: &Value, base: AppConfigData) -> (AppConfigData, Vec<(String, String)>) {
+    let mut config = base;
+    let mut errors = Vec::new();
+
+    macro_rules! section {
+        ($($field:ident),*) => {$(
+            if let Some(section) = value.get(stringify!($field)) {
+                match serde_path_to_error::deserialize(section) {
+                    Ok(parsed) => config.$field = parsed,
+                    Err(e) => {
+                        let inner = e.path().to_string();
+                        let path = if inner == "." {
+                            stringify!($field).to_string()
+                        } else if inner.starts_with('[') {
+                            format!("{}{}", stringify!($field), inner)
+                        } else {
+                            format!("{}.{}", stringify!($field), inner)
+                        };
+                        errors.push((path, e.into_inner().to_string()));
+                    }
+                }
+            }
+        )*};
+    }
+
+    section!(
+        schema_version, name, version, author, description, homepage, repository, copyright, app,
+        window, windows, tray, float_ball, servers, updater, shortcuts, dev
+    );
+    (config, errors)
+}
+
+/// 找到出错字段来自哪一层，并在该层文件中定位行列
+fn data_error(
+    path: &str,
+    message: String,
+    sources: &BTreeMap<String, ConfigLayer>,
+    files: &[LayerFile],
+) -> ConfigError {
+    // 出错路径本身或其祖先是叶子时直接命中；否则取其下所有叶子中优先级最高的层
+    let layer = sources
+        .iter()
+        .filter(|(key, _)| is_within(path, key) || is_within(key, path))
+        .map(|(_, layer)| *layer)
+        .max()
+        .unwrap_or(ConfigLayer::Default);
+
+    let file = files.iter().rev().find(|f| f.layer == layer);
+    let position = file.and_then(|f| locate(&f.text, path));
+    ConfigError {
+        kind: ConfigErrorKind::Data,
+        layer,
+        file: file.map(|f| f.file.clone()),
+        path: Some(path.to_string()),
+        line: position.map(|p| p.0),
+        column: position.map(|p| p.1),
+        message,
+    }
+}
+
+/// 内置默认层（app.config.json 由同一份 Rust Default 生成，不再单独嵌入）
+pub(super) fn default_value() -> Value {
+    serde_json::to_value(AppConfigData::default()).unwrap_or(Value::Null)
+}
+
+/// 内置默认层解析后的配置，用于回退非法取值
+pub(super) fn default_config() -> AppConfigData {
+    parse_config(&default_value(), AppConfigData::default()).0
+}
+
+/// 当前用户层的原始 JSON（文件不存在或损坏时为空对象）
+pub(super) fn user_layer() -> Value {
+    config_paths()
+        .and_then(|paths| read_layer_file(&paths.user_file, ConfigLayer::User).ok().flatten())
+        .map(|(value, _)| value)
+        .unwrap_or_else(|| Value::Object(Map::new()))
+}
+
+/// 启动时调用一次：用户文件的结构版本落后时备份并写回升级后的内容。
+/// 损坏的文件保持原样，由加载时报告
+pub fn migrate_user_file() {
+    let Some(paths) = config_paths() else {
+        return;
+    };
+    let Ok(text) = std::fs::read_to_string(&paths.user_file) else {
+        return;
+    };
+    let Ok(mut value) = serde_json::from_str::<Value>(&text) else {
+        return;
+    };
+    if !value.is_object() {
+        return;
+    }
+    if let Err(e) = migrate::migrate_file(&paths.user_file, &mut value) {
+        log::warn!("Failed to migrate {}: {}", paths.user_file.display(), e);
+    }
+}
+
+/// 读取一层配置文件并升级到当前结构版本；文件不存在返回 Ok(None)
+fn read_layer_file(path: &Path, layer: ConfigLayer) -> Result<Option<(Value, String)>, ConfigError> {
+    let file = path.display().to_string();
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(file_error(ConfigErrorKind::Io, layer, &file, e.to_string()));
+        }
+    };
+    let mut value = parse_layer_text(&text, &file, layer)?;
+    // 只在内存中升级；用户文件由启动时的 migrate_user_file 写回
+    migrate::migrate(&mut value)
+        .map_err(|e| file_error(ConfigErrorKind::Version, layer, &file, e))?;
+    Ok(Some((value, text)))
+}
+
+fn parse_layer_text(text: &str, file: &str, layer: ConfigLayer) -> Result<Value, ConfigError> {
+    match serde_json::from_str::<Value>(text) {
+        Ok(value) if value.is_object() => Ok(value),
+        Ok(_) => Err(file_error(
+            ConfigErrorKind::Syntax,
+            layer,
+            file,
+            "config file must contain a JSON object".to_string(),
+        )),
+        Err(e) => Err(ConfigError {
+            kind: ConfigErrorKind::Syntax,
+            layer,
+            file: Some(file.to_string()),
+            path: None,
+            line: Some(e.line()),
+            column: Some(e.column()),
+            message: e.to_string(),
+        }),
+    }
+}
+
+fn file_error(kind: ConfigErrorKind, layer: ConfigLayer, file: &str, message: String) -> ConfigError {
+    ConfigError {
+        kind,
+        layer,
+        file: Some(file.to_string()),
+        path: None,
+        line: None,
+        column: None,
+        message,
+    }
+}
+
+/// 把 PIXELPUNK_CONFIG__A__B=value 形式的环境变量转成嵌套 JSON；
+/// 值优先按 JSON 解析，失败时当作字符串
+fn env_value(vars: impl Iterator<Item = (String, String)>) -> Value {
+    let mut root = Value::Object(Map::new());
+    for (key, raw) in vars {
+        let Some(path) = key.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let segments: Vec<String> = path
+            .split("__")
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_lowercase())
+            .collect();
+        if segments.is_empty() {
+            continue;
+        }
+        let value = serde_json::from_str(&raw).unwrap_or(Value::String(raw));
+
+        let mut node = &mut root;
+        for segment in &segments[..segments.len() - 1] {
+            let map = node.as_object_mut().expect("env node is always an object");
+            node = map
+                .entry(segment.clone())
+                .or_insert_with(|| Value::Object(Map::new()));
+            if !node.is_object() {
+                *node = Value::Object(Map::new());
+            }
+        }
+        if let Some(map) = node.as_object_mut() {
+            map.insert(segments[segments.len() - 1].clone(), value);
+        }
+    }
+    root
+}
+
+/// 深度合并：对象逐键递归，数组和标量整体替换
+pub fn deep_merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base_map), Value::Object(overlay_map)) => {
+            for (key, value) in overlay_map {
+                match base_map.get_mut(&key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => {
+                        base_map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// 按元素的 key 字段合并的数组（数组路径，key 字段名）
+const KEYED_ARRAYS: &[(&str, &str)] = &[("windows", "label")];
+
+/// 与 deep_merge 相同，同时记录被覆盖叶子字段的来源。KEYED_ARRAYS 中的数组例外：
+/// 同 key 的元素逐字段覆盖，新的 key 追加在末尾；其余数组整体替换
+fn merge_into(
+    base: &mut Value,
+    overlay: Value,
+    path: &str,
+    layer: ConfigLayer,
+    sources: &mut BTreeMap<String, ConfigLayer>,
+) {
+    let array_key = KEYED_ARRAYS
+        .iter()
+        .find(|(array, _)| *array == path)
+        .map(|(_, key)| *key);
+    match (base, overlay, array_key) {
+        (Value::Array(items), Value::Array(overlay_items), Some(key)) => {
+            for item in overlay_items {
+                let existing = item.get(key).and_then(|id| {
+                    items
+                        .iter_mut()
+                        .find(|existing| existing.get(key) == Some(id))
+                });
+                match existing {
+                    // 元素内部的来源不单独记录，整个数组记为最后修改它的层
+                    Some(existing) => merge_into(existing, item, "", layer, &mut BTreeMap::new()),
+                    None => items.push(item),
+                }
+            }
+            sources.insert(path.to_string(), layer);
+        }
+        (base, overlay, _) => merge_values(base, overlay, path, layer, sources),
+    }
+}
+
+fn merge_values(
+    base: &mut Value,
+    overlay: Value,
+    path: &str,
+    layer: ConfigLayer,
+    sources: &mut BTreeMap<String, ConfigLayer>,
+) {
+    match (base, overlay) {
+        (Value::Object(base_map), Value::Object(overlay_map)) => {
+            for (key, value) in overlay_map {
+                let child_path = join_path(path, &key);
+                match base_map.get_mut(&key) {
+                    Some(existing) => merge_into(existing, value, &child_path, layer, sources),
+                    None => {
+                        record_leaves(&value, &child_path, layer, sources);
+                        base_map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => {
+            // 整体替换时，旧的子字段来源一并作废
+            let prefix = format!("{}.", path);
+            sources.retain(|k, _| !k.starts_with(&prefix));
+            record_leaves(&overlay, path, layer, sources);
+            *base = overlay;
+        }
+    }
+}
+
+fn record_leaves(
+    value: &Value,
+    path: &str,
+    layer: ConfigLayer,
+    sources: &mut BTreeMap<String, ConfigLayer>,
+) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                record_leaves(child, &join_path(path, key), layer, sources);
+            }
+        }
+        _ => {
+            sources.insert(path.to_string(), layer);
+        }
+    }
+}
+
+/// key 是否等于 parent 或位于 parent 之下
+pub(super) fn is_within(key: &str, parent: &str) -> bool {
+    key == parent
+        || key
+            .strip_prefix(parent)
+            .is_some_and(|rest| rest.starts_with('.') || rest.starts_with('['))
+}
+
+pub(super) fn join_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WindowDefinition;
+
+    #[test]
+    fn reading_an_old_file_migrates_only_in_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(USER_CONFIG_FILE);
+        let text = include_str!("../../tests/fixtures/migrate/v0.json");
+        std::fs::write(&path, text).unwrap();
+
+        let (value, _) = read_layer_file(&path, ConfigLayer::User).unwrap().unwrap();
+        assert_eq!(migrate::schema_version(&value), migrate::CURRENT_SCHEMA_VERSION);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), text);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    fn load_user_file(dir: &Path, text: &str) -> LayeredConfig {
+        let paths = ConfigPaths {
+            user_file: dir.join(USER_CONFIG_FILE),
+            machine_file: dir.join("machine").join(USER_CONFIG_FILE),
+            policy_file: dir.join("machine").join(POLICY_FILE),
+        };
+        std::fs::write(&paths.user_file, text).unwrap();
+        merge_layers(Some(&paths), None)
+    }
+
+    #[test]
+    fn user_file_overrides_one_window_by_label() {
+        let dir = tempfile::tempdir().unwrap();
+        let loaded = load_user_file(
+            dir.path(),
+            r#"{ "windows": [{ "label": "about", "width": 640, "always_on_top": true }] }"#,
+        );
+        assert!(loaded.diagnostics.is_empty(), "{:?}", loaded.diagnostics);
+        assert_eq!(loaded.sources.get("windows"), Some(&ConfigLayer::User));
+
+        let defaults = AppConfigData::default();
+        let labels = |windows: &[WindowDefinition]| -> Vec<String> {
+            windows.iter().map(|window| window.label.clone()).collect()
+        };
+        assert_eq!(labels(&loaded.config.windows), labels(&defaults.windows));
+        let about = loaded.config.window_definition("about").unwrap();
+        let default_about = defaults.window_definition("about").unwrap();
+        assert_eq!(about.width, 640.0);
+        assert!(about.always_on_top);
+        assert_eq!(about.height, default_about.height);
+        assert_eq!(about.route, default_about.route);
+        assert_eq!(
+            loaded.config.window_definition("login").unwrap().width,
+            defaults.window_definition("login").unwrap().width
+        );
+    }
+
+    #[test]
+    fn user_file_adds_a_window_with_default_chrome() {
+        let dir = tempfile::tempdir().unwrap();
+        let loaded = load_user_file(
+            dir.path(),
+            r#"{ "windows": [{ "label": "help", "title": "帮助", "route": "/help", "width": 480, "height": 520 }] }"#,
+        );
+        assert!(loaded.diagnostics.is_empty(), "{:?}", loaded.diagnostics);
+        assert_eq!(
+            loaded.config.windows.len(),
+            AppConfigData::default().windows.len() + 1
+        );
+        let help = loaded.config.window_definition("help").unwrap();
+        assert!(help.resizable && help.decorations && !help.transparent);
+        assert!(help.capabilities.is_empty());
+    }
+
+    #[test]
+    fn invalid_window_reports_the_user_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let loaded = load_user_file(
+            dir.path(),
+            "{\n  \"windows\": [{ \"label\": \"about\", \"width\": \"wide\" }]\n}",
+        );
+        assert_eq!(loaded.diagnostics.len(), 1);
+        let error = &loaded.diagnostics[0];
+        assert_eq!(error.layer, ConfigLayer::User);
+        assert_eq!(error.path.as_deref(), Some("windows[2].width"));
+        assert_eq!(
+            loaded.config.windows.len(),
+            AppConfigData::default().windows.len()
+        );
+    }
+}
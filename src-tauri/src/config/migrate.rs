@@ -0,0 +1,306 @@
+use serde_json::{json, Map, Value};
+use std::path::Path;
+
+use super::layers::deep_merge;
+
+/// 当前配置结构版本；每次字段新增/改名导致旧文档无法解析时递增，并在 MIGRATIONS 末尾追加一步
+pub const CURRENT_SCHEMA_VERSION: u32 = 4;
+
+/// 升级函数，下标 i 负责把版本 i 的文档升级到 i + 1
+type Migration = fn(&mut Map<String, Value>);
+
+const MIGRATIONS: &[Migration] = &[v0_to_v1, v1_to_v2, v2_to_v3, v3_to_v4];
+
+/// 读取文档声明的版本，缺省视为 0（引入 schema_version 之前的文档）
+pub fn schema_version(doc: &Value) -> u32 {
+    doc.get("schema_version")
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .unwrap_or(0)
+}
+
+/// 逐步把文档升级到 CURRENT_SCHEMA_VERSION，返回原始版本；
+/// 文档版本比当前程序更新时返回错误
+pub fn migrate(doc: &mut Value) -> Result<u32, String> {
+    let from = schema_version(doc);
+    if from > CURRENT_SCHEMA_VERSION {
+        return Err(format!(
+            "config schema_version {} is newer than supported {}",
+            from, CURRENT_SCHEMA_VERSION
+        ));
+    }
+    let Value::Object(map) = doc else {
+        return Err("config document is not a JSON object".to_string());
+    };
+
+    for version in from..CURRENT_SCHEMA_VERSION {
+        MIGRATIONS[version as usize](map);
+        map.insert("schema_version".to_string(), Value::from(version + 1));
+        log::info!("Config migrated from v{} to v{}", version, version + 1);
+    }
+    Ok(from)
+}
+
+/// 升级磁盘上的配置文件：先备份为 `<file>.v<旧版本>.bak`，再原子写回
+pub fn migrate_file(path: &Path, doc: &mut Value) -> Result<(), String> {
+    let from = schema_version(doc);
+    if from >= CURRENT_SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    let original = doc.clone();
+    migrate(doc)?;
+
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("config");
+    let backup = path.with_file_name(format!("{}.v{}.bak", file_name, from));
+    std::fs::copy(path, &backup)
+        .map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+    log::info!("Config backed up to {}", backup.display());
+
+    if let Err(e) = super::persist::write_atomic(path, doc) {
+        // 写回失败时保持内存中的旧文档，下次启动再试
+        *doc = original;
+        return Err(e.to_string());
+    }
+    Ok(())
+}
+
+/// v0 版本中可能出现的 camelCase 键名：从前端 app.config.ts 复制来的手写配置沿用了这些写法。
+/// 按所在对象列出，只改名这些已知字段，用户自定义的值（如服务端名、快捷键）保持原样
+const V0_CAMEL_KEYS: &[(&str, &[&str])] = &[
+    ("", &["floatBall"]),
+    ("app", &["singleInstance", "rememberWindowState", "quitOnClose"]),
+    (
+        "window",
+        &["minWidth", "minHeight", "maxWidth", "maxHeight", "alwaysOnTop", "skipTaskbar"],
+    ),
+    ("float_ball", &["defaultX", "defaultY", "alwaysOnTop"]),
+    ("float_ball.panel", &["expandOnHover", "hoverDelay"]),
+    ("float_ball.upload", &["apiUrl", "maxFileSize", "allowedTypes"]),
+    ("dev", &["openDevTools"]),
+];
+
+/// v0 → v1：已知的 camelCase 键名改为 snake_case
+fn v0_to_v1(doc: &mut Map<String, Value>) {
+    for (section, keys) in V0_CAMEL_KEYS {
+        let mut target = Some(&mut *doc);
+        for segment in section.split('.').filter(|s| !s.is_empty()) {
+            target = target.and_then(|map| map.get_mut(segment)).and_then(Value::as_object_mut);
+        }
+        let Some(map) = target else {
+            continue;
+        };
+        for key in keys.iter() {
+            let Some(value) = map.remove(*key) else {
+                continue;
+            };
+            let snake = camel_to_snake(key);
+            match map.get_mut(&snake) {
+                // 同时存在两种写法时合并，冲突的字段以 snake_case 为准
+                Some(existing) => {
+                    let mut merged = value;
+                    deep_merge(&mut merged, existing.take());
+                    *existing = merged;
+                }
+                None => {
+                    map.insert(snake, value);
+                }
+            }
+        }
+    }
+}
+
+/// v1 → v2：托盘菜单项的 action 由 "about" / "show" / "quit" / "custom" 字符串改为带参数的动作对象
+fn v1_to_v2(doc: &mut Map<String, Value>) {
+    let Some(groups) = doc.get_mut("tray").and_then(|tray| tray.get_mut("menus")).and_then(Value::as_array_mut) else {
+        return;
+    };
+    let items = groups
+        .iter_mut()
+        .filter_map(|group| group.get_mut("items").and_then(Value::as_array_mut))
+        .flatten();
+    for item in items {
+        let id = item.get("id").and_then(Value::as_str).unwrap_or_default().to_string();
+        let Some(action) = item.get_mut("action") else {
+            continue;
+        };
+        let Some(name) = action.as_str() else {
+            continue;
+        };
+        *action = match name {
+            "about" => json!({ "type": "show_window", "label": "about" }),
+            "show" => json!({ "type": "show_window", "label": "main" }),
+            "quit" => json!({ "type": "quit" }),
+            // custom 原本没有任何行为，改为通知前端由其自行处理
+            _ => json!({ "type": "emit", "event": "tray:action", "payload": { "id": id } }),
+        };
+    }
+}
+
+/// v2 → v3：托盘菜单由「分组 + 菜单项」两层结构改为带 kind 的节点树，原分组变为子菜单
+fn v2_to_v3(doc: &mut Map<String, Value>) {
+    let Some(groups) = doc.get_mut("tray").and_then(|tray| tray.get_mut("menus")).and_then(Value::as_array_mut) else {
+        return;
+    };
+    for group in groups.iter_mut().filter_map(Value::as_object_mut) {
+        if group.contains_key("kind") {
+            continue;
+        }
+        group.insert("kind".to_string(), json!("submenu"));
+        let items = group.get_mut("items").and_then(Value::as_array_mut);
+        for item in items.into_iter().flatten().filter_map(Value::as_object_mut) {
+            item.entry("kind").or_insert_with(|| json!("item"));
+        }
+    }
+}
+
+/// v3 → v4：float_ball.margin 移入新增的 float_ball.placement；
+/// 旧版本的悬浮球固定放在主显示器右下角，迁移后保持这一行为
+fn v3_to_v4(doc: &mut Map<String, Value>) {
+    let Some(float_ball) = doc.get_mut("float_ball").and_then(Value::as_object_mut) else {
+        return;
+    };
+    let Some(margin) = float_ball.remove("margin") else {
+        return;
+    };
+    float_ball.entry("placement").or_insert_with(|| {
+        json!({
+            "monitor": { "type": "primary" },
+            "anchor": "bottom_right",
+            "margin": margin,
+        })
+    });
+}
+
+fn camel_to_snake(key: &str) -> String {
+    let mut out = String::with_capacity(key.len() + 4);
+    for (i, ch) in key.chars().enumerate() {
+        if ch.is_ascii_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.push(ch.to_ascii_lowercase());
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::layers::{self, parse_config};
+    use crate::config::AppConfigData;
+
+    /// 每个结构版本一份夹具，vN 是 v(N-1) 经过一步升级后的预期结果
+    const FIXTURES: [&str; CURRENT_SCHEMA_VERSION as usize + 1] = [
+        include_str!("../../tests/fixtures/migrate/v0.json"),
+        include_str!("../../tests/fixtures/migrate/v1.json"),
+        include_str!("../../tests/fixtures/migrate/v2.json"),
+        include_str!("../../tests/fixtures/migrate/v3.json"),
+        include_str!("../../tests/fixtures/migrate/v4.json"),
+    ];
+
+    fn fixture(version: usize) -> Value {
+        serde_json::from_str(FIXTURES[version]).unwrap()
+    }
+
+    #[test]
+    fn each_step_matches_the_next_fixture() {
+        for (version, step) in MIGRATIONS.iter().enumerate() {
+            let mut doc = fixture(version);
+            let map = doc.as_object_mut().unwrap();
+            step(map);
+            map.insert("schema_version".to_string(), Value::from(version + 1));
+            assert_eq!(doc, fixture(version + 1), "v{} -> v{}", version, version + 1);
+        }
+    }
+
+    #[test]
+    fn migrate_runs_every_step_from_the_declared_version() {
+        for version in 0..=CURRENT_SCHEMA_VERSION as usize {
+            let mut doc = fixture(version);
+            assert_eq!(migrate(&mut doc), Ok(version as u32));
+            assert_eq!(doc, fixture(CURRENT_SCHEMA_VERSION as usize));
+        }
+    }
+
+    #[test]
+    fn migrated_fixture_parses_without_errors() {
+        let mut merged = layers::default_value();
+        deep_merge(&mut merged, fixture(CURRENT_SCHEMA_VERSION as usize));
+        let (config, errors) = parse_config(&merged, AppConfigData::default());
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert!(config.app.quit_on_close);
+        assert!(!config.float_ball.enabled);
+        assert_eq!(config.float_ball.placement.margin, 120.0);
+    }
+
+    #[test]
+    fn v0_to_v1_only_renames_known_keys() {
+        let mut doc = json!({
+            "floatBall": { "defaultX": 5, "unknownKey": 1 },
+            "servers": { "profiles": [{ "name": "myServer", "baseUrl": "x" }] },
+            "tray": { "menus": [{ "action": { "payload": { "userId": 1 } } }] },
+        });
+        v0_to_v1(doc.as_object_mut().unwrap());
+        assert_eq!(
+            doc,
+            json!({
+                "float_ball": { "default_x": 5, "unknownKey": 1 },
+                "servers": { "profiles": [{ "name": "myServer", "baseUrl": "x" }] },
+                "tray": { "menus": [{ "action": { "payload": { "userId": 1 } } }] },
+            })
+        );
+    }
+
+    #[test]
+    fn v0_to_v1_prefers_snake_case_on_conflict() {
+        let mut doc = json!({ "app": { "quitOnClose": true, "quit_on_close": false } });
+        v0_to_v1(doc.as_object_mut().unwrap());
+        assert_eq!(doc, json!({ "app": { "quit_on_close": false } }));
+    }
+
+    #[test]
+    fn v3_to_v4_keeps_an_existing_placement() {
+        let placement = json!({ "monitor": { "type": "cursor" }, "anchor": "top_left", "margin": 8 });
+        let mut doc = json!({ "float_ball": { "margin": 120, "placement": placement.clone() } });
+        v3_to_v4(doc.as_object_mut().unwrap());
+        assert_eq!(doc, json!({ "float_ball": { "placement": placement } }));
+    }
+
+    #[test]
+    fn newer_documents_are_rejected() {
+        let mut doc = json!({ "schema_version": CURRENT_SCHEMA_VERSION + 1 });
+        assert!(migrate(&mut doc).is_err());
+    }
+
+    #[test]
+    fn migrate_file_backs_up_and_rewrites() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.config.json");
+        std::fs::write(&path, FIXTURES[0]).unwrap();
+
+        let mut doc = fixture(0);
+        migrate_file(&path, &mut doc).unwrap();
+        assert_eq!(doc, fixture(CURRENT_SCHEMA_VERSION as usize));
+        let written: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written, doc);
+        let backup = dir.path().join("app.config.json.v0.bak");
+        assert_eq!(std::fs::read_to_string(backup).unwrap(), FIXTURES[0]);
+    }
+
+    #[test]
+    fn migrate_file_leaves_current_files_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.config.json");
+        let current = FIXTURES[CURRENT_SCHEMA_VERSION as usize];
+        std::fs::write(&path, current).unwrap();
+
+        let mut doc = fixture(CURRENT_SCHEMA_VERSION as usize);
+        migrate_file(&path, &mut doc).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), current);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,707 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+
+pub mod bundle;
+mod error;
+mod layers;
+mod migrate;
+mod persist;
+mod reload;
+pub mod schema;
+pub mod servers;
+mod validate;
+
+pub use error::ConfigError;
+pub use layers::{init_paths, migrate_user_file, LayeredConfig};
+pub use persist::{ConfigChange, ConfigWriteError};
+pub use reload::watch;
+pub use validate::{ConfigIssue, Severity};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AppSettings {
+    pub single_instance: bool,
+    pub remember_window_state: bool,
+    pub quit_on_close: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WindowConfig {
+    pub width: f64,
+    pub height: f64,
+    pub min_width: Option<f64>,
+    pub min_height: Option<f64>,
+    pub max_width: Option<f64>,
+    pub max_height: Option<f64>,
+    pub resizable: bool,
+    pub maximizable: bool,
+    pub minimizable: bool,
+    pub closable: bool,
+    pub always_on_top: bool,
+    pub center: bool,
+    pub skip_taskbar: bool,
+}
+
+/// 放置窗口时选择的显示器；找不到时退回主显示器
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MonitorTarget {
+    /// 鼠标所在的显示器
+    Cursor,
+    /// 主窗口所在的显示器
+    MainWindow,
+    /// 系统主显示器
+    Primary,
+    /// 按名称指定的显示器
+    Named { name: String },
+}
+
+/// 窗口贴靠的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Anchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Center,
+}
+
+/// 窗口在显示器可用区域（不含任务栏、菜单栏）中的位置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WindowPlacement {
+    pub monitor: MonitorTarget,
+    pub anchor: Anchor,
+    /// 距可用区域边缘的距离（逻辑像素）
+    pub margin: f64,
+}
+
+/// 注册的窗口，通过 open_window 按 label 创建；主窗口由 tauri.conf.json 创建，不在此列
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WindowDefinition {
+    pub label: String,
+    pub title: String,
+    /// 前端路由（如 `/login`，使用 hash 路由）或独立页面（如 `about.html`）
+    pub route: String,
+    pub width: f64,
+    pub height: f64,
+    #[serde(default = "default_true")]
+    pub resizable: bool,
+    #[serde(default = "default_true")]
+    pub maximizable: bool,
+    #[serde(default = "default_true")]
+    pub minimizable: bool,
+    #[serde(default = "default_true")]
+    pub decorations: bool,
+    #[serde(default)]
+    pub transparent: bool,
+    #[serde(default)]
+    pub always_on_top: bool,
+    #[serde(default)]
+    pub skip_taskbar: bool,
+    /// 创建时居中（设置了 placement 时以 placement 为准）
+    #[serde(default)]
+    pub center: bool,
+    /// 创建时的位置；显示器变化时重新放置
+    #[serde(default)]
+    pub placement: Option<WindowPlacement>,
+    /// 创建窗口时额外授予的权限（如 `core:window:allow-set-size`）；
+    /// tauri.conf.json 中已按 label 声明的能力不需要重复
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 托盘菜单项点击后执行的动作
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TrayAction {
+    /// 显示并聚焦窗口；windows 中注册的窗口不存在时自动创建
+    ShowWindow { label: String },
+    /// 显示主窗口并跳转到前端路由，如 `/settings`
+    OpenRoute { route: String },
+    /// 向所有窗口广播事件，payload 缺省为 `{ id }`
+    Emit {
+        event: String,
+        #[serde(default)]
+        payload: Option<Value>,
+    },
+    /// 显示/隐藏悬浮球
+    ToggleFloatBall,
+    /// 上传剪贴板中的图片或文件
+    UploadClipboard,
+    /// 用系统浏览器打开 http(s) 链接
+    OpenUrl { url: String },
+    Quit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TrayMenuItem {
+    pub id: String,
+    pub label: String,
+    pub action: TrayAction,
+    /// PNG 图标路径，相对路径按应用资源目录解析
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// 菜单快捷键，如 `CmdOrCtrl+Q`
+    #[serde(default)]
+    pub accelerator: Option<String>,
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+/// 复选菜单项：勾选状态取自一个布尔配置项，点击时取反并写入用户配置
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TrayCheckItem {
+    pub id: String,
+    pub label: String,
+    /// 绑定的配置项路径，如 `app.quit_on_close`
+    pub setting: String,
+    #[serde(default)]
+    pub accelerator: Option<String>,
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TraySubmenu {
+    pub label: String,
+    #[serde(default)]
+    pub disabled: bool,
+    pub items: Vec<TrayMenuEntry>,
+}
+
+/// 「最近上传」子菜单的占位节点，内容由最近上传记录动态生成
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TrayRecentUploads {
+    pub label: String,
+}
+
+/// 托盘菜单节点；子菜单可以任意嵌套，也可以直接放在顶层
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TrayMenuEntry {
+    Item(TrayMenuItem),
+    Check(TrayCheckItem),
+    Separator,
+    Submenu(TraySubmenu),
+    RecentUploads(TrayRecentUploads),
+}
+
+/// 复制上传链接时使用的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkFormat {
+    Url,
+    Markdown,
+    Html,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecentUploadsConfig {
+    /// 最多保留的记录数
+    pub limit: u32,
+    /// 点击记录时复制到剪贴板的格式
+    pub copy_format: LinkFormat,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TrayConfig {
+    pub enabled: bool,
+    pub tooltip: Option<String>,
+    pub title: Option<String>,
+    pub menus: Vec<TrayMenuEntry>,
+    pub recent_uploads: RecentUploadsConfig,
+}
+
+impl TrayConfig {
+    /// 按 id 查找菜单项（包括嵌套子菜单中的）
+    pub fn find(&self, id: &str) -> Option<&TrayMenuEntry> {
+        fn find_in<'a>(entries: &'a [TrayMenuEntry], id: &str) -> Option<&'a TrayMenuEntry> {
+            entries.iter().find_map(|entry| match entry {
+                TrayMenuEntry::Item(item) if item.id == id => Some(entry),
+                TrayMenuEntry::Check(check) if check.id == id => Some(entry),
+                TrayMenuEntry::Submenu(submenu) => find_in(&submenu.items, id),
+                _ => None,
+            })
+        }
+        find_in(&self.menus, id)
+    }
+
+    /// 复选菜单项绑定的所有配置项
+    pub fn bound_settings(&self) -> Vec<&str> {
+        fn collect<'a>(entries: &'a [TrayMenuEntry], out: &mut Vec<&'a str>) {
+            for entry in entries {
+                match entry {
+                    TrayMenuEntry::Check(check) => out.push(&check.setting),
+                    TrayMenuEntry::Submenu(submenu) => collect(&submenu.items, out),
+                    _ => {}
+                }
+            }
+        }
+        let mut out = Vec::new();
+        collect(&self.menus, &mut out);
+        out
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DevConfig {
+    pub open_dev_tools: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FloatBallPanelConfig {
+    pub width: f64,
+    pub height: f64,
+    pub expand_on_hover: bool,
+    pub hover_delay: u64,
+}
+
+/// 大文件分片上传：init 创建会话，逐片上传（带 SHA-256 校验），complete 合并
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChunkUploadConfig {
+    /// 超过该大小（字节）的文件分片上传，0 表示不分片
+    pub threshold: u64,
+    /// 每片大小（字节）
+    pub chunk_size: u64,
+    /// 分片上传的文件大小上限（字节），代替 upload.max_file_size
+    pub max_file_size: u64,
+    /// 单个分片失败后的重试次数
+    pub retries: u32,
+    pub init_url: String,
+    pub chunk_url: String,
+    pub complete_url: String,
+}
+
+/// 秒传：上传前在本地计算 MD5，整批询问服务端哪些文件已存在，已存在的文件不再上传内容
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InstantUploadConfig {
+    pub enabled: bool,
+    /// 批量检测已存在的文件
+    pub check_url: String,
+    /// 按 MD5 创建文件记录
+    pub instant_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FloatBallUploadConfig {
+    pub api_url: String,
+    pub max_file_size: u64,
+    pub allowed_types: Vec<String>,
+    pub concurrent: u32,
+    pub chunk: ChunkUploadConfig,
+    pub instant: InstantUploadConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FloatBallConfig {
+    pub enabled: bool,
+    pub width: f64,
+    pub height: f64,
+    /// 固定位置（逻辑像素）；都为 0 时按 placement 放置
+    pub default_x: f64,
+    pub default_y: f64,
+    pub always_on_top: bool,
+    pub placement: WindowPlacement,
+    pub panel: FloatBallPanelConfig,
+    pub upload: FloatBallUploadConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ShortcutConfig {
+    /// 快捷键组合，如 `CommandOrControl+Shift+F`
+    pub key: String,
+    pub description: String,
+    /// 全局快捷键（应用最小化也能响应）
+    pub global: bool,
+    /// 处理函数名，前端通过 shortcutManager.registerHandler 注册
+    pub handler: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdaterSettings {
+    /// 是否允许检查和安装更新（前端 updater.config.ts 也需启用）
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ServerTlsConfig {
+    /// 接受无效或自签名证书（仅用于本地调试）
+    pub accept_invalid_certs: bool,
+    /// 额外信任的 CA 证书（PEM 文件路径）
+    pub ca_cert: Option<String>,
+}
+
+/// 一个具名的服务端配置
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ServerProfile {
+    pub name: String,
+    /// 服务端根地址，如 `http://localhost:9520`；上传等相对路径基于它拼接
+    pub base_url: String,
+    /// 请求超时（毫秒）
+    pub timeout_ms: u64,
+    pub tls: ServerTlsConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ServersConfig {
+    /// 当前使用的服务端配置名
+    pub active: String,
+    pub profiles: Vec<ServerProfile>,
+}
+
+impl ServersConfig {
+    pub fn get(&self, name: &str) -> Option<&ServerProfile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    /// 当前使用的服务端（校验保证 active 一定存在）
+    pub fn active_profile(&self) -> Option<&ServerProfile> {
+        self.get(&self.active)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AppConfigData {
+    /// 配置结构版本，旧文档加载时按 migrate 模块逐步升级
+    pub schema_version: u32,
+    pub name: String,
+    pub version: String,
+    pub author: String,
+    pub description: String,
+    pub homepage: Option<String>,
+    pub repository: Option<String>,
+    pub copyright: Option<String>,
+    pub app: AppSettings,
+    pub window: WindowConfig,
+    pub windows: Vec<WindowDefinition>,
+    pub tray: TrayConfig,
+    pub float_ball: FloatBallConfig,
+    pub servers: ServersConfig,
+    pub updater: UpdaterSettings,
+    pub shortcuts: Vec<ShortcutConfig>,
+    pub dev: DevConfig,
+}
+
+// 默认配置
+impl Default for AppConfigData {
+    fn default() -> Self {
+        Self {
+            schema_version: migrate::CURRENT_SCHEMA_VERSION,
+            name: "Tauri Vue Template".to_string(),
+            version: "1.0.0".to_string(),
+            author: "Your Name".to_string(),
+            description: "基于 Tauri + Vue 3 + TypeScript 的跨平台桌面应用开发模板".to_string(),
+            homepage: Some("https://github.com".to_string()),
+            repository: Some("https://github.com".to_string()),
+            copyright: Some("Copyright © 2025 Your Name. All rights reserved.".to_string()),
+            app: AppSettings {
+                single_instance: true,
+                remember_window_state: true,
+                quit_on_close: false,
+            },
+            window: WindowConfig {
+                width: 1200.0,
+                height: 800.0,
+                min_width: Some(800.0),
+                min_height: Some(600.0),
+                max_width: None,
+                max_height: None,
+                resizable: true,
+                maximizable: true,
+                minimizable: true,
+                closable: true,
+                always_on_top: false,
+                center: true,
+                skip_taskbar: false,
+            },
+            windows: vec![
+                WindowDefinition {
+                    label: "login".to_string(),
+                    title: "PixelPunk - 登录".to_string(),
+                    route: "/login".to_string(),
+                    width: 900.0,
+                    height: 600.0,
+                    resizable: false,
+                    maximizable: false,
+                    minimizable: true,
+                    decorations: false,
+                    transparent: false,
+                    always_on_top: false,
+                    skip_taskbar: false,
+                    center: true,
+                    capabilities: vec![],
+                    placement: None,
+                },
+                // 尺寸、位置和置顶由 float_ball 配置决定
+                WindowDefinition {
+                    label: "float-ball".to_string(),
+                    title: "Float Ball".to_string(),
+                    route: "/float-ball".to_string(),
+                    width: 60.0,
+                    height: 60.0,
+                    resizable: false,
+                    maximizable: false,
+                    minimizable: false,
+                    decorations: false,
+                    transparent: true,
+                    always_on_top: true,
+                    skip_taskbar: true,
+                    center: false,
+                    capabilities: vec![],
+                    placement: None,
+                },
+                WindowDefinition {
+                    label: "about".to_string(),
+                    title: "关于".to_string(),
+                    route: "about.html".to_string(),
+                    width: 480.0,
+                    height: 520.0,
+                    resizable: false,
+                    maximizable: false,
+                    minimizable: true,
+                    decorations: true,
+                    transparent: false,
+                    always_on_top: false,
+                    skip_taskbar: false,
+                    center: true,
+                    capabilities: vec![],
+                    placement: None,
+                },
+            ],
+            tray: TrayConfig {
+                enabled: true,
+                tooltip: Some("Tauri Vue Template".to_string()),
+                title: None,
+                menus: vec![
+                    TrayMenuEntry::Submenu(TraySubmenu {
+                        label: "应用".to_string(),
+                        disabled: false,
+                        items: vec![
+                            TrayMenuEntry::Item(TrayMenuItem {
+                                id: "about".to_string(),
+                                label: "关于".to_string(),
+                                action: TrayAction::ShowWindow {
+                                    label: "about".to_string(),
+                                },
+                                icon: None,
+                                accelerator: None,
+                                disabled: false,
+                            }),
+                            TrayMenuEntry::Item(TrayMenuItem {
+                                id: "settings".to_string(),
+                                label: "设置".to_string(),
+                                action: TrayAction::OpenRoute {
+                                    route: "/settings".to_string(),
+                                },
+                                icon: None,
+                                accelerator: None,
+                                disabled: false,
+                            }),
+                        ],
+                    }),
+                    TrayMenuEntry::Submenu(TraySubmenu {
+                        label: "窗口".to_string(),
+                        disabled: false,
+                        items: vec![
+                            TrayMenuEntry::Item(TrayMenuItem {
+                                id: "show".to_string(),
+                                label: "显示窗口".to_string(),
+                                action: TrayAction::ShowWindow {
+                                    label: "main".to_string(),
+                                },
+                                icon: None,
+                                accelerator: None,
+                                disabled: false,
+                            }),
+                            TrayMenuEntry::Check(TrayCheckItem {
+                                id: "quit-on-close".to_string(),
+                                label: "关闭窗口时退出".to_string(),
+                                setting: "app.quit_on_close".to_string(),
+                                accelerator: None,
+                                disabled: false,
+                            }),
+                        ],
+                    }),
+                    TrayMenuEntry::RecentUploads(TrayRecentUploads {
+                        label: "最近上传".to_string(),
+                    }),
+                    TrayMenuEntry::Separator,
+                    TrayMenuEntry::Item(TrayMenuItem {
+                        id: "quit".to_string(),
+                        label: "退出".to_string(),
+                        action: TrayAction::Quit,
+                        icon: None,
+                        accelerator: Some("CmdOrCtrl+Q".to_string()),
+                        disabled: false,
+                    }),
+                ],
+                recent_uploads: RecentUploadsConfig {
+                    limit: 10,
+                    copy_format: LinkFormat::Url,
+                },
+            },
+            float_ball: FloatBallConfig {
+                enabled: true,
+                width: 400.0,
+                height: 450.0,
+                default_x: 0.0,
+                default_y: 0.0,
+                always_on_top: true,
+                placement: WindowPlacement {
+                    monitor: MonitorTarget::Cursor,
+                    anchor: Anchor::BottomRight,
+                    margin: 120.0,
+                },
+                panel: FloatBallPanelConfig {
+                    width: 320.0,
+                    height: 450.0,
+                    expand_on_hover: true,
+                    hover_delay: 300,
+                },
+                upload: FloatBallUploadConfig {
+                    api_url: "/api/upload".to_string(),
+                    max_file_size: 104857600, // 100MB
+                    allowed_types: vec![],
+                    concurrent: 3,
+                    chunk: ChunkUploadConfig {
+                        threshold: 20971520,       // 20MB
+                        chunk_size: 5242880,       // 5MB
+                        max_file_size: 4294967296, // 4GB
+                        retries: 3,
+                        init_url: "/api/upload/chunk/init".to_string(),
+                        chunk_url: "/api/upload/chunk".to_string(),
+                        complete_url: "/api/upload/chunk/complete".to_string(),
+                    },
+                    instant: InstantUploadConfig {
+                        enabled: true,
+                        check_url: "/api/v1/files/check-duplicate".to_string(),
+                        instant_url: "/api/v1/files/instant-upload".to_string(),
+                    },
+                },
+            },
+            servers: ServersConfig {
+                active: "local".to_string(),
+                profiles: vec![ServerProfile {
+                    name: "local".to_string(),
+                    base_url: "http://localhost:9520".to_string(),
+                    timeout_ms: 30_000,
+                    tls: ServerTlsConfig {
+                        accept_invalid_certs: false,
+                        ca_cert: None,
+                    },
+                }],
+            },
+            updater: UpdaterSettings { enabled: true },
+            shortcuts: vec![
+                ShortcutConfig {
+                    key: "CommandOrControl+Shift+F".to_string(),
+                    description: "显示/隐藏悬浮球".to_string(),
+                    global: true,
+                    handler: "toggleFloatBall".to_string(),
+                },
+                ShortcutConfig {
+                    key: "CommandOrControl+,".to_string(),
+                    description: "打开设置".to_string(),
+                    global: false,
+                    handler: "openSettings".to_string(),
+                },
+                ShortcutConfig {
+                    key: "F11".to_string(),
+                    description: "全屏切换".to_string(),
+                    global: false,
+                    handler: "toggleFullscreen".to_string(),
+                },
+            ],
+            dev: DevConfig {
+                open_dev_tools: true,
+            },
+        }
+    }
+}
+
+impl AppConfigData {
+    /// 按 label 查找注册的窗口
+    pub fn window_definition(&self, label: &str) -> Option<&WindowDefinition> {
+        self.windows.iter().find(|w| w.label == label)
+    }
+
+    /// 当前生效的配置（分层合并结果，热重载后自动更新）
+    pub fn load() -> Self {
+        layers::current().config
+    }
+
+    /// 测试中替换当前生效的配置
+    #[cfg(test)]
+    pub fn set_current(config: Self) {
+        let mut layered = layers::current();
+        layered.config = config;
+        layers::replace_current(layered);
+    }
+}
+
+/// config:changed 事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChangedPayload {
+    pub changes: Vec<ConfigChange>,
+}
+
+/// 写入新的用户层，立即重载并把变化广播给所有窗口
+fn apply_user_layer(app: &AppHandle, user: Value) -> Result<LayeredConfig, ConfigWriteError> {
+    persist::save_user_layer(user)?;
+    let changes = reload::reload(app).map_err(|message| ConfigWriteError::Io { message })?;
+    log::info!("User config saved, {} field(s) changed", changes.len());
+    Ok(layers::current())
+}
+
+/// 返回当前配置及每个字段的来源层
+#[tauri::command]
+pub fn get_app_config() -> LayeredConfig {
+    layers::current()
+}
+
+/// get_config_diagnostics 的返回值
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigDiagnostics {
+    /// 解析错误，出错的配置段已回退为默认值
+    pub errors: Vec<ConfigError>,
+    /// 语义校验问题，对应的值已被钳制或回退
+    pub issues: Vec<ConfigIssue>,
+}
+
+/// 返回当前配置加载时遇到的问题（均为空表示全部正常）
+#[tauri::command]
+pub fn get_config_diagnostics() -> ConfigDiagnostics {
+    let current = layers::current();
+    ConfigDiagnostics {
+        errors: current.diagnostics,
+        issues: current.issues,
+    }
+}
+
+/// 用完整文档替换用户配置层
+#[tauri::command]
+pub fn set_app_config(app: AppHandle, config: Value) -> Result<LayeredConfig, ConfigWriteError> {
+    apply_user_layer(&app, config)
+}
+
+/// 把部分 JSON 深度合并进用户配置层
+#[tauri::command]
+pub fn patch_app_config(app: AppHandle, patch: Value) -> Result<LayeredConfig, ConfigWriteError> {
+    let mut user = layers::user_layer();
+    if !patch.is_object() {
+        return Err(ConfigWriteError::Invalid {
+            errors: vec![persist::FieldError {
+                path: String::new(),
+                message: "config patch must be a JSON object".to_string(),
+            }],
+        });
+    }
+    layers::deep_merge(&mut user, patch);
+    apply_user_layer(&app, user)
+}
@@ -0,0 +1,273 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+
+use super::layers::{self, is_within, join_path};
+use super::migrate;
+use super::validate::Severity;
+use super::AppConfigData;
+
+/// 单个字段的校验错误
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub path: String,
+    pub message: String,
+}
+
+/// 写入配置失败的原因
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConfigWriteError {
+    /// 补丁内容不合法，errors 中逐字段列出
+    Invalid { errors: Vec<FieldError> },
+    /// 配置目录未初始化或文件写入失败
+    Io { message: String },
+    /// 导入文件无法解析、版本不支持或校验和不匹配
+    Bundle { message: String },
+}
+
+impl std::fmt::Display for ConfigWriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid { errors } => {
+                let fields: Vec<String> =
+                    errors.iter().map(|e| format!("{}: {}", e.path, e.message)).collect();
+                write!(f, "invalid config: {}", fields.join("; "))
+            }
+            Self::Io { message } | Self::Bundle { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl ConfigWriteError {
+    pub(super) fn io(message: impl Into<String>) -> Self {
+        Self::Io {
+            message: message.into(),
+        }
+    }
+}
+
+/// 单个叶子字段的变更
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChange {
+    pub path: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// 校验新的用户层：拒绝未知字段，并确认与默认值合并后能反序列化为 AppConfigData
+pub fn validate_user_layer(user: &Value) -> Result<(), Vec<FieldError>> {
+    let defaults = layers::default_value();
+    let mut errors = Vec::new();
+
+    if !user.is_object() {
+        errors.push(FieldError {
+            path: String::new(),
+            message: "config patch must be a JSON object".to_string(),
+        });
+        return Err(errors);
+    }
+    check_unknown_fields(user, &defaults, "", &mut errors);
+    check_locked(user, &mut errors);
+
+    let mut merged = defaults;
+    layers::deep_merge(&mut merged, user.clone());
+    let (parsed, section_errors) = layers::parse_config(&merged, AppConfigData::default());
+    errors.extend(
+        section_errors
+            .into_iter()
+            .map(|(path, message)| FieldError { path, message }),
+    );
+    // 语义校验：Error 级别直接拒绝，Warning 级别允许写入，加载时自动钳制
+    errors.extend(
+        parsed
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .map(|issue| FieldError {
+                path: issue.path,
+                message: issue.message,
+            }),
+    );
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn check_unknown_fields(value: &Value, schema: &Value, path: &str, errors: &mut Vec<FieldError>) {
+    let (Value::Object(map), Value::Object(schema_map)) = (value, schema) else {
+        return;
+    };
+    // 内部带标签的枚举（如 placement.monitor）各变体的字段不同，默认值只是其中一个变体，交给反序列化检查
+    if schema_map.get("type").is_some_and(Value::is_string) {
+        return;
+    }
+    for (key, child) in map {
+        // 顶层 "$schema" 仅供编辑器使用
+        if path.is_empty() && key == "$schema" {
+            continue;
+        }
+        let child_path = join_path(path, key);
+        match schema_map.get(key) {
+            Some(schema_child) => check_unknown_fields(child, schema_child, &child_path, errors),
+            None => errors.push(FieldError {
+                path: child_path,
+                message: "unknown field".to_string(),
+            }),
+        }
+    }
+}
+
+/// 拒绝修改被策略锁定的字段；与当前生效值或原用户层相同的值不算修改
+fn check_locked(user: &Value, errors: &mut Vec<FieldError>) {
+    let current = layers::current();
+    if current.locked.is_empty() {
+        return;
+    }
+    let mut user_leaves = BTreeMap::new();
+    let mut effective = BTreeMap::new();
+    let mut previous = BTreeMap::new();
+    flatten(user, "", &mut user_leaves);
+    flatten(&serde_json::to_value(&current.config).unwrap_or(Value::Null), "", &mut effective);
+    flatten(&layers::user_layer(), "", &mut previous);
+
+    for (path, value) in user_leaves {
+        let Some(locked) = current
+            .locked
+            .iter()
+            .find(|locked| is_within(&path, locked) || is_within(locked, &path))
+        else {
+            continue;
+        };
+        if effective.get(&path) == Some(&value) || previous.get(&path) == Some(&value) {
+            continue;
+        }
+        errors.push(FieldError {
+            message: format!("\"{}\" is locked by administrator policy", locked),
+            path,
+        });
+    }
+}
+
+/// 先写临时文件并落盘，再 rename 覆盖，避免写到一半时留下残缺的配置
+pub fn write_atomic(path: &Path, value: &Value) -> Result<(), ConfigWriteError> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| ConfigWriteError::io("config path has no parent directory"))?;
+    std::fs::create_dir_all(dir)
+        .map_err(|e| ConfigWriteError::io(format!("Failed to create config dir: {}", e)))?;
+
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| ConfigWriteError::io(format!("Failed to serialize config: {}", e)))?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    let result = (|| {
+        let mut file = std::fs::File::create(&tmp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        std::fs::rename(&tmp_path, path)
+    })();
+
+    result.map_err(|e| {
+        let _ = std::fs::remove_file(&tmp_path);
+        ConfigWriteError::io(format!("Failed to write {}: {}", path.display(), e))
+    })
+}
+
+/// 校验并写入新的用户层，写入时标记为当前结构版本
+pub fn save_user_layer(mut user: Value) -> Result<(), ConfigWriteError> {
+    if let Value::Object(map) = &mut user {
+        map.insert(
+            "schema_version".to_string(),
+            Value::from(migrate::CURRENT_SCHEMA_VERSION),
+        );
+    }
+    validate_user_layer(&user).map_err(|errors| ConfigWriteError::Invalid { errors })?;
+    let paths =
+        layers::config_paths().ok_or_else(|| ConfigWriteError::io("config paths not initialized"))?;
+    write_atomic(&paths.user_file, &user)
+}
+
+/// 对比两份配置，列出所有变化的叶子字段
+pub fn diff(old: &AppConfigData, new: &AppConfigData) -> Vec<ConfigChange> {
+    let mut old_leaves = BTreeMap::new();
+    let mut new_leaves = BTreeMap::new();
+    flatten(&serde_json::to_value(old).unwrap_or(Value::Null), "", &mut old_leaves);
+    flatten(&serde_json::to_value(new).unwrap_or(Value::Null), "", &mut new_leaves);
+
+    let mut changes = Vec::new();
+    for (path, new_value) in &new_leaves {
+        let old_value = old_leaves.remove(path).unwrap_or(Value::Null);
+        if &old_value != new_value {
+            changes.push(ConfigChange {
+                path: path.clone(),
+                old: old_value,
+                new: new_value.clone(),
+            });
+        }
+    }
+    for (path, old_value) in old_leaves {
+        changes.push(ConfigChange {
+            path,
+            old: old_value,
+            new: Value::Null,
+        });
+    }
+    changes
+}
+
+fn flatten(value: &Value, path: &str, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                flatten(child, &join_path(path, key), out);
+            }
+        }
+        _ => {
+            out.insert(path.to_string(), value.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MonitorTarget;
+    use serde_json::json;
+
+    fn unknown_fields(user: &Value) -> Vec<String> {
+        let mut errors = Vec::new();
+        check_unknown_fields(user, &layers::default_value(), "", &mut errors);
+        errors.into_iter().map(|error| error.path).collect()
+    }
+
+    #[test]
+    fn named_monitor_is_accepted() {
+        let patch = json!({
+            "float_ball": { "placement": { "monitor": { "type": "named", "name": "DELL U2720Q" } } }
+        });
+        assert_eq!(unknown_fields(&patch), Vec::<String>::new());
+
+        let mut merged = layers::default_value();
+        layers::deep_merge(&mut merged, patch);
+        let (config, errors) = layers::parse_config(&merged, AppConfigData::default());
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(
+            config.float_ball.placement.monitor,
+            MonitorTarget::Named {
+                name: "DELL U2720Q".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_fields_are_reported() {
+        let patch = json!({ "$schema": "./schema.json", "float_ball": { "colour": "red" }, "extra": 1 });
+        assert_eq!(unknown_fields(&patch), vec!["extra", "float_ball.colour"]);
+    }
+}
@@ -0,0 +1,131 @@
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use std::ffi::OsStr;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::persist::{self, ConfigChange};
+use super::servers;
+use super::{layers, AppConfigData, ConfigChangedPayload};
+
+/// 文件变化后的去抖时间
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 持有文件监听器，放入 Tauri managed state 以保证应用运行期间不被释放
+pub struct ConfigWatcher {
+    _debouncer: Mutex<Debouncer<RecommendedWatcher>>,
+}
+
+/// 重新加载配置；失败时保留旧配置并返回错误
+pub fn reload(app: &AppHandle) -> Result<Vec<ConfigChange>, String> {
+    let new = layers::try_load_layered().map_err(|errors| {
+        errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ")
+    })?;
+    let old = layers::replace_current(new.clone());
+
+    let changes = persist::diff(&old.config, &new.config);
+    if changes.is_empty() {
+        return Ok(changes);
+    }
+
+    log::info!("Config reloaded, {} field(s) changed", changes.len());
+    apply_changes(app, &new.config, &changes);
+    servers::notify_switch(app, &old.config, &new.config);
+    if let Err(e) = app.emit(
+        "config:changed",
+        ConfigChangedPayload {
+            changes: changes.clone(),
+        },
+    ) {
+        log::warn!("Failed to emit config:changed: {}", e);
+    }
+    Ok(changes)
+}
+
+/// 把变化的配置段重新应用到窗口、托盘和悬浮球
+fn apply_changes(app: &AppHandle, config: &AppConfigData, changes: &[ConfigChange]) {
+    let touched = |key: &str| {
+        changes
+            .iter()
+            .any(|c| c.path == key || c.path.starts_with(&format!("{}.", key)))
+    };
+
+    if touched("window") {
+        if let Some(window) = app.get_webview_window("main") {
+            crate::window::apply_window_config(&window, &config.window);
+            log::info!("Main window config re-applied");
+        }
+    }
+
+    // 复选菜单项的勾选状态来自其绑定的配置项，这些配置项变化时也要重建菜单
+    let check_changed = config.tray.bound_settings().into_iter().any(&touched);
+    if touched("tray") || touched("name") || check_changed {
+        if let Err(e) = crate::tray::apply_config(app, config) {
+            log::error!("Failed to rebuild system tray: {:?}", e);
+        }
+    }
+
+    if touched("float_ball") {
+        let reposition = ["float_ball.default_x", "float_ball.default_y", "float_ball.placement"]
+            .iter()
+            .any(|p| touched(p));
+        crate::window::apply_float_ball_config(app, &config.float_ball, reposition);
+    }
+}
+
+/// 监听用户配置文件和本机覆盖文件，变化时去抖后热重载
+pub fn watch(app: &AppHandle) -> Result<(), String> {
+    let paths = layers::config_paths().ok_or("config paths not initialized")?;
+    let files = [&paths.user_file, &paths.machine_file, &paths.policy_file];
+
+    let handle = app.clone();
+    let mut debouncer = new_debouncer(RELOAD_DEBOUNCE, move |result: DebounceEventResult| {
+        match result {
+            Ok(events) => {
+                // 只关心配置文件和策略文件，目录里的其他文件（包括写入时的 .tmp）一律忽略
+                let watched = [
+                    Some(OsStr::new(layers::USER_CONFIG_FILE)),
+                    Some(OsStr::new(layers::POLICY_FILE)),
+                ];
+                if !events.iter().any(|e| watched.contains(&e.path.file_name())) {
+                    return;
+                }
+                if let Err(e) = reload(&handle) {
+                    log::error!("Failed to reload config, keeping previous: {}", e);
+                }
+            }
+            Err(e) => log::warn!("Config watcher error: {:?}", e),
+        }
+    })
+    .map_err(|e| e.to_string())?;
+
+    // 监听所在目录而不是文件本身：原子写入会 rename 替换文件
+    let mut watched_dirs = Vec::new();
+    for file in files {
+        let Some(dir) = file.parent() else {
+            continue;
+        };
+        if file == &paths.user_file {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        // 本机覆盖文件和策略文件位于同一目录
+        if !dir.is_dir() || watched_dirs.contains(&dir) {
+            continue;
+        }
+        watched_dirs.push(dir);
+        match debouncer.watcher().watch(dir, RecursiveMode::NonRecursive) {
+            Ok(()) => log::info!("Watching config dir: {}", dir.display()),
+            Err(e) => log::warn!("Failed to watch {}: {}", dir.display(), e),
+        }
+    }
+
+    app.manage(ConfigWatcher {
+        _debouncer: Mutex::new(debouncer),
+    });
+    Ok(())
+}
@@ -0,0 +1,312 @@
+//! 由 Rust 配置结构生成 app.config.json（取 `AppConfigData::default()`）、它的 JSON Schema
+//! 和前端 TypeScript 类型。Rust 结构与默认值是唯一来源，这些文件都不手动修改。
+//!
+//! 生成：`cargo run --example config_schema`；提交的文件过期时 `cargo test` 会失败
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Write;
+use std::io;
+
+use super::AppConfigData;
+
+/// 内置默认配置文件相对 src-tauri 的路径
+pub const DEFAULTS_PATH: &str = "app.config.json";
+/// JSON Schema 文件相对 src-tauri 的路径
+pub const SCHEMA_PATH: &str = "schemas/app.config.schema.json";
+/// TypeScript 类型文件相对 src-tauri 的路径
+pub const TYPESCRIPT_PATH: &str = "../src/types/config.generated.ts";
+
+/// 所有生成文件：(相对 src-tauri 的路径, 内容)
+pub fn outputs() -> [(&'static str, String); 3] {
+    [
+        (DEFAULTS_PATH, defaults_json()),
+        (SCHEMA_PATH, json_schema()),
+        (TYPESCRIPT_PATH, typescript()),
+    ]
+}
+
+#[derive(Serialize)]
+struct DefaultsFile {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    #[serde(flatten)]
+    config: AppConfigData,
+}
+
+/// 由 Rust 默认值生成 app.config.json（带 "$schema" 引用、换行结尾的格式化文本）
+pub fn defaults_json() -> String {
+    let file = DefaultsFile {
+        schema: "./schemas/app.config.schema.json",
+        config: AppConfigData::default(),
+    };
+    let mut out = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, WholeNumbers::default());
+    file.serialize(&mut serializer)
+        .expect("default config is serializable");
+    let mut out = String::from_utf8(out).expect("serde_json writes UTF-8");
+    out.push('\n');
+    out
+}
+
+/// 缩进格式，整数值的浮点数写成 `1200` 而不是 `1200.0`
+#[derive(Default)]
+struct WholeNumbers(serde_json::ser::PrettyFormatter<'static>);
+
+impl serde_json::ser::Formatter for WholeNumbers {
+    fn write_f64<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: f64) -> io::Result<()> {
+        if value.fract() == 0.0 && value.abs() < 1e15 {
+            write!(writer, "{}", value as i64)
+        } else {
+            self.0.write_f64(writer, value)
+        }
+    }
+
+    fn begin_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.begin_array(writer)
+    }
+
+    fn end_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_array(writer)
+    }
+
+    fn begin_array_value<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        self.0.begin_array_value(writer, first)
+    }
+
+    fn end_array_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_array_value(writer)
+    }
+
+    fn begin_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.begin_object(writer)
+    }
+
+    fn end_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_object(writer)
+    }
+
+    fn begin_object_key<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        self.0.begin_object_key(writer, first)
+    }
+
+    fn begin_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.begin_object_value(writer)
+    }
+
+    fn end_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.0.end_object_value(writer)
+    }
+}
+
+/// 完整描述解析后配置的 Schema（所有字段必填）
+fn full_schema() -> Value {
+    serde_json::to_value(schemars::schema_for!(AppConfigData)).expect("config schema is serializable")
+}
+
+/// 生成 app.config.json 的 JSON Schema（带换行结尾的格式化文本）。
+/// 每一层配置文件都只写需要覆盖的字段，因此去掉所有 required 约束
+pub fn json_schema() -> String {
+    let mut schema = full_schema();
+    strip_required(&mut schema);
+
+    // 允许配置文件通过 "$schema" 引用本文件以获得编辑器提示
+    if let Some(props) = schema.get_mut("properties").and_then(Value::as_object_mut) {
+        props.insert(
+            "$schema".to_string(),
+            serde_json::json!({ "type": "string" }),
+        );
+    }
+
+    let mut out = serde_json::to_string_pretty(&schema).expect("config schema is serializable");
+    out.push('\n');
+    out
+}
+
+fn strip_required(schema: &mut Value) {
+    match schema {
+        Value::Object(map) => {
+            map.remove("required");
+            map.values_mut().for_each(strip_required);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_required),
+        _ => {}
+    }
+}
+
+/// 生成与 Rust 结构一一对应的 TypeScript 类型（字段保持 snake_case）
+pub fn typescript() -> String {
+    let schema = full_schema();
+    let mut out = String::new();
+    out.push_str("// 此文件由 src-tauri/examples/config_schema.rs 生成，请勿手动修改\n");
+    out.push_str("// 与 src-tauri/src/config 中的 Rust 结构一一对应（snake_case）\n");
+
+    let root_name = schema
+        .get("title")
+        .and_then(Value::as_str)
+        .unwrap_or("AppConfigData")
+        .to_string();
+    write_declaration(&mut out, &root_name, &schema);
+
+    if let Some(definitions) = schema.get("definitions").and_then(Value::as_object) {
+        for (name, definition) in definitions {
+            write_declaration(&mut out, name, definition);
+        }
+    }
+    out
+}
+
+fn write_declaration(out: &mut String, name: &str, schema: &Value) {
+    out.push('\n');
+    write_doc(out, schema, "");
+    if schema.get("properties").is_some() {
+        let _ = writeln!(out, "export interface {} {{", name);
+        write_properties(out, schema, "  ");
+        out.push_str("}\n");
+    } else {
+        let _ = writeln!(out, "export type {} = {};", name, ts_type(schema));
+    }
+}
+
+fn write_properties(out: &mut String, schema: &Value, indent: &str) {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return;
+    };
+    for (key, property) in properties {
+        write_doc(out, property, indent);
+        let optional = if required.contains(&key.as_str()) { "" } else { "?" };
+        let key = if key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            key.clone()
+        } else {
+            format!("\"{}\"", key)
+        };
+        let _ = writeln!(out, "{}{}{}: {};", indent, key, optional, ts_type(property));
+    }
+}
+
+fn write_doc(out: &mut String, schema: &Value, indent: &str) {
+    if let Some(description) = schema.get("description").and_then(Value::as_str) {
+        let _ = writeln!(out, "{}/** {} */", indent, description.replace('\n', " "));
+    }
+}
+
+fn ts_type(schema: &Value) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference.rsplit('/').next().unwrap_or("unknown").to_string();
+    }
+    if let Some(value) = schema.get("const") {
+        return value.to_string();
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return union(values.iter().map(Value::to_string));
+    }
+    for key in ["anyOf", "oneOf"] {
+        if let Some(variants) = schema.get(key).and_then(Value::as_array) {
+            return union(variants.iter().map(ts_type));
+        }
+    }
+    if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+        return all.iter().map(ts_type).collect::<Vec<_>>().join(" & ");
+    }
+
+    match schema.get("type") {
+        Some(Value::Array(types)) => union(
+            types
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|t| instance_type(t, schema)),
+        ),
+        Some(Value::String(t)) => instance_type(t, schema),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn instance_type(instance: &str, schema: &Value) -> String {
+    match instance {
+        "string" => "string".to_string(),
+        "integer" | "number" => "number".to_string(),
+        "boolean" => "boolean".to_string(),
+        "null" => "null".to_string(),
+        "array" => {
+            let item = schema.get("items").map(ts_type).unwrap_or_else(|| "unknown".to_string());
+            if item.contains(' ') {
+                format!("({})[]", item)
+            } else {
+                format!("{}[]", item)
+            }
+        }
+        "object" => {
+            if schema.get("properties").is_some() {
+                let mut inline = String::from("{\n");
+                write_properties(&mut inline, schema, "    ");
+                inline.push_str("  }");
+                inline
+            } else {
+                let value = match schema.get("additionalProperties") {
+                    Some(Value::Object(_)) => ts_type(&schema["additionalProperties"]),
+                    _ => "unknown".to_string(),
+                };
+                format!("Record<string, {}>", value)
+            }
+        }
+        _ => "unknown".to_string(),
+    }
+}
+
+fn union(parts: impl Iterator<Item = String>) -> String {
+    let mut seen: Vec<String> = Vec::new();
+    for part in parts {
+        if !seen.contains(&part) {
+            seen.push(part);
+        }
+    }
+    seen.join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn generated_files_are_up_to_date() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let stale: Vec<&str> = outputs()
+            .iter()
+            .filter(|(relative, content)| {
+                std::fs::read_to_string(root.join(relative)).unwrap_or_default() != *content
+            })
+            .map(|(relative, _)| *relative)
+            .collect();
+        assert!(
+            stale.is_empty(),
+            "out of date with the Rust config: {:?}; run `cargo run --example config_schema` in src-tauri",
+            stale
+        );
+    }
+
+    #[test]
+    fn defaults_json_parses_back_to_the_defaults() {
+        let value: Value = serde_json::from_str(&defaults_json()).unwrap();
+        let mut config = value.as_object().unwrap().clone();
+        config.remove("$schema");
+        let parsed: AppConfigData = serde_json::from_value(Value::Object(config)).unwrap();
+        assert_eq!(
+            serde_json::to_value(parsed).unwrap(),
+            serde_json::to_value(AppConfigData::default()).unwrap()
+        );
+    }
+}
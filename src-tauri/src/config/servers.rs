@@ -0,0 +1,218 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_http::reqwest;
+
+use super::persist::{ConfigWriteError, FieldError};
+use super::{patch_app_config, AppConfigData, LayeredConfig, ServerProfile, ServersConfig};
+
+/// server:switched 事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerSwitchedPayload {
+    pub name: String,
+    pub base_url: String,
+    /// 切换前的服务端配置名
+    pub previous: Option<String>,
+}
+
+/// test_server 的返回值
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerTestResult {
+    pub name: String,
+    /// 是否收到了 HTTP 响应（不论状态码）
+    pub reachable: bool,
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+/// 按服务端配置构建 HTTP 客户端（超时、证书选项）
+pub(crate) fn client_for(profile: &ServerProfile) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder()
+        .timeout(Duration::from_millis(profile.timeout_ms))
+        .danger_accept_invalid_certs(profile.tls.accept_invalid_certs);
+    if let Some(path) = &profile.tls.ca_cert {
+        let pem = std::fs::read(path).map_err(|e| format!("Failed to read CA cert {}: {}", path, e))?;
+        let cert = reqwest::Certificate::from_pem(&pem)
+            .map_err(|e| format!("Invalid CA cert {}: {}", path, e))?;
+        builder = builder.add_root_certificate(cert);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// 当前服务端（名称或地址）变化时通知所有窗口重新认证
+pub(super) fn notify_switch(app: &AppHandle, old: &AppConfigData, new: &AppConfigData) {
+    let Some(active) = new.servers.active_profile() else {
+        return;
+    };
+    let previous = old.servers.active_profile();
+    if previous.is_some_and(|p| p.name == active.name && p.base_url == active.base_url) {
+        return;
+    }
+
+    log::info!("Switched server to {} ({})", active.name, active.base_url);
+    let payload = ServerSwitchedPayload {
+        name: active.name.clone(),
+        base_url: active.base_url.clone(),
+        previous: previous.map(|p| p.name.clone()),
+    };
+    if let Err(e) = app.emit("server:switched", payload) {
+        log::warn!("Failed to emit server:switched: {}", e);
+    }
+}
+
+fn invalid(path: &str, message: String) -> ConfigWriteError {
+    ConfigWriteError::Invalid {
+        errors: vec![FieldError {
+            path: path.to_string(),
+            message,
+        }],
+    }
+}
+
+/// 返回所有服务端配置及当前使用的配置名
+#[tauri::command]
+pub fn list_servers() -> ServersConfig {
+    AppConfigData::load().servers
+}
+
+/// 新增服务端配置写入用户层的补丁（名称不能与已有的重复）
+fn add_patch(mut servers: ServersConfig, profile: ServerProfile) -> Result<Value, ConfigWriteError> {
+    if servers.get(&profile.name).is_some() {
+        return Err(invalid(
+            "servers.profiles",
+            format!("server \"{}\" already exists", profile.name),
+        ));
+    }
+    servers.profiles.push(profile);
+    // 数组整体覆盖，需要写入完整列表
+    Ok(json!({ "servers": { "profiles": servers.profiles } }))
+}
+
+/// 切换当前服务端写入用户层的补丁（只能切换到已有的配置）
+fn switch_patch(servers: &ServersConfig, name: &str) -> Result<Value, ConfigWriteError> {
+    if servers.get(name).is_none() {
+        return Err(invalid("servers.active", format!("unknown server \"{}\"", name)));
+    }
+    Ok(json!({ "servers": { "active": name } }))
+}
+
+/// 新增服务端配置（名称不能与已有的重复）
+#[tauri::command]
+pub fn add_server(app: AppHandle, profile: ServerProfile) -> Result<LayeredConfig, ConfigWriteError> {
+    let patch = add_patch(AppConfigData::load().servers, profile)?;
+    patch_app_config(app, patch)
+}
+
+/// 切换当前服务端，成功后广播 server:switched
+#[tauri::command]
+pub fn switch_server(app: AppHandle, name: String) -> Result<LayeredConfig, ConfigWriteError> {
+    let patch = switch_patch(&AppConfigData::load().servers, &name)?;
+    patch_app_config(app, patch)
+}
+
+/// 测试服务端连通性；name 为空时测试当前服务端
+#[tauri::command]
+pub async fn test_server(name: Option<String>) -> Result<ServerTestResult, String> {
+    let servers = AppConfigData::load().servers;
+    let profile = match &name {
+        Some(name) => servers.get(name),
+        None => servers.active_profile(),
+    }
+    .cloned()
+    .ok_or_else(|| format!("Server not found: {}", name.unwrap_or(servers.active.clone())))?;
+
+    let client = client_for(&profile)?;
+    let started = Instant::now();
+    let response = client.get(&profile.base_url).send().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    Ok(match response {
+        Ok(response) => ServerTestResult {
+            name: profile.name,
+            reachable: true,
+            status: Some(response.status().as_u16()),
+            latency_ms,
+            error: None,
+        },
+        Err(e) => ServerTestResult {
+            name: profile.name,
+            reachable: false,
+            status: None,
+            latency_ms,
+            error: Some(e.to_string()),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{layers, persist};
+
+    fn servers(names: &[&str]) -> ServersConfig {
+        let mut servers = AppConfigData::default().servers;
+        let template = servers.profiles[0].clone();
+        servers.profiles = names
+            .iter()
+            .map(|name| ServerProfile {
+                name: name.to_string(),
+                base_url: format!("https://{}.example.com", name),
+                ..template.clone()
+            })
+            .collect();
+        servers.active = names[0].to_string();
+        servers
+    }
+
+    fn error_paths(error: ConfigWriteError) -> Vec<String> {
+        match error {
+            ConfigWriteError::Invalid { errors } => errors.into_iter().map(|e| e.path).collect(),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn duplicate_server_names_are_rejected() {
+        let mut profile = servers(&["home"]).profiles.remove(0);
+        profile.base_url = "https://other.example.com".to_string();
+        let error = add_patch(servers(&["home", "work"]), profile).unwrap_err();
+        assert_eq!(error_paths(error), ["servers.profiles"]);
+
+        let patch = add_patch(servers(&["home"]), servers(&["work"]).profiles.remove(0)).unwrap();
+        let names: Vec<&str> = patch["servers"]["profiles"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|profile| profile["name"].as_str())
+            .collect();
+        assert_eq!(names, ["home", "work"]);
+    }
+
+    #[test]
+    fn switching_to_an_unknown_server_is_rejected() {
+        let error = switch_patch(&servers(&["home"]), "missing").unwrap_err();
+        assert_eq!(error_paths(error), ["servers.active"]);
+    }
+
+    #[test]
+    fn switching_only_changes_the_active_server_in_the_user_layer() {
+        let mut user = json!({
+            "servers": { "profiles": servers(&["home", "work"]).profiles },
+            "float_ball": { "enabled": false },
+        });
+        let patch = switch_patch(&servers(&["home", "work"]), "work").unwrap();
+        layers::deep_merge(&mut user, patch);
+        assert_eq!(user["servers"]["active"], "work");
+        assert_eq!(user["servers"]["profiles"].as_array().unwrap().len(), 2);
+        assert_eq!(user["float_ball"]["enabled"], false);
+        persist::validate_user_layer(&user).unwrap();
+
+        let mut merged = layers::default_value();
+        layers::deep_merge(&mut merged, user);
+        let (config, errors) = layers::parse_config(&merged, AppConfigData::default());
+        assert!(errors.is_empty());
+        assert_eq!(config.servers.active_profile().unwrap().base_url, "https://work.example.com");
+    }
+}
@@ -0,0 +1,885 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use tauri::utils::acl::Identifier;
+use tauri_plugin_global_shortcut::Shortcut;
+
+use super::{AppConfigData, MonitorTarget, TrayAction, TrayMenuEntry, WindowPlacement};
+
+/// 同时上传数上限
+pub const MAX_CONCURRENT_UPLOADS: u32 = 10;
+/// 分片大小范围（字节）
+const MIN_CHUNK_SIZE: u64 = 256 * 1024;
+const MAX_CHUNK_SIZE: u64 = 100 * 1024 * 1024;
+/// 单个分片重试次数上限
+const MAX_CHUNK_RETRIES: u32 = 10;
+/// 最近上传记录数上限
+pub const MAX_RECENT_UPLOADS: u32 = 50;
+/// 悬停展开延迟上限（毫秒）
+pub const MAX_HOVER_DELAY: u64 = 10_000;
+/// 主窗口 label，由 tauri.conf.json 创建
+const MAIN_WINDOW: &str = "main";
+/// 服务端超时取值非法时使用的默认值（毫秒）
+const DEFAULT_SERVER_TIMEOUT_MS: u64 = 30_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// 取值可疑，已自动修正到合法范围
+    Warning,
+    /// 取值无法使用，已回退为默认值（写入时直接拒绝）
+    Error,
+}
+
+/// 语义校验发现的问题
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    pub path: String,
+    pub message: String,
+}
+
+impl AppConfigData {
+    /// 只检查不修改，返回所有问题
+    pub fn validate(&self) -> Vec<ConfigIssue> {
+        self.clone().sanitize(&AppConfigData::default())
+    }
+
+    /// 检查并修正：Warning 级别的值被钳制到合法范围，Error 级别的值回退为 defaults 中的值
+    pub fn sanitize(&mut self, defaults: &AppConfigData) -> Vec<ConfigIssue> {
+        let mut issues = Issues::default();
+
+        // 主窗口
+        let window = &mut self.window;
+        let window_defaults = &defaults.window;
+        for (name, value, default) in [
+            ("width", &mut window.width, window_defaults.width),
+            ("height", &mut window.height, window_defaults.height),
+        ] {
+            if !is_positive(*value) {
+                issues.error(format!("window.{}", name), format!("must be > 0, got {}", value));
+                *value = default;
+            }
+        }
+        for (axis, min, max) in [
+            ("width", &mut window.min_width, &mut window.max_width),
+            ("height", &mut window.min_height, &mut window.max_height),
+        ] {
+            if let (Some(min_v), Some(max_v)) = (*min, *max) {
+                if min_v > max_v {
+                    issues.warning(
+                        format!("window.max_{}", axis),
+                        format!("max_{0} {1} < min_{0} {2}, raised to min_{0}", axis, max_v, min_v),
+                    );
+                    *max = Some(min_v);
+                }
+            }
+        }
+        for (axis, value, min, max) in [
+            ("width", &mut window.width, window.min_width, window.max_width),
+            ("height", &mut window.height, window.min_height, window.max_height),
+        ] {
+            let clamped = value.max(min.unwrap_or(0.0)).min(max.unwrap_or(f64::MAX));
+            if clamped != *value {
+                issues.warning(
+                    format!("window.{}", axis),
+                    format!("{} is outside min/max, clamped to {}", value, clamped),
+                );
+                *value = clamped;
+            }
+        }
+
+        // 窗口注册表：label 非空且唯一、地址为前端路由或本地页面、尺寸为正，否则整项丢弃
+        let mut labels = HashSet::new();
+        let mut index = 0;
+        self.windows.retain_mut(|window| {
+            let path = format!("windows[{}]", index);
+            index += 1;
+            let problem = if window.label.trim().is_empty() {
+                Some(("label", "window label must not be empty".to_string()))
+            } else if window.label == MAIN_WINDOW {
+                Some(("label", "main window is defined in tauri.conf.json".to_string()))
+            } else if !labels.insert(window.label.clone()) {
+                Some(("label", format!("duplicate window label \"{}\"", window.label)))
+            } else if !is_window_route(&window.route) {
+                Some((
+                    "route",
+                    format!(
+                        "must be a route starting with \"/\" or a local .html page, got \"{}\"",
+                        window.route
+                    ),
+                ))
+            } else if !is_positive(window.width) || !is_positive(window.height) {
+                Some((
+                    "width",
+                    format!("size must be > 0, got {}x{}", window.width, window.height),
+                ))
+            } else {
+                None
+            };
+            if let Some((field, message)) = problem {
+                issues.error(format!("{}.{}", path, field), format!("{}, window dropped", message));
+                return false;
+            }
+            if let Some(placement) = &mut window.placement {
+                sanitize_placement(placement, &format!("{}.placement", path), &mut issues);
+            }
+            window.capabilities.retain(|permission| {
+                let valid = Identifier::try_from(permission.clone()).is_ok();
+                if !valid {
+                    issues.warning(
+                        format!("{}.capabilities", path),
+                        format!("invalid permission identifier \"{}\" removed", permission),
+                    );
+                }
+                valid
+            });
+            true
+        });
+
+        // 托盘菜单：id 必须非空且全局唯一，动作参数和绑定的配置项必须可用，否则菜单项被丢弃
+        let settings = serde_json::to_value(&*self).unwrap_or_default();
+        sanitize_menu(
+            &mut self.tray.menus,
+            "tray.menus",
+            &settings,
+            &mut HashSet::new(),
+            &mut issues,
+        );
+        let recent = &mut self.tray.recent_uploads;
+        let limit = recent.limit.clamp(1, MAX_RECENT_UPLOADS);
+        if limit != recent.limit {
+            issues.warning(
+                "tray.recent_uploads.limit".to_string(),
+                format!(
+                    "must be between 1 and {}, got {}, clamped to {}",
+                    MAX_RECENT_UPLOADS, recent.limit, limit
+                ),
+            );
+            recent.limit = limit;
+        }
+
+        // 悬浮球
+        let float_ball = &mut self.float_ball;
+        let float_defaults = &defaults.float_ball;
+        for (name, value, default) in [
+            ("width", &mut float_ball.width, float_defaults.width),
+            ("height", &mut float_ball.height, float_defaults.height),
+            ("panel.width", &mut float_ball.panel.width, float_defaults.panel.width),
+            ("panel.height", &mut float_ball.panel.height, float_defaults.panel.height),
+        ] {
+            if !is_positive(*value) {
+                issues.error(format!("float_ball.{}", name), format!("must be > 0, got {}", value));
+                *value = default;
+            }
+        }
+        sanitize_placement(&mut float_ball.placement, "float_ball.placement", &mut issues);
+        if float_ball.panel.hover_delay > MAX_HOVER_DELAY {
+            issues.warning(
+                "float_ball.panel.hover_delay".to_string(),
+                format!(
+                    "{}ms is too long, clamped to {}ms",
+                    float_ball.panel.hover_delay, MAX_HOVER_DELAY
+                ),
+            );
+            float_ball.panel.hover_delay = MAX_HOVER_DELAY;
+        }
+
+        // 上传
+        let upload = &mut float_ball.upload;
+        let upload_defaults = &float_defaults.upload;
+        if upload.api_url.trim().is_empty() {
+            issues.error(
+                "float_ball.upload.api_url".to_string(),
+                "must not be empty".to_string(),
+            );
+            upload.api_url = upload_defaults.api_url.clone();
+        }
+        if upload.max_file_size == 0 {
+            issues.error(
+                "float_ball.upload.max_file_size".to_string(),
+                "must be > 0".to_string(),
+            );
+            upload.max_file_size = upload_defaults.max_file_size;
+        }
+        let concurrent = upload.concurrent.clamp(1, MAX_CONCURRENT_UPLOADS);
+        if concurrent != upload.concurrent {
+            issues.warning(
+                "float_ball.upload.concurrent".to_string(),
+                format!(
+                    "must be between 1 and {}, got {}, clamped to {}",
+                    MAX_CONCURRENT_UPLOADS, upload.concurrent, concurrent
+                ),
+            );
+            upload.concurrent = concurrent;
+        }
+        let chunk = &mut upload.chunk;
+        let chunk_defaults = &upload_defaults.chunk;
+        for (name, url, default) in [
+            ("init_url", &mut chunk.init_url, &chunk_defaults.init_url),
+            ("chunk_url", &mut chunk.chunk_url, &chunk_defaults.chunk_url),
+            ("complete_url", &mut chunk.complete_url, &chunk_defaults.complete_url),
+        ] {
+            if url.trim().is_empty() {
+                issues.error(
+                    format!("float_ball.upload.chunk.{}", name),
+                    "must not be empty".to_string(),
+                );
+                *url = default.clone();
+            }
+        }
+        let chunk_size = chunk.chunk_size.clamp(MIN_CHUNK_SIZE, MAX_CHUNK_SIZE);
+        if chunk_size != chunk.chunk_size {
+            issues.warning(
+                "float_ball.upload.chunk.chunk_size".to_string(),
+                format!(
+                    "must be between {} and {} bytes, got {}, clamped to {}",
+                    MIN_CHUNK_SIZE, MAX_CHUNK_SIZE, chunk.chunk_size, chunk_size
+                ),
+            );
+            chunk.chunk_size = chunk_size;
+        }
+        if chunk.max_file_size == 0 {
+            issues.error(
+                "float_ball.upload.chunk.max_file_size".to_string(),
+                "must be > 0".to_string(),
+            );
+            chunk.max_file_size = chunk_defaults.max_file_size;
+        }
+        if chunk.retries > MAX_CHUNK_RETRIES {
+            issues.warning(
+                "float_ball.upload.chunk.retries".to_string(),
+                format!("{} is too many, clamped to {}", chunk.retries, MAX_CHUNK_RETRIES),
+            );
+            chunk.retries = MAX_CHUNK_RETRIES;
+        }
+        let instant = &mut upload.instant;
+        let instant_defaults = &upload_defaults.instant;
+        for (name, url, default) in [
+            ("check_url", &mut instant.check_url, &instant_defaults.check_url),
+            ("instant_url", &mut instant.instant_url, &instant_defaults.instant_url),
+        ] {
+            if url.trim().is_empty() {
+                issues.error(
+                    format!("float_ball.upload.instant.{}", name),
+                    "must not be empty".to_string(),
+                );
+                *url = default.clone();
+            }
+        }
+
+        // 服务端：名称非空且唯一、地址为 http(s)，不合法的配置被丢弃
+        let servers = &mut self.servers;
+        let mut names = HashSet::new();
+        let mut index = 0;
+        servers.profiles.retain_mut(|profile| {
+            let path = format!("servers.profiles[{}]", index);
+            index += 1;
+            if profile.name.trim().is_empty() {
+                issues.error(
+                    format!("{}.name", path),
+                    "server name must not be empty, profile dropped".to_string(),
+                );
+                return false;
+            }
+            if !names.insert(profile.name.clone()) {
+                issues.error(
+                    format!("{}.name", path),
+                    format!("duplicate server name \"{}\", profile dropped", profile.name),
+                );
+                return false;
+            }
+            if !is_http_url(&profile.base_url) {
+                issues.error(
+                    format!("{}.base_url", path),
+                    format!(
+                        "must start with http:// or https://, got \"{}\", profile dropped",
+                        profile.base_url
+                    ),
+                );
+                return false;
+            }
+            if profile.timeout_ms == 0 {
+                issues.error(format!("{}.timeout_ms", path), "must be > 0".to_string());
+                profile.timeout_ms = DEFAULT_SERVER_TIMEOUT_MS;
+            }
+            true
+        });
+        if servers.profiles.is_empty() {
+            issues.error(
+                "servers.profiles".to_string(),
+                "at least one server profile is required".to_string(),
+            );
+            servers.profiles = defaults.servers.profiles.clone();
+        }
+        if servers.active_profile().is_none() {
+            if let Some(fallback) = servers.profiles.first().map(|p| p.name.clone()) {
+                issues.error(
+                    "servers.active".to_string(),
+                    format!(
+                        "unknown server \"{}\", falling back to \"{}\"",
+                        servers.active, fallback
+                    ),
+                );
+                servers.active = fallback;
+            }
+        }
+
+        issues.0
+    }
+}
+
+/// 递归检查菜单节点；菜单项问题导致丢弃，快捷键无法解析时只去掉快捷键
+fn sanitize_menu(
+    entries: &mut Vec<TrayMenuEntry>,
+    path: &str,
+    settings: &Value,
+    seen: &mut HashSet<String>,
+    issues: &mut Issues,
+) {
+    let mut index = 0;
+    entries.retain_mut(|entry| {
+        let path = format!("{}[{}]", path, index);
+        index += 1;
+        if let TrayMenuEntry::Submenu(submenu) = entry {
+            sanitize_menu(&mut submenu.items, &format!("{}.items", path), settings, seen, issues);
+            if submenu.label.trim().is_empty() {
+                issues.error(
+                    format!("{}.label", path),
+                    "submenu label must not be empty, submenu dropped".to_string(),
+                );
+                return false;
+            }
+            return true;
+        }
+        if let TrayMenuEntry::RecentUploads(recent) = entry {
+            // 内容按固定 id 动态生成，只能出现一次
+            if !seen.insert(crate::recent_uploads::MENU_ID_PREFIX.to_string()) {
+                issues.error(
+                    path,
+                    "recent_uploads may appear only once, entry dropped".to_string(),
+                );
+                return false;
+            }
+            if recent.label.trim().is_empty() {
+                issues.error(
+                    format!("{}.label", path),
+                    "submenu label must not be empty, submenu dropped".to_string(),
+                );
+                return false;
+            }
+            return true;
+        }
+
+        let (id, label, problem) = match &*entry {
+            TrayMenuEntry::Item(item) => (
+                &item.id,
+                &item.label,
+                action_problem(&item.action).map(|p| ("action", p)),
+            ),
+            TrayMenuEntry::Check(check) => (
+                &check.id,
+                &check.label,
+                setting_problem(settings, &check.setting).map(|p| ("setting", p)),
+            ),
+            _ => return true,
+        };
+        let problem = if id.trim().is_empty() {
+            Some(("id", "menu item id must not be empty".to_string()))
+        } else if id.starts_with(crate::recent_uploads::MENU_ID_PREFIX) {
+            Some((
+                "id",
+                format!("id prefix \"{}\" is reserved", crate::recent_uploads::MENU_ID_PREFIX),
+            ))
+        } else if !seen.insert(id.clone()) {
+            Some(("id", format!("duplicate menu item id \"{}\"", id)))
+        } else if label.trim().is_empty() {
+            Some(("label", "menu item label must not be empty".to_string()))
+        } else {
+            problem
+        };
+        if let Some((field, problem)) = problem {
+            issues.error(format!("{}.{}", path, field), format!("{}, item dropped", problem));
+            return false;
+        }
+
+        let accelerator = match entry {
+            TrayMenuEntry::Item(item) => &mut item.accelerator,
+            TrayMenuEntry::Check(check) => &mut check.accelerator,
+            _ => return true,
+        };
+        if let Some(key) = accelerator.as_deref() {
+            if key.parse::<Shortcut>().is_err() {
+                issues.warning(
+                    format!("{}.accelerator", path),
+                    format!("invalid accelerator \"{}\", removed", key),
+                );
+                *accelerator = None;
+            }
+        }
+        true
+    });
+}
+
+/// 边距不能为负；未填写名称的指定显示器改为主显示器
+fn sanitize_placement(placement: &mut WindowPlacement, path: &str, issues: &mut Issues) {
+    if placement.margin.is_nan() || placement.margin < 0.0 {
+        issues.warning(
+            format!("{}.margin", path),
+            format!("must be >= 0, got {}, clamped to 0", placement.margin),
+        );
+        placement.margin = 0.0;
+    }
+    if matches!(&placement.monitor, MonitorTarget::Named { name } if name.trim().is_empty()) {
+        issues.warning(
+            format!("{}.monitor.name", path),
+            "monitor name must not be empty, using the primary monitor".to_string(),
+        );
+        placement.monitor = MonitorTarget::Primary;
+    }
+}
+
+/// 复选菜单项只能绑定到已存在的布尔配置项
+fn setting_problem(settings: &Value, setting: &str) -> Option<String> {
+    let pointer = format!("/{}", setting.replace('.', "/"));
+    match settings.pointer(&pointer) {
+        Some(Value::Bool(_)) => None,
+        Some(_) => Some(format!("setting \"{}\" is not a boolean", setting)),
+        None => Some(format!("unknown setting \"{}\"", setting)),
+    }
+}
+
+/// 动作参数明显不可用时返回原因
+fn action_problem(action: &TrayAction) -> Option<String> {
+    match action {
+        TrayAction::ShowWindow { label } if label.trim().is_empty() => {
+            Some("window label must not be empty".to_string())
+        }
+        TrayAction::OpenRoute { route } if !route.starts_with('/') => {
+            Some(format!("route must start with \"/\", got \"{}\"", route))
+        }
+        TrayAction::Emit { event, .. } if event.trim().is_empty() => {
+            Some("event name must not be empty".to_string())
+        }
+        TrayAction::OpenUrl { url } if !is_http_url(url) => {
+            Some(format!("url must start with http:// or https://, got \"{}\"", url))
+        }
+        _ => None,
+    }
+}
+
+fn is_http_url(url: &str) -> bool {
+    let url = url.trim();
+    ["http://", "https://"].iter().any(|scheme| {
+        url.len() > scheme.len()
+            && url.get(..scheme.len()).is_some_and(|s| s.eq_ignore_ascii_case(scheme))
+    })
+}
+
+/// 窗口地址：hash 路由 `/xxx` 或随前端打包的 `.html` 页面（不允许外部地址）
+fn is_window_route(route: &str) -> bool {
+    if route.starts_with('/') {
+        return !route.starts_with("//");
+    }
+    let page = route.split(['?', '#']).next().unwrap_or_default();
+    page.ends_with(".html") && !page.contains(':') && !page.starts_with('.')
+}
+
+/// NaN 也视为非法
+fn is_positive(value: f64) -> bool {
+    value > 0.0
+}
+
+#[derive(Default)]
+struct Issues(Vec<ConfigIssue>);
+
+impl Issues {
+    fn warning(&mut self, path: String, message: String) {
+        self.0.push(ConfigIssue {
+            severity: Severity::Warning,
+            path,
+            message,
+        });
+    }
+
+    fn error(&mut self, path: String, message: String) {
+        self.0.push(ConfigIssue {
+            severity: Severity::Error,
+            path,
+            message,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        ServerProfile, TrayCheckItem, TrayMenuItem, TrayRecentUploads, TraySubmenu,
+    };
+
+    /// 发现的问题：(级别, 路径)
+    type Found = Vec<(Severity, String)>;
+    /// 修改配置的函数及预期的问题路径
+    type Case = (fn(&mut AppConfigData), &'static str);
+
+    /// 修改默认配置后执行 sanitize，返回修正后的配置和发现的问题
+    fn sanitized(f: impl FnOnce(&mut AppConfigData)) -> (AppConfigData, Found) {
+        let mut config = AppConfigData::default();
+        f(&mut config);
+        let issues = config.sanitize(&AppConfigData::default());
+        let issues = issues.into_iter().map(|issue| (issue.severity, issue.path)).collect();
+        (config, issues)
+    }
+
+    fn one(severity: Severity, path: &str) -> Found {
+        vec![(severity, path.to_string())]
+    }
+
+    fn item(id: &str, action: TrayAction) -> TrayMenuEntry {
+        TrayMenuEntry::Item(TrayMenuItem {
+            id: id.to_string(),
+            label: id.to_string(),
+            action,
+            icon: None,
+            accelerator: None,
+            disabled: false,
+        })
+    }
+
+    fn check(id: &str, setting: &str) -> TrayMenuEntry {
+        TrayMenuEntry::Check(TrayCheckItem {
+            id: id.to_string(),
+            label: id.to_string(),
+            setting: setting.to_string(),
+            accelerator: None,
+            disabled: false,
+        })
+    }
+
+    fn server(name: &str, base_url: &str) -> ServerProfile {
+        ServerProfile {
+            base_url: base_url.to_string(),
+            name: name.to_string(),
+            ..AppConfigData::default().servers.profiles[0].clone()
+        }
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        assert_eq!(sanitized(|_| {}).1, vec![]);
+    }
+
+    #[test]
+    fn window_size_must_be_positive() {
+        let (config, issues) = sanitized(|c| c.window.width = 0.0);
+        assert_eq!(issues, one(Severity::Error, "window.width"));
+        assert_eq!(config.window.width, 1200.0);
+
+        let (config, issues) = sanitized(|c| c.window.height = f64::NAN);
+        assert_eq!(issues, one(Severity::Error, "window.height"));
+        assert_eq!(config.window.height, 800.0);
+    }
+
+    #[test]
+    fn window_max_below_min_is_raised() {
+        let (config, issues) = sanitized(|c| c.window.max_width = Some(700.0));
+        // max 被提升到 min 后宽度超出范围，再被钳制
+        assert_eq!(
+            issues,
+            vec![
+                (Severity::Warning, "window.max_width".to_string()),
+                (Severity::Warning, "window.width".to_string()),
+            ]
+        );
+        assert_eq!(config.window.max_width, Some(800.0));
+        assert_eq!(config.window.width, 800.0);
+    }
+
+    #[test]
+    fn window_size_is_clamped_to_min_max() {
+        let (config, issues) = sanitized(|c| c.window.height = 100.0);
+        assert_eq!(issues, one(Severity::Warning, "window.height"));
+        assert_eq!(config.window.height, 600.0);
+    }
+
+    #[test]
+    fn invalid_window_definitions_are_dropped() {
+        let cases: [Case; 6] = [
+            (|c| c.windows[1].label = " ".to_string(), "windows[1].label"),
+            (|c| c.windows[1].label = "main".to_string(), "windows[1].label"),
+            (|c| c.windows[1].label = "login".to_string(), "windows[1].label"),
+            (|c| c.windows[1].route = "https://example.com".to_string(), "windows[1].route"),
+            (|c| c.windows[1].route = "../secret.html".to_string(), "windows[1].route"),
+            (|c| c.windows[1].height = 0.0, "windows[1].width"),
+        ];
+        for (f, path) in cases {
+            let (config, issues) = sanitized(f);
+            assert_eq!(issues, one(Severity::Error, path));
+            assert_eq!(config.windows.len(), 2, "{}", path);
+        }
+    }
+
+    #[test]
+    fn invalid_window_capabilities_are_removed() {
+        let (config, issues) = sanitized(|c| {
+            c.windows[0].capabilities =
+                vec!["core:window:allow-set-size".to_string(), "not a permission".to_string()];
+        });
+        assert_eq!(issues, one(Severity::Warning, "windows[0].capabilities"));
+        assert_eq!(config.windows[0].capabilities, vec!["core:window:allow-set-size"]);
+    }
+
+    #[test]
+    fn window_placement_is_sanitized() {
+        let (config, issues) = sanitized(|c| {
+            c.windows[2].placement = Some(WindowPlacement {
+                monitor: MonitorTarget::Named { name: String::new() },
+                anchor: crate::config::Anchor::Center,
+                margin: -5.0,
+            });
+        });
+        assert_eq!(
+            issues,
+            vec![
+                (Severity::Warning, "windows[2].placement.margin".to_string()),
+                (Severity::Warning, "windows[2].placement.monitor.name".to_string()),
+            ]
+        );
+        let placement = config.windows[2].placement.as_ref().unwrap();
+        assert_eq!(placement.margin, 0.0);
+        assert_eq!(placement.monitor, MonitorTarget::Primary);
+    }
+
+    #[test]
+    fn invalid_menu_items_are_dropped() {
+        let quit = || TrayAction::Quit;
+        let cases: Vec<(TrayMenuEntry, &str)> = vec![
+            (item("", quit()), "tray.menus[5].id"),
+            (item("quit", quit()), "tray.menus[5].id"),
+            (item("recent-uploads:0", quit()), "tray.menus[5].id"),
+            (
+                item("a", TrayAction::ShowWindow { label: String::new() }),
+                "tray.menus[5].action",
+            ),
+            (
+                item("a", TrayAction::OpenRoute { route: "settings".to_string() }),
+                "tray.menus[5].action",
+            ),
+            (
+                item("a", TrayAction::Emit { event: String::new(), payload: None }),
+                "tray.menus[5].action",
+            ),
+            (
+                item("a", TrayAction::OpenUrl { url: "file:///etc/passwd".to_string() }),
+                "tray.menus[5].action",
+            ),
+            (check("a", "app.missing"), "tray.menus[5].setting"),
+            (check("a", "window.width"), "tray.menus[5].setting"),
+        ];
+        for (entry, path) in cases {
+            let (config, issues) = sanitized(|c| c.tray.menus.push(entry));
+            assert_eq!(issues, one(Severity::Error, path));
+            assert_eq!(config.tray.menus.len(), 5, "{}", path);
+        }
+
+        let (_, issues) = sanitized(|c| {
+            let TrayMenuEntry::Item(item) = &mut c.tray.menus[4] else {
+                unreachable!()
+            };
+            item.label = String::new();
+        });
+        assert_eq!(issues, one(Severity::Error, "tray.menus[4].label"));
+    }
+
+    #[test]
+    fn nested_menu_items_report_their_path() {
+        let (config, issues) = sanitized(|c| {
+            let TrayMenuEntry::Submenu(submenu) = &mut c.tray.menus[1] else {
+                unreachable!()
+            };
+            submenu.items.push(item("show", TrayAction::Quit));
+        });
+        assert_eq!(issues, one(Severity::Error, "tray.menus[1].items[2].id"));
+        let TrayMenuEntry::Submenu(submenu) = &config.tray.menus[1] else {
+            unreachable!()
+        };
+        assert_eq!(submenu.items.len(), 2);
+    }
+
+    #[test]
+    fn invalid_submenus_are_dropped() {
+        let (config, issues) = sanitized(|c| {
+            c.tray.menus.push(TrayMenuEntry::Submenu(TraySubmenu {
+                label: String::new(),
+                disabled: false,
+                items: vec![],
+            }));
+        });
+        assert_eq!(issues, one(Severity::Error, "tray.menus[5].label"));
+        assert_eq!(config.tray.menus.len(), 5);
+
+        let recent = |label: &str| {
+            TrayMenuEntry::RecentUploads(TrayRecentUploads {
+                label: label.to_string(),
+            })
+        };
+        let (config, issues) = sanitized(|c| c.tray.menus.push(recent("again")));
+        assert_eq!(issues, one(Severity::Error, "tray.menus[5]"));
+        assert_eq!(config.tray.menus.len(), 5);
+
+        let (config, issues) = sanitized(|c| c.tray.menus[2] = recent(" "));
+        assert_eq!(issues, one(Severity::Error, "tray.menus[2].label"));
+        assert_eq!(config.tray.menus.len(), 4);
+    }
+
+    #[test]
+    fn invalid_accelerators_are_removed() {
+        let (config, issues) = sanitized(|c| {
+            let TrayMenuEntry::Item(item) = &mut c.tray.menus[4] else {
+                unreachable!()
+            };
+            item.accelerator = Some("Ctrl+Nope+".to_string());
+        });
+        assert_eq!(issues, one(Severity::Warning, "tray.menus[4].accelerator"));
+        let TrayMenuEntry::Item(item) = &config.tray.menus[4] else {
+            unreachable!()
+        };
+        assert_eq!(item.accelerator, None);
+    }
+
+    #[test]
+    fn recent_uploads_limit_is_clamped() {
+        let (config, issues) = sanitized(|c| c.tray.recent_uploads.limit = 0);
+        assert_eq!(issues, one(Severity::Warning, "tray.recent_uploads.limit"));
+        assert_eq!(config.tray.recent_uploads.limit, 1);
+
+        let (config, _) = sanitized(|c| c.tray.recent_uploads.limit = 1000);
+        assert_eq!(config.tray.recent_uploads.limit, MAX_RECENT_UPLOADS);
+    }
+
+    #[test]
+    fn float_ball_size_must_be_positive() {
+        let cases: [Case; 4] = [
+            (|c| c.float_ball.width = -1.0, "float_ball.width"),
+            (|c| c.float_ball.height = 0.0, "float_ball.height"),
+            (|c| c.float_ball.panel.width = 0.0, "float_ball.panel.width"),
+            (|c| c.float_ball.panel.height = f64::NAN, "float_ball.panel.height"),
+        ];
+        for (f, path) in cases {
+            let (config, issues) = sanitized(f);
+            assert_eq!(issues, one(Severity::Error, path));
+            assert!(config.validate().is_empty(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn float_ball_placement_and_hover_delay_are_clamped() {
+        let (config, issues) = sanitized(|c| c.float_ball.placement.margin = -1.0);
+        assert_eq!(issues, one(Severity::Warning, "float_ball.placement.margin"));
+        assert_eq!(config.float_ball.placement.margin, 0.0);
+
+        let (config, issues) = sanitized(|c| c.float_ball.panel.hover_delay = 60_000);
+        assert_eq!(issues, one(Severity::Warning, "float_ball.panel.hover_delay"));
+        assert_eq!(config.float_ball.panel.hover_delay, MAX_HOVER_DELAY);
+    }
+
+    #[test]
+    fn upload_values_fall_back_or_clamp() {
+        let errors: [Case; 7] = [
+            (|c| c.float_ball.upload.api_url = " ".to_string(), "float_ball.upload.api_url"),
+            (|c| c.float_ball.upload.max_file_size = 0, "float_ball.upload.max_file_size"),
+            (
+                |c| c.float_ball.upload.chunk.init_url = String::new(),
+                "float_ball.upload.chunk.init_url",
+            ),
+            (
+                |c| c.float_ball.upload.chunk.chunk_url = String::new(),
+                "float_ball.upload.chunk.chunk_url",
+            ),
+            (
+                |c| c.float_ball.upload.chunk.complete_url = String::new(),
+                "float_ball.upload.chunk.complete_url",
+            ),
+            (
+                |c| c.float_ball.upload.chunk.max_file_size = 0,
+                "float_ball.upload.chunk.max_file_size",
+            ),
+            (
+                |c| c.float_ball.upload.instant.check_url = String::new(),
+                "float_ball.upload.instant.check_url",
+            ),
+        ];
+        for (f, path) in errors {
+            let (config, issues) = sanitized(f);
+            assert_eq!(issues, one(Severity::Error, path));
+            // 回退为默认值后不再有问题
+            assert!(config.validate().is_empty(), "{}", path);
+        }
+        let (config, issues) = sanitized(|c| c.float_ball.upload.instant.instant_url = String::new());
+        assert_eq!(issues, one(Severity::Error, "float_ball.upload.instant.instant_url"));
+        assert_eq!(config.float_ball.upload.instant.instant_url, "/api/v1/files/instant-upload");
+
+        let (config, issues) = sanitized(|c| c.float_ball.upload.concurrent = 0);
+        assert_eq!(issues, one(Severity::Warning, "float_ball.upload.concurrent"));
+        assert_eq!(config.float_ball.upload.concurrent, 1);
+
+        let (config, issues) = sanitized(|c| c.float_ball.upload.chunk.chunk_size = 1);
+        assert_eq!(issues, one(Severity::Warning, "float_ball.upload.chunk.chunk_size"));
+        assert_eq!(config.float_ball.upload.chunk.chunk_size, MIN_CHUNK_SIZE);
+
+        let (config, issues) = sanitized(|c| c.float_ball.upload.chunk.retries = 99);
+        assert_eq!(issues, one(Severity::Warning, "float_ball.upload.chunk.retries"));
+        assert_eq!(config.float_ball.upload.chunk.retries, MAX_CHUNK_RETRIES);
+    }
+
+    #[test]
+    fn invalid_server_profiles_are_dropped() {
+        for (profile, path) in [
+            (server(" ", "http://a"), "servers.profiles[1].name"),
+            (server("local", "http://a"), "servers.profiles[1].name"),
+            (server("b", "ftp://a"), "servers.profiles[1].base_url"),
+        ] {
+            let (config, issues) = sanitized(|c| c.servers.profiles.push(profile));
+            assert_eq!(issues, one(Severity::Error, path));
+            assert_eq!(config.servers.profiles.len(), 1);
+        }
+    }
+
+    #[test]
+    fn server_timeout_falls_back() {
+        let (config, issues) = sanitized(|c| c.servers.profiles[0].timeout_ms = 0);
+        assert_eq!(issues, one(Severity::Error, "servers.profiles[0].timeout_ms"));
+        assert_eq!(config.servers.profiles[0].timeout_ms, DEFAULT_SERVER_TIMEOUT_MS);
+    }
+
+    #[test]
+    fn servers_fall_back_to_defaults() {
+        let (config, issues) = sanitized(|c| c.servers.profiles[0].base_url = "nope".to_string());
+        assert_eq!(
+            issues,
+            vec![
+                (Severity::Error, "servers.profiles[0].base_url".to_string()),
+                (Severity::Error, "servers.profiles".to_string()),
+            ]
+        );
+        assert_eq!(config.servers.profiles[0].name, "local");
+
+        let (config, issues) = sanitized(|c| {
+            c.servers.profiles.push(server("staging", "https://staging.example.com"));
+            c.servers.active = "gone".to_string();
+        });
+        assert_eq!(issues, one(Severity::Error, "servers.active"));
+        assert_eq!(config.servers.active, "local");
+    }
+
+    #[test]
+    fn validate_does_not_modify() {
+        let mut config = AppConfigData::default();
+        config.float_ball.upload.concurrent = 0;
+        let issues = config.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert_eq!(config.float_ball.upload.concurrent, 0);
+    }
+}
@@ -0,0 +1,357 @@
+//! 窗口与显示器的几何计算（物理像素），不依赖 tauri 运行时
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Anchor, WindowPlacement};
+
+/// 标题栏至少要有这么高、这么宽的部分落在某个屏幕上，才认为窗口仍可被拖动
+const TITLE_BAR_HEIGHT: i64 = 32;
+const MIN_VISIBLE_WIDTH: i64 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x as f64 && x < self.right() as f64 && y >= self.y as f64 && y < self.bottom() as f64
+    }
+
+    fn right(&self) -> i64 {
+        self.x as i64 + self.width as i64
+    }
+
+    fn bottom(&self) -> i64 {
+        self.y as i64 + self.height as i64
+    }
+
+    /// 与另一矩形相交部分的宽和高，不相交时为 None
+    fn overlap(&self, other: &Rect) -> Option<(i64, i64)> {
+        let width = self.right().min(other.right()) - (self.x as i64).max(other.x as i64);
+        let height = self.bottom().min(other.bottom()) - (self.y as i64).max(other.y as i64);
+        (width > 0 && height > 0).then_some((width, height))
+    }
+
+    fn overlap_area(&self, other: &Rect) -> i64 {
+        self.overlap(other).map_or(0, |(w, h)| w * h)
+    }
+}
+
+/// 一个已连接的显示器
+#[derive(Debug, Clone, PartialEq)]
+pub struct Screen {
+    pub name: Option<String>,
+    /// 显示器整体区域
+    pub bounds: Rect,
+    /// 去掉任务栏、菜单栏后的可用区域
+    pub work_area: Rect,
+    pub scale_factor: f64,
+}
+
+/// 标题栏是否有足够的部分落在某个屏幕的可用区域内
+fn is_reachable(rect: &Rect, screens: &[Screen]) -> bool {
+    let title_height = TITLE_BAR_HEIGHT.min(rect.height as i64) as u32;
+    let title_bar = Rect::new(rect.x, rect.y, rect.width, title_height);
+    screens.iter().any(|screen| {
+        title_bar
+            .overlap(&screen.work_area)
+            .is_some_and(|(width, _)| width >= MIN_VISIBLE_WIDTH.min(rect.width as i64))
+    })
+}
+
+/// 把窗口完整放进 area：尺寸超出时缩小，位置超出时推回
+pub fn clamp_into(rect: Rect, area: &Rect) -> Rect {
+    let width = rect.width.min(area.width);
+    let height = rect.height.min(area.height);
+    let max_x = area.right() - width as i64;
+    let max_y = area.bottom() - height as i64;
+    Rect {
+        x: (rect.x as i64).clamp(area.x as i64, max_x) as i32,
+        y: (rect.y as i64).clamp(area.y as i64, max_y) as i32,
+        width,
+        height,
+    }
+}
+
+/// 把保存的窗口区域放到当前连接的屏幕上：
+/// 保存时所在的显示器仍连接（或未记录显示器）且标题栏可见时原样返回；否则放进保存时的显示器，
+/// 该显示器不在时放进与窗口重叠最多的屏幕，都不重叠时放进第一个屏幕（调用方应把主显示器放在首位）
+pub fn fit_to_screens(rect: Rect, monitor: Option<&str>, screens: &[Screen]) -> Rect {
+    let saved = monitor.and_then(|name| screens.iter().find(|s| s.name.as_deref() == Some(name)));
+    if (saved.is_some() || monitor.is_none()) && is_reachable(&rect, screens) {
+        return rect;
+    }
+    let target = saved
+        .or_else(|| {
+            screens
+                .iter()
+                .map(|screen| (screen, rect.overlap_area(&screen.work_area)))
+                .filter(|(_, area)| *area > 0)
+                .max_by_key(|(_, area)| *area)
+                .map(|(screen, _)| screen)
+        })
+        .or_else(|| screens.first());
+    match target {
+        Some(screen) => clamp_into(rect, &screen.work_area),
+        None => rect,
+    }
+}
+
+/// 包含该点的屏幕（按显示器整体区域判断）
+pub fn screen_at(screens: &[Screen], x: f64, y: f64) -> Option<&Screen> {
+    screens.iter().find(|screen| screen.bounds.contains(x, y))
+}
+
+/// 按贴靠位置把 width × height 的窗口放进 area，margin 为距边缘的距离；
+/// 可用区域放不下时窗口贴着区域左上角
+pub fn anchor_in(area: &Rect, width: u32, height: u32, anchor: Anchor, margin: u32) -> (i32, i32) {
+    let (left, top) = (area.x as i64, area.y as i64);
+    let right = area.right() - width as i64;
+    let bottom = area.bottom() - height as i64;
+    let margin = margin as i64;
+    let (x, y) = match anchor {
+        Anchor::TopLeft => (left + margin, top + margin),
+        Anchor::TopRight => (right - margin, top + margin),
+        Anchor::BottomLeft => (left + margin, bottom - margin),
+        Anchor::BottomRight => (right - margin, bottom - margin),
+        Anchor::Center => ((left + right) / 2, (top + bottom) / 2),
+    };
+    // 边距过大时先保证窗口在区域内
+    (x.min(right).max(left) as i32, y.min(bottom).max(top) as i32)
+}
+
+/// 按放置规则在屏幕的可用区域内贴靠；width/height/margin 为逻辑像素，按屏幕的缩放比例换算，返回物理像素
+pub fn anchor_on(screen: &Screen, placement: &WindowPlacement, width: f64, height: f64) -> (i32, i32) {
+    let scale = screen.scale_factor;
+    anchor_in(
+        &screen.work_area,
+        (width * scale).round() as u32,
+        (height * scale).round() as u32,
+        placement.anchor,
+        (placement.margin * scale).round() as u32,
+    )
+}
+
+/// 窗口所在屏幕在布局变化前后是否不同：缩放比例或可用区域变了，或屏幕已断开。
+/// 屏幕按名称对应，没有名称时按显示器左上角对应
+fn screen_changed(current: &[Screen], before: &Screen) -> bool {
+    let after = current.iter().find(|screen| match (&screen.name, &before.name) {
+        (Some(name), Some(before_name)) => name == before_name,
+        _ => (screen.bounds.x, screen.bounds.y) == (before.bounds.x, before.bounds.y),
+    });
+    after.map_or(true, |after| {
+        after.scale_factor != before.scale_factor || after.work_area != before.work_area
+    })
+}
+
+/// 显示器布局变化后是否重新放置设置了 placement 的窗口。window 为窗口当前区域（物理像素），
+/// width/height 为逻辑尺寸，fits 为当前位置在新布局下是否仍可用：
+/// 所在屏幕的缩放比例或可用区域变了、且窗口仍停在变化前的贴靠位置时总是重新放置；
+/// 其他窗口（包括用户拖动过的）只在放不下时重新放置
+pub fn needs_reanchor(
+    previous: &[Screen],
+    current: &[Screen],
+    window: &Rect,
+    placement: &WindowPlacement,
+    size: (f64, f64),
+    fits: bool,
+) -> bool {
+    if !fits {
+        return true;
+    }
+    let center_x = window.x as f64 + window.width as f64 / 2.0;
+    let center_y = window.y as f64 + window.height as f64 / 2.0;
+    screen_at(previous, center_x, center_y).is_some_and(|before| {
+        screen_changed(current, before)
+            && anchor_on(before, placement, size.0, size.1) == (window.x, window.y)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(name: &str, work_area: Rect) -> Screen {
+        Screen {
+            name: Some(name.to_string()),
+            bounds: work_area,
+            work_area,
+            scale_factor: 1.0,
+        }
+    }
+
+    /// 主显示器 1920×1080（底部 40 像素任务栏），右侧一台 1280×1024
+    fn two_screens() -> Vec<Screen> {
+        vec![
+            screen("primary", Rect::new(0, 0, 1920, 1040)),
+            screen("right", Rect::new(1920, 0, 1280, 1024)),
+        ]
+    }
+
+    #[test]
+    fn anchor_in_places_each_corner_with_margin() {
+        let area = Rect::new(100, 50, 1000, 800);
+        let cases = [
+            (Anchor::TopLeft, (120, 70)),
+            (Anchor::TopRight, (100 + 1000 - 200 - 20, 70)),
+            (Anchor::BottomLeft, (120, 50 + 800 - 100 - 20)),
+            (Anchor::BottomRight, (880, 730)),
+            (Anchor::Center, (500, 400)),
+        ];
+        for (anchor, expected) in cases {
+            assert_eq!(anchor_in(&area, 200, 100, anchor, 20), expected, "{:?}", anchor);
+        }
+    }
+
+    #[test]
+    fn anchor_in_keeps_the_window_inside_when_the_margin_is_too_large() {
+        let area = Rect::new(0, 0, 400, 300);
+        assert_eq!(anchor_in(&area, 100, 100, Anchor::BottomRight, 1000), (0, 0));
+        assert_eq!(anchor_in(&area, 100, 100, Anchor::TopLeft, 1000), (300, 200));
+    }
+
+    #[test]
+    fn anchor_in_pins_oversized_windows_to_the_top_left() {
+        let area = Rect::new(-1280, 0, 1280, 1024);
+        for anchor in [Anchor::TopLeft, Anchor::BottomRight, Anchor::Center] {
+            assert_eq!(anchor_in(&area, 2000, 1200, anchor, 10), (-1280, 0), "{:?}", anchor);
+        }
+    }
+
+    #[test]
+    fn clamp_into_pushes_the_window_back_inside() {
+        let area = Rect::new(0, 0, 1920, 1040);
+        assert_eq!(
+            clamp_into(Rect::new(1800, 1000, 400, 300), &area),
+            Rect::new(1520, 740, 400, 300)
+        );
+        assert_eq!(
+            clamp_into(Rect::new(-500, -20, 400, 300), &area),
+            Rect::new(0, 0, 400, 300)
+        );
+        let inside = Rect::new(100, 100, 400, 300);
+        assert_eq!(clamp_into(inside, &area), inside);
+    }
+
+    #[test]
+    fn clamp_into_shrinks_windows_larger_than_the_area() {
+        let area = Rect::new(1920, 0, 1280, 1024);
+        assert_eq!(
+            clamp_into(Rect::new(0, 0, 2560, 1440), &area),
+            Rect::new(1920, 0, 1280, 1024)
+        );
+    }
+
+    #[test]
+    fn fit_to_screens_keeps_a_reachable_window_on_its_monitor() {
+        let screens = two_screens();
+        let rect = Rect::new(2000, 100, 800, 600);
+        assert_eq!(fit_to_screens(rect, Some("right"), &screens), rect);
+        // 部分超出屏幕但标题栏仍可拖动
+        let partly_off = Rect::new(1700, 900, 800, 600);
+        assert_eq!(fit_to_screens(partly_off, Some("primary"), &screens), partly_off);
+        // 未记录显示器时只看是否可达
+        assert_eq!(fit_to_screens(rect, None, &screens), rect);
+    }
+
+    #[test]
+    fn fit_to_screens_moves_a_hidden_title_bar_onto_the_saved_monitor() {
+        let screens = two_screens();
+        let above = Rect::new(2000, -200, 800, 600);
+        assert_eq!(
+            fit_to_screens(above, Some("right"), &screens),
+            Rect::new(2000, 0, 800, 600)
+        );
+        // 标题栏只有一小段可见
+        let sliver = Rect::new(3180, 100, 800, 600);
+        assert_eq!(
+            fit_to_screens(sliver, Some("right"), &screens),
+            Rect::new(2400, 100, 800, 600)
+        );
+    }
+
+    #[test]
+    fn fit_to_screens_uses_the_most_overlapping_screen_when_the_monitor_is_gone() {
+        let screens = two_screens();
+        // 保存时的显示器已断开：即使窗口仍可见，也放进与它重叠最多的屏幕
+        let rect = Rect::new(1800, 100, 800, 600);
+        assert_eq!(
+            fit_to_screens(rect, Some("left"), &screens),
+            Rect::new(1920, 100, 800, 600)
+        );
+    }
+
+    #[test]
+    fn fit_to_screens_falls_back_to_the_first_screen() {
+        let screens = two_screens();
+        let far_away = Rect::new(-3000, 2000, 800, 600);
+        assert_eq!(
+            fit_to_screens(far_away, Some("left"), &screens),
+            Rect::new(0, 440, 800, 600)
+        );
+        assert_eq!(
+            fit_to_screens(far_away, None, &screens),
+            Rect::new(0, 440, 800, 600)
+        );
+        assert_eq!(fit_to_screens(far_away, None, &[]), far_away);
+    }
+
+    fn corner() -> WindowPlacement {
+        WindowPlacement {
+            monitor: crate::config::MonitorTarget::Primary,
+            anchor: Anchor::BottomRight,
+            margin: 20.0,
+        }
+    }
+
+    /// 按放置规则贴靠在 screen 上的 60×60 窗口
+    fn anchored(screen: &Screen) -> Rect {
+        let (x, y) = anchor_on(screen, &corner(), 60.0, 60.0);
+        let side = (60.0 * screen.scale_factor) as u32;
+        Rect::new(x, y, side, side)
+    }
+
+    #[test]
+    fn anchored_windows_follow_scale_and_work_area_changes() {
+        let before = two_screens();
+        let window = anchored(&before[0]);
+        assert_eq!((window.x, window.y), (1840, 960));
+
+        let mut scaled = two_screens();
+        scaled[0].scale_factor = 1.5;
+        assert!(needs_reanchor(&before, &scaled, &window, &corner(), (60.0, 60.0), true));
+
+        let mut taskbar_moved = two_screens();
+        taskbar_moved[0].work_area = Rect::new(0, 40, 1920, 1040);
+        assert!(needs_reanchor(&before, &taskbar_moved, &window, &corner(), (60.0, 60.0), true));
+    }
+
+    #[test]
+    fn moved_windows_stay_unless_they_no_longer_fit() {
+        let before = two_screens();
+        let mut scaled = two_screens();
+        scaled[0].scale_factor = 1.5;
+        // 用户拖动过的窗口
+        let moved = Rect::new(500, 300, 60, 60);
+        assert!(!needs_reanchor(&before, &scaled, &moved, &corner(), (60.0, 60.0), true));
+        assert!(needs_reanchor(&before, &scaled, &moved, &corner(), (60.0, 60.0), false));
+    }
+
+    #[test]
+    fn anchored_windows_stay_when_their_screen_is_unchanged() {
+        let before = two_screens();
+        let window = anchored(&before[0]);
+        let mut other_scaled = two_screens();
+        other_scaled[1].scale_factor = 2.0;
+        assert!(!needs_reanchor(&before, &other_scaled, &window, &corner(), (60.0, 60.0), true));
+        assert!(!needs_reanchor(&before, &before, &window, &corner(), (60.0, 60.0), true));
+    }
+}
@@ -0,0 +1,306 @@
+//! 秒传：在 Rust 侧流式计算文件 MD5（多个文件并行），整批询问服务端哪些文件已存在，
+//! 已存在的文件只创建记录、不再上传内容。MD5 按路径、大小和修改时间缓存在应用缓存目录（按 profile 隔离）
+
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_http::reqwest;
+
+use crate::config::{FloatBallUploadConfig, ServerProfile};
+use crate::upload;
+
+const FILE_NAME: &str = "hash-cache.json";
+/// 缓存条目上限，超出时丢弃最久未用的
+const MAX_ENTRIES: usize = 5000;
+/// 同时计算哈希的线程数上限（受磁盘速度限制，更多线程没有意义）
+const MAX_HASH_THREADS: usize = 4;
+/// 每次从文件读取的字节数
+const READ_CHUNK: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    size: u64,
+    /// 修改时间（Unix 毫秒）
+    modified: i64,
+    md5: String,
+    /// 最近一次使用时间（Unix 秒）
+    used_at: i64,
+}
+
+/// MD5 缓存（Tauri managed state），按文件路径保存，大小或修改时间变化后失效
+pub struct HashCache {
+    path: PathBuf,
+    entries: Mutex<HashMap<PathBuf, CacheEntry>>,
+}
+
+impl HashCache {
+    /// 从缓存目录读取缓存，文件不存在或损坏时从空开始
+    pub fn load(cache_dir: PathBuf) -> Self {
+        let path = cache_dir.join(FILE_NAME);
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                log::warn!("Ignoring corrupted {}: {}", path.display(), e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        Self {
+            path,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, CacheEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn get(&self, path: &Path, size: u64, modified: i64) -> Option<String> {
+        let mut entries = self.entries();
+        let entry = entries
+            .get_mut(path)
+            .filter(|entry| entry.size == size && entry.modified == modified)?;
+        entry.used_at = chrono::Utc::now().timestamp();
+        Some(entry.md5.clone())
+    }
+
+    fn insert(&self, path: PathBuf, size: u64, modified: i64, md5: String) {
+        let used_at = chrono::Utc::now().timestamp();
+        self.entries().insert(
+            path,
+            CacheEntry {
+                size,
+                modified,
+                md5,
+                used_at,
+            },
+        );
+    }
+
+    /// 只保留最近使用的 MAX_ENTRIES 条，先写临时文件再重命名
+    fn save(&self) -> Result<(), String> {
+        let text = {
+            let mut entries = self.entries();
+            if entries.len() > MAX_ENTRIES {
+                let mut used: Vec<i64> = entries.values().map(|entry| entry.used_at).collect();
+                used.sort_unstable_by(|a, b| b.cmp(a));
+                let oldest_kept = used[MAX_ENTRIES - 1];
+                entries.retain(|_, entry| entry.used_at >= oldest_kept);
+            }
+            serde_json::to_string(&*entries).map_err(|e| e.to_string())?
+        };
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, text).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, &self.path).map_err(|e| e.to_string())
+    }
+}
+
+/// 流式计算文件 MD5（十六进制），不把整个文件读入内存
+fn md5_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Md5::new();
+    let mut buf = vec![0; READ_CHUNK];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 缓存键：文件大小和修改时间
+fn file_key(path: &Path) -> (u64, i64) {
+    let size = std::fs::metadata(path).map_or(0, |m| m.len());
+    (size, upload::modified_ms(path))
+}
+
+/// 计算文件 MD5；计算期间文件大小或修改时间变化时丢弃结果，
+/// 否则可能把前后不一致的内容的摘要当作新内容的摘要缓存和秒传
+fn hash_unchanged(
+    path: &Path,
+    key: (u64, i64),
+    hash: impl Fn(&Path) -> std::io::Result<String>,
+) -> Option<String> {
+    let md5 = hash(path)
+        .map_err(|e| log::warn!("Failed to hash {}: {}", path.display(), e))
+        .ok()?;
+    if file_key(path) != key {
+        log::warn!("{} changed while hashing", path.display());
+        return None;
+    }
+    Some(md5)
+}
+
+/// 计算一批文件的 MD5，与 paths 一一对应，读取失败或计算期间被修改的为 None。
+/// 命中缓存的文件不再读取，其余文件由多个线程并行计算（阻塞调用，应放在阻塞线程池中执行）
+pub fn hash_files<R: Runtime>(app: &AppHandle<R>, paths: &[PathBuf]) -> Vec<Option<String>> {
+    let cache = app.try_state::<HashCache>();
+    let keys: Vec<(u64, i64)> = paths.iter().map(|path| file_key(path)).collect();
+    let mut hashes: Vec<Option<String>> = paths
+        .iter()
+        .zip(&keys)
+        .map(|(path, (size, modified))| cache.as_ref()?.get(path, *size, *modified))
+        .collect();
+
+    let missing: Vec<usize> = (0..paths.len()).filter(|&i| hashes[i].is_none()).collect();
+    if missing.is_empty() {
+        return hashes;
+    }
+    let threads = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(MAX_HASH_THREADS)
+        .min(missing.len());
+    let next = AtomicUsize::new(0);
+    let computed = Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                while let Some(&index) = missing.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    if let Some(md5) = hash_unchanged(&paths[index], keys[index], md5_file) {
+                        computed
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .push((index, md5));
+                    }
+                }
+            });
+        }
+    });
+
+    for (index, md5) in computed.into_inner().unwrap_or_else(|e| e.into_inner()) {
+        if let Some(cache) = &cache {
+            let (size, modified) = keys[index];
+            cache.insert(paths[index].clone(), size, modified, md5.clone());
+        }
+        hashes[index] = Some(md5);
+    }
+    if let Some(cache) = &cache {
+        if let Err(e) = cache.save() {
+            log::warn!("Failed to save hash cache: {}", e);
+        }
+    }
+    log::info!(
+        "Hashed {} file(s), {} from cache",
+        missing.len(),
+        paths.len() - missing.len()
+    );
+    hashes
+}
+
+/// 一个待检测的文件
+pub struct Candidate<'a> {
+    pub name: &'a str,
+    pub size: u64,
+    pub md5: &'a str,
+}
+
+/// 秒传接口
+pub struct InstantUpload<'a> {
+    pub client: &'a reqwest::Client,
+    pub server: &'a ServerProfile,
+    pub config: &'a FloatBallUploadConfig,
+    pub token: Option<&'a str>,
+}
+
+impl InstantUpload<'_> {
+    async fn post_json(
+        &self,
+        api_url: &str,
+        body: Value,
+        name: &str,
+    ) -> Result<reqwest::Response, String> {
+        let mut request = self
+            .client
+            .post(upload::upload_url(self.server, api_url))
+            .header("Content-Type", "application/json")
+            .body(body.to_string());
+        if let Some(token) = self.token {
+            request = request.bearer_auth(token);
+        }
+        request.send().await.map_err(|e| format!("{}: {}", name, e))
+    }
+
+    /// 一次请求检测整批文件，返回服务端已存在的 MD5
+    pub async fn check(&self, files: &[Candidate<'_>]) -> Result<HashSet<String>, String> {
+        let body = json!({
+            "files": files
+                .iter()
+                .map(|file| json!({ "md5": file.md5, "size": file.size, "file_name": file.name }))
+                .collect::<Vec<_>>(),
+        });
+        let response = self
+            .post_json(&self.config.instant.check_url, body, "check-duplicate")
+            .await?;
+        let data = upload::parse_data(response, "check-duplicate").await?;
+        Ok(data
+            .get("duplicates")
+            .and_then(Value::as_array)
+            .map(|duplicates| {
+                duplicates
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_ascii_lowercase)
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// 按 MD5 创建文件记录，返回文件地址
+    pub async fn upload(
+        &self,
+        file: &Candidate<'_>,
+        fields: &[(&'static str, String)],
+    ) -> Result<String, String> {
+        let mut body = Map::new();
+        body.insert("md5".to_string(), json!(file.md5));
+        body.insert("size".to_string(), json!(file.size));
+        body.insert("file_name".to_string(), json!(file.name));
+        for (key, value) in fields {
+            body.insert(key.to_string(), json!(value));
+        }
+        let response = self
+            .post_json(&self.config.instant.instant_url, Value::Object(body), file.name)
+            .await?;
+        upload::parse_response(response, file.name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_of_an_unchanged_file_is_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "alpha").unwrap();
+        assert_eq!(
+            hash_unchanged(&path, file_key(&path), md5_file).as_deref(),
+            Some("2c1743a391305fbf367df8e4f069f9f9")
+        );
+    }
+
+    #[test]
+    fn hash_of_a_file_modified_while_hashing_is_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "alpha").unwrap();
+        let key = file_key(&path);
+        let md5 = hash_unchanged(&path, key, |path| {
+            let md5 = md5_file(path);
+            std::fs::write(path, "alpha and more").unwrap();
+            md5
+        });
+        assert_eq!(md5, None);
+    }
+}
@@ -1,16 +1,30 @@
-use tauri::Manager;
-use tauri::menu::{Menu, MenuItem, Submenu};
-use tauri::tray::TrayIconBuilder;
-use tauri::image::Image;
-use tauri::{WebviewUrl, WebviewWindowBuilder};
-use image::GenericImageView;
+use tauri::{Emitter, Manager};
 
+mod auth_state;
+mod chunk_upload;
+mod clipboard;
 mod config;
 mod commands;
+mod geometry;
+mod instant_upload;
 mod macos;
+mod placement;
+mod profile;
+mod recent_uploads;
 mod single_instance;
+#[cfg(test)]
+mod test_server;
+mod tray;
+mod tray_status;
+mod upload;
+mod upload_queue;
+mod window;
+mod window_state;
 use config::AppConfigData;
-use single_instance::SingleInstance;
+
+/// 供 examples/config_schema.rs 生成 JSON Schema 与 TypeScript 类型
+pub use config::schema as config_schema;
+use single_instance::{SecondInstanceArgs, SingleInstance};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -23,13 +37,40 @@ pub fn run() {
     .plugin(tauri_plugin_process::init())
     .plugin(tauri_plugin_dialog::init())
     .plugin(tauri_plugin_http::init())
+    .manage(tray_status::TrayStatus::default())
     .invoke_handler(tauri::generate_handler![
       config::get_app_config,
+      config::set_app_config,
+      config::patch_app_config,
+      config::get_config_diagnostics,
+      config::servers::list_servers,
+      config::servers::add_server,
+      config::servers::test_server,
+      config::servers::switch_server,
+      config::bundle::export_config,
+      config::bundle::preview_config_import,
+      config::bundle::import_config,
+      auth_state::get_auth_state,
+      auth_state::update_auth_state,
       commands::show_login_window,
       commands::toggle_float_ball,
       commands::close_float_ball,
       commands::is_float_ball_visible,
       commands::show_main_window,
+      commands::open_window,
+      commands::close_window,
+      commands::toggle_window,
+      profile::get_launch_profile,
+      recent_uploads::add_recent_upload,
+      recent_uploads::list_recent_uploads,
+      recent_uploads::clear_recent_uploads,
+      tray_status::update_tray_status,
+      upload::upload_clipboard,
+      upload_queue::enqueue_upload,
+      upload_queue::pause_upload,
+      upload_queue::resume_upload,
+      upload_queue::cancel_upload,
+      upload_queue::list_uploads,
     ])
     .setup(|app| {
       // 先初始化日志
@@ -39,19 +80,56 @@ pub fn run() {
           .build(),
       )?;
 
-      // 加载配置
+      // 加载配置（内置默认值 → 用户文件 → 本机覆盖 → 环境变量 → 策略文件）；
+      // 以 --profile 启动时用户配置放在 profiles/<name> 子目录
+      if let Some(name) = profile::current() {
+        log::info!("Launch profile: {}", name);
+      }
+      match app.path().app_config_dir() {
+        Ok(dir) => config::init_paths(&profile::scope(&dir)),
+        Err(e) => log::warn!("Failed to resolve app config dir: {}", e),
+      }
       let config = AppConfigData::load();
       log::info!("Application starting: {}", config.name);
 
-      // 单实例检查
+      // 单实例检查：在写入任何文件、加载任何状态之前进行，第二个实例不应改动正在运行的实例的数据
       if config.app.single_instance {
-        match SingleInstance::new(&config.name) {
-          Ok(_instance) => {
+        match SingleInstance::new(&config.name, profile::current()) {
+          Ok(instance) => {
             log::info!("Single instance lock acquired");
-            // 锁将在应用退出时自动释放
+
+            // 后续启动的进程会把参数转发过来：激活窗口并通知前端
+            let handle = app.handle().clone();
+            let listening = instance.listen(move |args| {
+              log::info!("Second instance launched with {:?}", args.args);
+              window::focus_main_window(&handle);
+              if let Err(e) = handle.emit("second-instance", args) {
+                log::warn!("Failed to emit second-instance: {}", e);
+              }
+            });
+            if let Err(e) = listening {
+              log::warn!("Failed to listen for second instances: {}", e);
+            }
+
+            // 交给 Tauri 持有，保证锁在整个运行期间有效，退出时在 RunEvent::Exit 中释放
+            app.manage(instance);
           }
           Err(e) => {
             log::warn!("Another instance is already running: {}", e);
+
+            // 把启动参数交给已运行的实例处理，成功则静默退出
+            match SingleInstance::notify_running(
+              &config.name,
+              profile::current(),
+              &SecondInstanceArgs::current(),
+            ) {
+              Ok(()) => {
+                log::info!("Launch arguments forwarded to the running instance");
+                std::process::exit(0);
+              }
+              Err(e) => log::warn!("Failed to notify the running instance: {}", e),
+            }
+
             // 显示提示对话框
             use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
             let _ = app.dialog()
@@ -66,36 +144,73 @@ pub fn run() {
         }
       }
 
-      // 应用窗口配置
-      if let Some(window) = app.get_webview_window("main") {
-        let window_config = &config.window;
+      // 旧版本的用户配置只在启动时升级写回一次（读取配置时只在内存中升级）
+      config::migrate_user_file();
 
-        // 应用窗口尺寸限制
-        if let (Some(min_w), Some(min_h)) = (window_config.min_width, window_config.min_height) {
-          let _ = window.set_min_size(Some(tauri::LogicalSize::new(min_w, min_h)));
+      // 最近上传记录（托盘菜单构建前加载）、窗口状态（窗口显示前加载）和上传队列
+      match app.path().app_data_dir() {
+        Ok(dir) => {
+          app.manage(recent_uploads::RecentUploads::load(profile::scope(&dir)));
+          app.manage(window_state::WindowStates::load(profile::scope(&dir)));
+          app.manage(upload_queue::UploadQueue::load(profile::scope(&dir)));
         }
-        if let (Some(max_w), Some(max_h)) = (window_config.max_width, window_config.max_height) {
-          let _ = window.set_max_size(Some(tauri::LogicalSize::new(max_w, max_h)));
+        Err(e) => log::warn!("Failed to resolve app data dir: {}", e),
+      }
+      // 秒传的 MD5 缓存
+      match app.path().app_cache_dir() {
+        Ok(dir) => {
+          app.manage(instant_upload::HashCache::load(profile::scope(&dir)));
         }
+        Err(e) => log::warn!("Failed to resolve app cache dir: {}", e),
+      }
+      // 登录状态：有前端保存的令牌时从已登录开始
+      app.manage(auth_state::AppState::new(upload::read_token(app.handle()).is_some()));
 
-        // 应用窗口行为
-        let _ = window.set_resizable(window_config.resizable);
-        let _ = window.set_maximizable(window_config.maximizable);
-        let _ = window.set_minimizable(window_config.minimizable);
-        let _ = window.set_closable(window_config.closable);
-        let _ = window.set_always_on_top(window_config.always_on_top);
-        let _ = window.set_skip_taskbar(window_config.skip_taskbar);
+      // 配置有误时提示用户（出错的配置段已回退为默认值）
+      let diagnostics = config::get_config_diagnostics();
+      let mut details: Vec<String> = diagnostics.errors.iter().map(|e| e.to_string()).collect();
+      details.extend(
+        diagnostics.issues.iter()
+          .filter(|issue| issue.severity == config::Severity::Error)
+          .map(|issue| format!("{}: {}", issue.path, issue.message)),
+      );
+      if !details.is_empty() {
+        use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+        app.dialog()
+          .message(format!("以下配置有误，已使用默认值：\n\n{}", details.join("\n")))
+          .title("配置错误")
+          .kind(MessageDialogKind::Warning)
+          .show(|_| {});
+      }
 
-        if window_config.center {
+      // 主窗口在 tauri.conf.json 中声明为 create: false，在这里创建以便按 profile 隔离 webview 数据
+      if let Some(main_config) = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|window_config| window_config.label == window::MAIN_WINDOW)
+        .cloned()
+      {
+        let builder = tauri::WebviewWindowBuilder::from_config(app.handle(), &main_config)?;
+        profile::scope_webview(app.handle(), builder).build()?;
+      }
+
+      // 应用窗口配置
+      if let Some(window) = app.get_webview_window("main") {
+        window::apply_window_config(&window, &config.window);
+
+        // 主窗口在 tauri.conf.json 中初始隐藏，按登录状态显示，这里恢复的状态在显示前生效
+        if !window_state::restore(&window) && config.window.center {
           let _ = window.center();
         }
+        window_state::track(&window);
 
-        // 处理窗口关闭事件
-        let quit_on_close = config.app.quit_on_close;
+        // 处理窗口关闭事件（每次读取当前配置，热重载后立即生效）
         let window_clone = window.clone();
         window.on_window_event(move |event| {
           if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-            if !quit_on_close {
+            if !AppConfigData::load().app.quit_on_close {
               // 不退出程序，最小化窗口到托盘（macOS 上 hide() 无法恢复，用 minimize()）
               api.prevent_close();
               let _ = window_clone.minimize();
@@ -106,6 +221,11 @@ pub fn run() {
         });
       }
 
+      // 按登录状态显示主窗口或登录窗口
+      if let Err(e) = auth_state::sync_windows(app.handle()) {
+        log::error!("Failed to show the initial window: {}", e);
+      }
+
       // 开发模式打开 DevTools
       if cfg!(debug_assertions) && config.dev.open_dev_tools {
         if let Some(window) = app.get_webview_window("main") {
@@ -118,129 +238,36 @@ pub fn run() {
 
       // 创建托盘（如果启用）
       if config.tray.enabled {
-        // 存储所有菜单项，防止被销毁
-        let mut all_items: Vec<MenuItem<tauri::Wry>> = Vec::new();
-        let mut submenus: Vec<Submenu<tauri::Wry>> = Vec::new();
+        match tray::create(app.handle(), &config) {
+          Ok(_) => log::info!("System tray created"),
+          Err(e) => log::error!("Failed to create system tray: {:?}", e),
+        }
+      }
 
-        for group in &config.tray.menus {
-          let start_idx = all_items.len();
+      // 最近上传记录变化时重建托盘菜单
+      recent_uploads::watch(app.handle());
 
-          // 为每个分组创建菜单项
-          for item in &group.items {
-            let menu_item = MenuItem::with_id(app, &item.id, &item.label, true, None::<&str>)?;
-            all_items.push(menu_item);
-          }
+      // 继续上次退出时未完成的上传
+      upload_queue::pump(app.handle());
 
-          // 获取当前分组的菜单项引用
-          let items_refs: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> =
-            all_items[start_idx..].iter().map(|item| item as &dyn tauri::menu::IsMenuItem<tauri::Wry>).collect();
+      // 显示器增减或缩放变化时重新放置悬浮球等窗口
+      placement::watch(app.handle());
 
-          let submenu = Submenu::with_items(app, &group.label, true, &items_refs)?;
-          submenus.push(submenu);
-        }
-
-        // 创建主菜单，包含所有子菜单
-        let submenu_refs: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> =
-          submenus.iter().map(|submenu| submenu as &dyn tauri::menu::IsMenuItem<tauri::Wry>).collect();
-        let menu = Menu::with_items(app, &submenu_refs)?;
-
-        // 加载并转换图标
-        let icon_bytes = include_bytes!("../icons/32x32.png");
-        let img = image::load_from_memory(icon_bytes).expect("Failed to load icon");
-        let (width, height) = img.dimensions();
-        let rgba = img.to_rgba8().into_raw();
-        let icon_image = Image::new_owned(rgba, width, height);
-
-        // 使用配置中的 tooltip 和 title
-        let tooltip = config.tray.tooltip.as_deref().unwrap_or(&config.name);
-        let title = config.tray.title.as_deref().unwrap_or("");
-
-        let tray_result = TrayIconBuilder::with_id("main-tray")
-          .icon(icon_image)
-          .icon_as_template(true) // macOS 模板图标
-          .tooltip(tooltip)
-          .title(title)
-          .menu(&menu)
-          .show_menu_on_left_click(true) // macOS 上左键点击显示菜单
-          .on_menu_event(|app, event| {
-            log::info!("Tray menu event: {}", event.id.as_ref());
-            match event.id.as_ref() {
-              "about" => {
-                // 检查关于窗口是否已存在
-                if let Some(about_window) = app.get_webview_window("about") {
-                  let _ = about_window.show();
-                  let _ = about_window.set_focus();
-                } else {
-                  // 创建新的关于窗口
-                  let about_url = if cfg!(debug_assertions) {
-                    "http://localhost:5173/about.html"
-                  } else {
-                    "about.html"
-                  };
-
-                  let _ = WebviewWindowBuilder::new(app, "about", WebviewUrl::App(about_url.into()))
-                    .title("关于")
-                    .inner_size(480.0, 520.0)
-                    .resizable(false)
-                    .center()
-                    .decorations(true)
-                    .build();
-                }
-              }
-              "settings" => {
-                // 设置功能待实现
-              }
-              "show" => {
-                // 显示并聚焦窗口
-                if let Some(window) = app.get_webview_window("main") {
-                  let is_visible = window.is_visible().unwrap_or(false);
-                  log::info!("Show menu clicked, window visible: {}", is_visible);
-
-                  let _ = window.unminimize();
-                  let _ = window.show();
-                  let _ = window.set_focus();
-
-                  log::info!("Window shown from tray menu");
-                }
-              }
-              "quit" => {
-                app.exit(0);
-              }
-              _ => {}
-            }
-          })
-          .on_tray_icon_event(|tray, event| {
-            log::info!("Tray icon event: {:?}", event);
-
-            // 处理所有点击事件（Click, DoubleClick, Enter 等）
-            if let Some(window) = tray.app_handle().get_webview_window("main") {
-              // 检查窗口是否可见
-              let is_visible = window.is_visible().unwrap_or(false);
-              log::info!("Window visible: {}", is_visible);
-
-              if !is_visible {
-                // 窗口隐藏时，先取消最小化，再显示，最后聚焦
-                let _ = window.unminimize();
-                let _ = window.show();
-                let _ = window.set_focus();
-                log::info!("Window restored from tray");
-              } else {
-                // 窗口已可见时，只需要聚焦
-                let _ = window.set_focus();
-                log::info!("Window focused");
-              }
-            }
-          })
-          .build(app);
-
-        match tray_result {
-          Ok(_) => log::info!("System tray created"),
-          Err(e) => log::error!("Failed to create system tray: {:?}", e),
-        }
+      // 监听配置文件变化并热重载
+      if let Err(e) = config::watch(app.handle()) {
+        log::warn!("Config hot reload disabled: {}", e);
       }
 
       Ok(())
     })
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|app, event| {
+      if let tauri::RunEvent::Exit = event {
+        window_state::flush(app);
+        if let Some(instance) = app.try_state::<SingleInstance>() {
+          instance.release();
+        }
+      }
+    });
 }
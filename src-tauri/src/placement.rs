@@ -0,0 +1,96 @@
+//! 多显示器放置：按 WindowPlacement 选择显示器（鼠标所在、主窗口所在、主显示器或指定名称），
+//! 贴靠到可用区域的角落；显示器增减、可用区域或缩放比例变化时重新放置
+
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Monitor, PhysicalPosition};
+
+use crate::config::{MonitorTarget, WindowPlacement};
+use crate::geometry::{self, Rect, Screen};
+
+/// 显示器布局检查间隔（tauri 没有显示器变化事件）
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+fn to_screen(monitor: &Monitor) -> Screen {
+    let (position, size) = (monitor.position(), monitor.size());
+    let area = monitor.work_area();
+    Screen {
+        name: monitor.name().cloned(),
+        bounds: Rect::new(position.x, position.y, size.width, size.height),
+        work_area: Rect::new(area.position.x, area.position.y, area.size.width, area.size.height),
+        scale_factor: monitor.scale_factor(),
+    }
+}
+
+/// 当前连接的屏幕，主显示器在首位
+pub fn screens(app: &AppHandle) -> Vec<Screen> {
+    let primary = app
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .map(|monitor| to_screen(&monitor));
+    let mut screens: Vec<Screen> = app
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(to_screen)
+        .collect();
+    if let Some(index) = screens.iter().position(|s| Some(s) == primary.as_ref()) {
+        screens.swap(0, index);
+    }
+    screens
+}
+
+/// 按目标选择显示器，找不到时退回主显示器
+fn target_screen<'a>(app: &AppHandle, target: &MonitorTarget, screens: &'a [Screen]) -> Option<&'a Screen> {
+    let found = match target {
+        MonitorTarget::Cursor => app
+            .cursor_position()
+            .ok()
+            .and_then(|point| geometry::screen_at(screens, point.x, point.y)),
+        MonitorTarget::MainWindow => app
+            .get_webview_window(crate::window::MAIN_WINDOW)
+            .and_then(|window| {
+                let position = window.outer_position().ok()?;
+                let size = window.outer_size().ok()?;
+                let x = position.x as f64 + size.width as f64 / 2.0;
+                let y = position.y as f64 + size.height as f64 / 2.0;
+                geometry::screen_at(screens, x, y)
+            }),
+        MonitorTarget::Primary => None,
+        MonitorTarget::Named { name } => screens.iter().find(|s| s.name.as_deref() == Some(name)),
+    };
+    if found.is_none() && !matches!(target, MonitorTarget::Primary) {
+        log::info!("Monitor for {:?} not found, using the primary monitor", target);
+    }
+    found.or_else(|| screens.first())
+}
+
+/// 计算窗口位置（物理像素）；width/height 为逻辑像素，按目标显示器的缩放比例换算
+pub fn position(
+    app: &AppHandle,
+    placement: &WindowPlacement,
+    width: f64,
+    height: f64,
+) -> Option<PhysicalPosition<i32>> {
+    let screens = screens(app);
+    let screen = target_screen(app, &placement.monitor, &screens)?;
+    let (x, y) = geometry::anchor_on(screen, placement, width, height);
+    Some(PhysicalPosition::new(x, y))
+}
+
+/// 定期检查显示器布局，变化时重新放置悬浮球和设置了 placement 的窗口
+pub fn watch(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut last = screens(&app);
+        loop {
+            std::thread::sleep(WATCH_INTERVAL);
+            let current = screens(&app);
+            if current != last {
+                log::info!("Monitor layout changed ({} screen(s)), re-anchoring windows", current.len());
+                crate::window::reanchor(&app, &last, &current);
+                last = current;
+            }
+        }
+    });
+}
@@ -0,0 +1,132 @@
+//! `--profile <name>` 启动参数：不同 profile 的实例使用各自的配置目录、数据文件、缓存、
+//! webview 数据和单实例锁，可以同时运行
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager, Runtime, WebviewWindowBuilder};
+
+static PROFILE: OnceLock<Option<String>> = OnceLock::new();
+
+/// 当前进程的 profile，未指定时为 None（默认 profile）。非 UTF-8 的参数按有损方式转换，不会成为合法的 profile 名
+pub fn current() -> Option<&'static str> {
+    PROFILE
+        .get_or_init(|| parse(std::env::args_os().map(|arg| arg.to_string_lossy().into_owned())))
+        .as_deref()
+}
+
+/// 解析 `--profile <name>` 或 `--profile=<name>`
+fn parse(args: impl Iterator<Item = String>) -> Option<String> {
+    let mut args = args.skip(1);
+    while let Some(arg) = args.next() {
+        let value = if arg == "--profile" {
+            args.next()
+        } else if let Some(value) = arg.strip_prefix("--profile=") {
+            Some(value.to_string())
+        } else {
+            continue;
+        };
+        return match value {
+            Some(name) if is_valid(&name) => Some(name),
+            other => {
+                log::warn!("Ignoring invalid --profile {:?}", other);
+                None
+            }
+        };
+    }
+    None
+}
+
+/// profile 名会出现在目录和锁文件名中，只允许字母、数字、`-` 和 `_`
+fn is_valid(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// 按 profile 隔离目录：默认 profile 直接使用 base，其他 profile 使用 `base/profiles/<name>`
+pub fn scope(base: &Path) -> PathBuf {
+    scope_to(base, current())
+}
+
+fn scope_to(base: &Path, profile: Option<&str>) -> PathBuf {
+    match profile {
+        Some(name) => base.join("profiles").join(name),
+        None => base.to_path_buf(),
+    }
+}
+
+/// 按 profile 隔离 webview 数据（localStorage、IndexedDB、Cookie 等）；默认 profile 沿用系统默认位置。
+/// 同一进程的所有窗口都要经过这里，保证共用同一份数据
+pub fn scope_webview<'a, R: Runtime, M: Manager<R>>(
+    app: &AppHandle<R>,
+    builder: WebviewWindowBuilder<'a, R, M>,
+) -> WebviewWindowBuilder<'a, R, M> {
+    let Some(name) = current() else {
+        return builder;
+    };
+    // macOS 的 WKWebView 不支持 data_directory，改用按 profile 名派生的数据存储标识（macOS 14+）
+    #[cfg(target_os = "macos")]
+    let builder = builder.data_store_identifier(data_store_id(name));
+    #[cfg(not(target_os = "macos"))]
+    let _ = name;
+    match app.path().app_local_data_dir() {
+        Ok(dir) => builder.data_directory(scope(&dir).join("webview")),
+        Err(e) => {
+            log::warn!("Failed to resolve app local data dir: {}", e);
+            builder
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn data_store_id(name: &str) -> [u8; 16] {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(format!("profile:{}", name).as_bytes());
+    let mut id = [0; 16];
+    id.copy_from_slice(&digest[..16]);
+    id
+}
+
+/// 返回启动时指定的 profile，前端据此隔离本地存储
+#[tauri::command]
+pub fn get_launch_profile() -> Option<String> {
+    current().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> impl Iterator<Item = String> {
+        std::iter::once("app")
+            .chain(list.iter().copied())
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn profile_is_read_from_either_form() {
+        assert_eq!(parse(args(&["--profile", "work"])), Some("work".to_string()));
+        assert_eq!(parse(args(&["--verbose", "--profile=work_2"])), Some("work_2".to_string()));
+        assert_eq!(parse(args(&[])), None);
+    }
+
+    #[test]
+    fn invalid_profiles_are_ignored() {
+        assert_eq!(parse(args(&["--profile"])), None);
+        assert_eq!(parse(args(&["--profile", "../etc"])), None);
+        assert_eq!(parse(args(&["--profile="])), None);
+        // 非 UTF-8 的参数有损转换后带有替换字符
+        assert_eq!(parse(args(&["--profile", "w\u{FFFD}rk"])), None);
+    }
+
+    #[test]
+    fn every_directory_is_scoped_the_same_way() {
+        for base in ["/config", "/data", "/cache", "/local-data"] {
+            let base = Path::new(base);
+            assert_eq!(scope_to(base, None), base);
+            assert_eq!(scope_to(base, Some("work")), base.join("profiles").join("work"));
+        }
+    }
+}
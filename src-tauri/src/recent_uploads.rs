@@ -0,0 +1,321 @@
+//! 最近上传记录：保存在应用数据目录（按 profile 隔离），显示为托盘的「最近上传」子菜单，
+//! 点击记录按配置的格式把链接复制到剪贴板
+
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::menu::{IsMenuItem, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Emitter, Listener, Manager, Runtime, Wry};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::config::{AppConfigData, LinkFormat};
+
+/// 动态生成的菜单项 id 前缀，配置中的菜单项不能使用
+pub const MENU_ID_PREFIX: &str = "recent-uploads:";
+const CLEAR_ID: &str = "recent-uploads:clear";
+/// 记录变化时广播，载荷为最新的列表
+pub const CHANGED_EVENT: &str = "recent-uploads:changed";
+const FILE_NAME: &str = "recent-uploads.json";
+/// 菜单中文件名的最大显示长度
+const MAX_LABEL_CHARS: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentUpload {
+    pub name: String,
+    pub url: String,
+    /// 上传时间（Unix 秒）
+    pub uploaded_at: i64,
+}
+
+/// 最近上传列表（Tauri managed state），最新的在前
+pub struct RecentUploads {
+    path: PathBuf,
+    items: Mutex<Vec<RecentUpload>>,
+}
+
+impl RecentUploads {
+    /// 从数据目录读取记录，文件不存在或损坏时从空列表开始
+    pub fn load(data_dir: PathBuf) -> Self {
+        let path = data_dir.join(FILE_NAME);
+        let items = match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                log::warn!("Ignoring corrupted {}: {}", path.display(), e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self {
+            path,
+            items: Mutex::new(items),
+        }
+    }
+
+    pub fn list(&self) -> Vec<RecentUpload> {
+        self.items.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// 修改列表并写回文件，返回修改后的列表
+    fn update(&self, f: impl FnOnce(&mut Vec<RecentUpload>)) -> Vec<RecentUpload> {
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut items);
+        if let Err(e) = self.save(&items) {
+            log::warn!("Failed to save recent uploads: {}", e);
+        }
+        items.clone()
+    }
+
+    /// 先写临时文件再重命名，避免写入中断留下半个文件
+    fn save(&self, items: &[RecentUpload]) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let text = serde_json::to_string_pretty(items).map_err(|e| e.to_string())?;
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, text).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, &self.path).map_err(|e| e.to_string())
+    }
+}
+
+/// 记录一次上传（同一链接只保留最新一条），超出配置的上限时丢弃最旧的记录
+pub fn record<R: Runtime>(app: &AppHandle<R>, name: &str, url: &str) -> Vec<RecentUpload> {
+    let Some(state) = app.try_state::<RecentUploads>() else {
+        return Vec::new();
+    };
+    let limit = AppConfigData::load().tray.recent_uploads.limit as usize;
+    let upload = RecentUpload {
+        name: name.to_string(),
+        url: url.to_string(),
+        uploaded_at: chrono::Utc::now().timestamp(),
+    };
+    let items = state.update(|items| {
+        items.retain(|item| item.url != upload.url);
+        items.insert(0, upload);
+        items.truncate(limit);
+    });
+    emit_changed(app, &items);
+    items
+}
+
+fn clear<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<RecentUploads>() else {
+        return;
+    };
+    let items = state.update(Vec::clear);
+    log::info!("Recent uploads cleared");
+    emit_changed(app, &items);
+}
+
+fn emit_changed<R: Runtime>(app: &AppHandle<R>, items: &[RecentUpload]) {
+    if let Err(e) = app.emit(CHANGED_EVENT, items) {
+        log::warn!("Failed to emit {}: {}", CHANGED_EVENT, e);
+    }
+}
+
+/// 记录变化时重建托盘菜单（setup 中调用一次）
+pub fn watch(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen_any(CHANGED_EVENT, move |_| {
+        if let Err(e) = crate::tray::apply_config(&handle, &AppConfigData::load()) {
+            log::error!("Failed to rebuild system tray: {:?}", e);
+        }
+    });
+}
+
+/// 按格式生成要复制的文本；图片使用图片语法，其他文件使用链接语法
+pub fn format_link(upload: &RecentUpload, format: LinkFormat) -> String {
+    let is_image = upload
+        .name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .is_some_and(|ext| {
+            ["png", "jpg", "jpeg", "gif", "webp", "bmp", "svg", "avif"].contains(&ext.as_str())
+        });
+    match format {
+        LinkFormat::Url => upload.url.clone(),
+        LinkFormat::Markdown => {
+            let name = upload.name.replace('[', "\\[").replace(']', "\\]");
+            let url = upload.url.replace('(', "%28").replace(')', "%29");
+            if is_image {
+                format!("![{}]({})", name, url)
+            } else {
+                format!("[{}]({})", name, url)
+            }
+        }
+        LinkFormat::Html => {
+            let name = escape_html(&upload.name);
+            let url = escape_html(&upload.url);
+            if is_image {
+                format!("<img src=\"{}\" alt=\"{}\">", url, name)
+            } else {
+                format!("<a href=\"{}\">{}</a>", url, name)
+            }
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn menu_label(upload: &RecentUpload) -> String {
+    let mut name: String = upload.name.chars().take(MAX_LABEL_CHARS).collect();
+    if upload.name.chars().count() > MAX_LABEL_CHARS {
+        name.push('…');
+    }
+    // 菜单文本中单个 & 表示助记符
+    let name = name.replace('&', "&&");
+    match Local.timestamp_opt(upload.uploaded_at, 0).single() {
+        Some(time) => format!("{}  {}", name, time.format("%m-%d %H:%M")),
+        None => name,
+    }
+}
+
+/// 构建「最近上传」子菜单：每条记录一项，末尾是「清空记录」
+pub fn build_submenu(app: &AppHandle, label: &str) -> tauri::Result<Submenu<Wry>> {
+    let limit = AppConfigData::load().tray.recent_uploads.limit as usize;
+    let mut items = app
+        .try_state::<RecentUploads>()
+        .map(|state| state.list())
+        .unwrap_or_default();
+    items.truncate(limit);
+
+    let submenu = Submenu::new(app, label, true)?;
+    if items.is_empty() {
+        submenu.append(&MenuItem::new(app, "暂无记录", false, None::<&str>)?)?;
+    }
+    for (index, upload) in items.iter().enumerate() {
+        let id = format!("{}{}", MENU_ID_PREFIX, index);
+        submenu.append(&MenuItem::with_id(app, id, menu_label(upload), true, None::<&str>)?)?;
+    }
+    let separator = PredefinedMenuItem::separator(app)?;
+    let clear = MenuItem::with_id(app, CLEAR_ID, "清空记录", !items.is_empty(), None::<&str>)?;
+    submenu.append_items(&[&separator as &dyn IsMenuItem<Wry>, &clear])?;
+    Ok(submenu)
+}
+
+/// 处理「最近上传」子菜单的点击，id 不属于该子菜单时返回 false
+pub fn handle_menu_event(app: &AppHandle, id: &str) -> bool {
+    if id == CLEAR_ID {
+        clear(app);
+        return true;
+    }
+    let Some(index) = id.strip_prefix(MENU_ID_PREFIX).and_then(|i| i.parse::<usize>().ok()) else {
+        return false;
+    };
+
+    let upload = app
+        .try_state::<RecentUploads>()
+        .and_then(|state| state.list().into_iter().nth(index));
+    let Some(upload) = upload else {
+        log::warn!("Recent upload {} no longer exists", index);
+        return true;
+    };
+    let format = AppConfigData::load().tray.recent_uploads.copy_format;
+    // 剪贴板工具是外部进程，不阻塞菜单事件所在的主线程
+    let app = app.clone();
+    std::thread::spawn(move || match crate::clipboard::write_text(&format_link(&upload, format)) {
+        Ok(()) => {
+            log::info!("Copied link of {}", upload.name);
+            let _ = app
+                .notification()
+                .builder()
+                .title("已复制链接")
+                .body(&upload.name)
+                .show();
+        }
+        Err(e) => log::error!("Failed to copy link of {}: {}", upload.name, e),
+    });
+    true
+}
+
+/// 记录一次上传（前端上传成功后调用），返回最新的列表
+#[tauri::command]
+pub fn add_recent_upload(app: AppHandle, name: String, url: String) -> Vec<RecentUpload> {
+    record(&app, &name, &url)
+}
+
+#[tauri::command]
+pub fn list_recent_uploads(app: AppHandle) -> Vec<RecentUpload> {
+    app.try_state::<RecentUploads>()
+        .map(|state| state.list())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn clear_recent_uploads(app: AppHandle) {
+    clear(&app);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tauri::test::mock_app;
+
+    fn upload(name: &str, url: &str) -> RecentUpload {
+        RecentUpload {
+            name: name.to_string(),
+            url: url.to_string(),
+            uploaded_at: 0,
+        }
+    }
+
+    #[test]
+    fn links_use_image_syntax_only_for_images() {
+        let image = upload("a [1].PNG", "https://x.test/a(1).png");
+        let file = upload("<b>.zip", "https://x.test/b.zip?a=1&b=2");
+
+        assert_eq!(format_link(&image, LinkFormat::Url), "https://x.test/a(1).png");
+        assert_eq!(format_link(&file, LinkFormat::Url), "https://x.test/b.zip?a=1&b=2");
+        assert_eq!(
+            format_link(&image, LinkFormat::Markdown),
+            "![a \\[1\\].PNG](https://x.test/a%281%29.png)"
+        );
+        assert_eq!(
+            format_link(&file, LinkFormat::Markdown),
+            "[<b>.zip](https://x.test/b.zip?a=1&b=2)"
+        );
+        assert_eq!(
+            format_link(&image, LinkFormat::Html),
+            "<img src=\"https://x.test/a(1).png\" alt=\"a [1].PNG\">"
+        );
+        assert_eq!(
+            format_link(&file, LinkFormat::Html),
+            "<a href=\"https://x.test/b.zip?a=1&amp;b=2\">&lt;b&gt;.zip</a>"
+        );
+    }
+
+    #[test]
+    fn records_are_deduplicated_limited_and_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = mock_app();
+        app.manage(RecentUploads::load(dir.path().to_path_buf()));
+        let limit = AppConfigData::load().tray.recent_uploads.limit as usize;
+
+        for i in 0..limit + 2 {
+            record(app.handle(), &format!("{}.png", i), &format!("https://x.test/{}", i));
+        }
+        // 重复的链接移到最前，不占两条
+        let items = record(app.handle(), "again.png", "https://x.test/5");
+        assert_eq!(items.len(), limit);
+        assert_eq!(items[0].name, "again.png");
+        assert_eq!(items.iter().filter(|item| item.url == "https://x.test/5").count(), 1);
+        assert_eq!(items[1].url, format!("https://x.test/{}", limit + 1));
+        assert!(items.iter().all(|item| item.url != "https://x.test/0"));
+
+        let reloaded = RecentUploads::load(dir.path().to_path_buf()).list();
+        let urls: Vec<&str> = reloaded.iter().map(|item| item.url.as_str()).collect();
+        let expected: Vec<&str> = items.iter().map(|item| item.url.as_str()).collect();
+        assert_eq!(urls, expected);
+    }
+
+    #[test]
+    fn corrupted_file_starts_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(FILE_NAME), "{not json").unwrap();
+        assert!(RecentUploads::load(dir.path().to_path_buf()).list().is_empty());
+    }
+}
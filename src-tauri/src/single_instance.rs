@@ -1,40 +1,252 @@
+use serde::{Deserialize, Serialize};
 use std::fs::{File, OpenOptions};
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
 
+/// 转发启动参数时的读写超时
+const IPC_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 后启动的进程转发给已运行实例的启动参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecondInstanceArgs {
+    pub args: Vec<String>,
+    pub cwd: String,
+}
+
+impl SecondInstanceArgs {
+    /// 当前进程的启动参数和工作目录；非 UTF-8 的参数按有损方式转换
+    pub fn current() -> Self {
+        Self {
+            args: std::env::args_os()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect(),
+            cwd: std::env::current_dir()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// 单实例锁；需要在应用整个生命周期内持有（放入 Tauri managed state），退出时调用 release
 pub struct SingleInstance {
-    lock_file: Option<File>,
+    lock_file: Mutex<Option<File>>,
     lock_path: PathBuf,
+    ipc_path: PathBuf,
 }
 
 impl SingleInstance {
-    pub fn new(app_name: &str) -> Result<Self, String> {
-        let lock_path = Self::get_lock_path(app_name)?;
+    /// profile 不同的实例互不影响（见 get_lock_path）
+    pub fn new(app_name: &str, profile: Option<&str>) -> Result<Self, String> {
+        Self::acquire(Self::get_lock_path(app_name, profile)?, Self::try_acquire_lock)
+    }
 
+    fn acquire(
+        lock_path: PathBuf,
+        try_lock: fn(&Path) -> Result<File, String>,
+    ) -> Result<Self, String> {
         // 尝试创建锁文件
-        let lock_file = Self::try_acquire_lock(&lock_path)?;
+        let mut lock_file = try_lock(&lock_path).map_err(|e| match read_pid(&lock_path) {
+            Some(pid) => format!("{} (pid {})", e, pid),
+            None => e,
+        })?;
+
+        // 正常退出时会清空 PID；仍有其他进程的 PID 说明上一个实例崩溃或被强制结束，锁已随进程退出释放
+        if let Some(pid) = read_pid_from(&mut lock_file).filter(|&pid| pid != std::process::id()) {
+            log::warn!("Recovered stale lock left by pid {}", pid);
+        }
+        // 写入当前 PID，便于排查以及在无法加锁的平台上识别残留的锁文件
+        write_pid(&mut lock_file).map_err(|e| format!("Failed to write lock file: {}", e))?;
 
         Ok(Self {
-            lock_file: Some(lock_file),
+            lock_file: Mutex::new(Some(lock_file)),
+            ipc_path: Self::get_ipc_path(&lock_path),
             lock_path,
         })
     }
 
-    fn get_lock_path(app_name: &str) -> Result<PathBuf, String> {
-        let temp_dir = std::env::temp_dir();
-        let lock_name = format!("{}.lock", app_name.replace(" ", "-").to_lowercase());
-        Ok(temp_dir.join(lock_name))
+    /// 释放锁并清理 IPC 端点；可重复调用
+    pub fn release(&self) {
+        let Some(file) = self.lock_file.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+            return;
+        };
+        let _ = std::fs::remove_file(&self.ipc_path);
+        // 锁文件本身保留（只清空 PID）：删除后再创建会让并发启动的进程锁住不同的文件
+        let _ = file.set_len(0);
+        drop(file);
+        #[cfg(not(any(unix, windows)))]
+        let _ = std::fs::remove_file(&self.lock_path);
+        log::info!("Single instance lock released: {}", self.lock_path.display());
+    }
+
+    /// 在后台线程接收后续启动的进程转发来的参数
+    pub fn listen<F>(&self, on_message: F) -> Result<(), String>
+    where
+        F: Fn(SecondInstanceArgs) + Send + 'static,
+    {
+        let listener = ipc::bind(&self.ipc_path)?;
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let result = stream
+                    .map_err(|e| e.to_string())
+                    .and_then(|mut stream| {
+                        let _ = stream.set_read_timeout(Some(IPC_TIMEOUT));
+                        let message = read_message(&mut stream)?;
+                        // 回复确认，发送方据此判断是否需要自行提示
+                        stream.write_all(b"ok\n").map_err(|e| e.to_string())?;
+                        Ok(message)
+                    });
+                match result {
+                    Ok(message) => on_message(message),
+                    Err(e) => log::warn!("Failed to read from second instance: {}", e),
+                }
+            }
+        });
+        Ok(())
     }
 
+    /// 把启动参数发给已运行的实例，收到确认才返回 Ok
+    pub fn notify_running(
+        app_name: &str,
+        profile: Option<&str>,
+        message: &SecondInstanceArgs,
+    ) -> Result<(), String> {
+        let lock_path = Self::get_lock_path(app_name, profile)?;
+        let mut stream = ipc::connect(&Self::get_ipc_path(&lock_path))?;
+        stream
+            .set_read_timeout(Some(IPC_TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(IPC_TIMEOUT)))
+            .map_err(|e| e.to_string())?;
+
+        let mut line = serde_json::to_string(message).map_err(|e| e.to_string())?;
+        line.push('\n');
+        stream.write_all(line.as_bytes()).map_err(|e| e.to_string())?;
+
+        let mut ack = String::new();
+        BufReader::new(stream)
+            .read_line(&mut ack)
+            .map_err(|e| e.to_string())?;
+        if ack.trim() == "ok" {
+            Ok(())
+        } else {
+            Err("Running instance did not acknowledge".to_string())
+        }
+    }
+
+    /// IPC 端点与锁文件放在一起：Unix 上是 socket，其他平台是记录本地端口的文件
+    fn get_ipc_path(lock_path: &Path) -> PathBuf {
+        if cfg!(unix) {
+            lock_path.with_extension("sock")
+        } else {
+            lock_path.with_extension("port")
+        }
+    }
+
+    /// 锁文件按用户和 profile 区分：`<运行时目录>/<app>-<用户>[-<profile>].lock`
+    fn get_lock_path(app_name: &str, profile: Option<&str>) -> Result<PathBuf, String> {
+        Ok(Self::lock_path_in(&Self::runtime_dir(app_name)?, app_name, profile))
+    }
+
+    fn lock_path_in(dir: &Path, app_name: &str, profile: Option<&str>) -> PathBuf {
+        let mut lock_name = format!(
+            "{}-{}",
+            app_name.replace(" ", "-").to_lowercase(),
+            Self::user_id()
+        );
+        if let Some(profile) = profile {
+            lock_name.push('-');
+            lock_name.push_str(profile);
+        }
+        dir.join(format!("{}.lock", lock_name))
+    }
+
+    /// 优先使用按用户隔离的 XDG_RUNTIME_DIR，否则使用临时目录下只有当前用户能访问的子目录
+    fn runtime_dir(app_name: &str) -> Result<PathBuf, String> {
+        if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_dir())
+        {
+            return Ok(dir);
+        }
+        let name = format!(
+            "{}-{}",
+            app_name.replace(" ", "-").to_lowercase(),
+            Self::user_id()
+        );
+        Self::private_dir(&std::env::temp_dir().join(name))
+    }
+
+    /// 创建权限为 0700 的目录；已存在时必须是当前用户所有、其他用户无权访问的目录（不能是符号链接），
+    /// 否则其他用户可以预先放置锁文件或指向别处的链接
     #[cfg(unix)]
-    fn try_acquire_lock(path: &PathBuf) -> Result<File, String> {
+    fn private_dir(dir: &Path) -> Result<PathBuf, String> {
+        use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+        match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(format!("Failed to create {}: {}", dir.display(), e)),
+        }
+        let metadata = std::fs::symlink_metadata(dir)
+            .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+        if !metadata.is_dir()
+            || metadata.uid() != unsafe { libc::getuid() }
+            || metadata.mode() & 0o077 != 0
+        {
+            return Err(format!(
+                "{} is not a private directory of the current user",
+                dir.display()
+            ));
+        }
+        Ok(dir.to_path_buf())
+    }
+
+    /// Windows 的临时目录本身按用户隔离
+    #[cfg(not(unix))]
+    fn private_dir(dir: &Path) -> Result<PathBuf, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        Ok(dir.to_path_buf())
+    }
+
+    /// 临时目录可能被多个用户共享，文件名中加入用户标识
+    #[cfg(unix)]
+    fn user_id() -> String {
+        unsafe { libc::getuid() }.to_string()
+    }
+
+    #[cfg(not(unix))]
+    fn user_id() -> String {
+        std::env::var("USERNAME")
+            .ok()
+            .map(|name| name.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "user".to_string())
+    }
+
+    #[cfg(unix)]
+    fn try_acquire_lock(path: &Path) -> Result<File, String> {
+        use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
         use std::os::unix::io::AsRawFd;
 
+        // 不跟随符号链接，且只使用自己的锁文件：共享目录中其他用户放置的文件不能被写入 PID
         let file = OpenOptions::new()
             .create(true)
+            .truncate(false)
+            .read(true)
             .write(true)
+            .mode(0o600)
+            .custom_flags(libc::O_NOFOLLOW)
             .open(path)
             .map_err(|e| format!("Failed to open lock file: {}", e))?;
+        let owner = file
+            .metadata()
+            .map_err(|e| format!("Failed to read lock file: {}", e))?
+            .uid();
+        if owner != unsafe { libc::getuid() } {
+            return Err(format!("Lock file {} belongs to another user", path.display()));
+        }
 
         // 尝试获取文件锁
         let fd = file.as_raw_fd();
@@ -50,41 +262,365 @@ impl SingleInstance {
     }
 
     #[cfg(windows)]
-    fn try_acquire_lock(path: &PathBuf) -> Result<File, String> {
-        // Windows: 使用独占打开模式
-        // 注意：这个实现在 Windows 上可能不够可靠
-        // 建议用户在 Windows 上使用第三方单实例解决方案
+    fn try_acquire_lock(path: &Path) -> Result<File, String> {
+        use std::os::windows::fs::OpenOptionsExt;
+
+        // Windows: 不共享的独占打开，进程退出（包括崩溃）时句柄由系统关闭
         OpenOptions::new()
             .create(true)
+            .truncate(false)
+            .read(true)
             .write(true)
+            .share_mode(0)
             .open(path)
             .map_err(|_| "Failed to acquire lock (another instance may be running)".to_string())
     }
 
     #[cfg(not(any(unix, windows)))]
-    fn try_acquire_lock(path: &PathBuf) -> Result<File, String> {
-        // 其他平台使用简单的文件存在检查
-        if path.exists() {
-            return Err("Another instance is already running".to_string());
+    fn try_acquire_lock(path: &Path) -> Result<File, String> {
+        Self::try_acquire_pid_lock(path)
+    }
+
+    /// 没有文件锁的平台：锁文件存在且记录的进程仍在运行时视为已被占用，
+    /// 否则（崩溃残留、PID 已清空）删除后重新创建
+    #[cfg(not(windows))]
+    #[cfg_attr(unix, allow(dead_code))]
+    fn try_acquire_pid_lock(path: &Path) -> Result<File, String> {
+        let own = std::process::id();
+        // 先把 PID 写进临时文件再硬链接到锁文件：链接是原子的且目标存在时失败，
+        // 其他进程看到的锁文件总是带着 PID
+        let tmp = path.with_extension(format!("lock.{}", own));
+        std::fs::write(&tmp, own.to_string())
+            .map_err(|e| format!("Failed to create lock file: {}", e))?;
+
+        let mut result = Err("Another instance is already running".to_string());
+        for _ in 0..2 {
+            match std::fs::hard_link(&tmp, path) {
+                Ok(()) => {
+                    result = OpenOptions::new()
+                        .read(true)
+                        .write(true)
+                        .open(path)
+                        .map_err(|e| format!("Failed to open lock file: {}", e));
+                    break;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => match read_pid(path) {
+                    Some(pid) if pid != own && process_alive(pid) => break,
+                    _ => {
+                        log::warn!("Removing stale lock file {}", path.display());
+                        let _ = std::fs::remove_file(path);
+                    }
+                },
+                Err(e) => {
+                    result = Err(format!("Failed to create lock file: {}", e));
+                    break;
+                }
+            }
         }
+        let _ = std::fs::remove_file(&tmp);
+        result
+    }
+}
 
-        OpenOptions::new()
-            .create(true)
-            .write(true)
-            .open(path)
-            .map_err(|e| format!("Failed to create lock file: {}", e))
+/// 进程是否仍在运行（无权向其发信号的进程也算运行中）
+#[cfg(unix)]
+#[allow(dead_code)]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// 有 /proc 时按进程目录判断；无法判断时保守地视为仍在运行
+#[cfg(not(any(unix, windows)))]
+fn process_alive(pid: u32) -> bool {
+    let proc_dir = Path::new("/proc");
+    !proc_dir.is_dir() || proc_dir.join(pid.to_string()).exists()
+}
+
+/// 锁文件中记录的持有者 PID
+fn read_pid(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn read_pid_from(file: &mut File) -> Option<u32> {
+    let mut text = String::new();
+    file.read_to_string(&mut text).ok()?;
+    text.trim().parse().ok()
+}
+
+fn write_pid(file: &mut File) -> std::io::Result<()> {
+    file.set_len(0)?;
+    file.rewind()?;
+    file.write_all(std::process::id().to_string().as_bytes())?;
+    file.sync_all()
+}
+
+/// 读取一行 JSON 消息
+fn read_message(stream: &mut impl Read) -> Result<SecondInstanceArgs, String> {
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+    serde_json::from_str(&line).map_err(|e| format!("Invalid message: {}", e))
+}
+
+#[cfg(unix)]
+mod ipc {
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::Path;
+
+    pub fn bind(path: &Path) -> Result<UnixListener, String> {
+        // 已持有锁，残留的 socket 一定来自退出的旧实例
+        let _ = std::fs::remove_file(path);
+        let listener = UnixListener::bind(path)
+            .map_err(|e| format!("Failed to bind {}: {}", path.display(), e))?;
+        let _ = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600));
+        Ok(listener)
+    }
+
+    pub fn connect(path: &Path) -> Result<UnixStream, String> {
+        UnixStream::connect(path).map_err(|e| format!("Failed to connect {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(not(unix))]
+mod ipc {
+    use std::net::{Ipv4Addr, TcpListener, TcpStream};
+    use std::path::Path;
+
+    pub fn bind(path: &Path) -> Result<TcpListener, String> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).map_err(|e| e.to_string())?;
+        let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+        std::fs::write(path, port.to_string())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(listener)
+    }
+
+    pub fn connect(path: &Path) -> Result<TcpStream, String> {
+        let port: u16 = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+            .trim()
+            .parse()
+            .map_err(|e| format!("Invalid port in {}: {}", path.display(), e))?;
+        TcpStream::connect((Ipv4Addr::LOCALHOST, port)).map_err(|e| e.to_string())
     }
 }
 
 impl Drop for SingleInstance {
     fn drop(&mut self) {
-        // 释放锁文件
-        if let Some(mut file) = self.lock_file.take() {
-            let _ = file.flush();
-            drop(file);
+        self.release();
+    }
+}
 
-            // 删除锁文件
-            let _ = std::fs::remove_file(&self.lock_path);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::{Child, ChildStdout, Command, Stdio};
+
+    /// 辅助进程持有的锁文件路径
+    const HOLDER_PATH: &str = "SINGLE_INSTANCE_TEST_LOCK";
+    /// 设置时辅助进程使用 PID 锁（无文件锁平台的实现）
+    const HOLDER_PID_LOCK: &str = "SINGLE_INSTANCE_TEST_PID_LOCK";
+
+    /// 在另一个进程中持有锁，直到标准输入关闭
+    #[test]
+    #[ignore = "helper process for the two-process tests"]
+    fn lock_holder() {
+        let Some(path) = std::env::var_os(HOLDER_PATH) else {
+            return;
+        };
+        let try_lock = lock_fn(std::env::var_os(HOLDER_PID_LOCK).is_some());
+        let _instance = SingleInstance::acquire(PathBuf::from(path), try_lock).unwrap();
+        println!("locked");
+        let _ = std::io::stdin().read_line(&mut String::new());
+    }
+
+    #[cfg(not(windows))]
+    fn lock_fn(pid_lock: bool) -> fn(&Path) -> Result<File, String> {
+        if pid_lock {
+            SingleInstance::try_acquire_pid_lock
+        } else {
+            SingleInstance::try_acquire_lock
         }
     }
+
+    #[cfg(windows)]
+    fn lock_fn(_pid_lock: bool) -> fn(&Path) -> Result<File, String> {
+        SingleInstance::try_acquire_lock
+    }
+
+    /// 持有锁的辅助进程；保留 stdout 以免测试框架的后续输出写入失败
+    struct Holder {
+        child: Child,
+        stdout: BufReader<ChildStdout>,
+    }
+
+    /// 启动持有 path 的辅助进程，等它拿到锁后返回
+    fn spawn_holder(path: &Path, pid_lock: bool) -> Holder {
+        let mut command = Command::new(std::env::current_exe().unwrap());
+        command
+            .args([
+                "--exact",
+                "single_instance::tests::lock_holder",
+                "--ignored",
+                "--nocapture",
+            ])
+            .env(HOLDER_PATH, path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped());
+        if pid_lock {
+            command.env(HOLDER_PID_LOCK, "1");
+        }
+        let mut child = command.spawn().unwrap();
+        let mut stdout = BufReader::new(child.stdout.take().unwrap());
+        // 测试框架把 "test ... " 和辅助进程的输出打印在同一行
+        let mut line = String::new();
+        while stdout.read_line(&mut line).unwrap() > 0 && !line.trim_end().ends_with("locked") {
+            line.clear();
+        }
+        assert!(
+            line.trim_end().ends_with("locked"),
+            "holder process exited without taking the lock"
+        );
+        Holder { child, stdout }
+    }
+
+    fn release_holder(mut holder: Holder) {
+        drop(holder.child.stdin.take());
+        let _ = std::io::copy(&mut holder.stdout, &mut std::io::sink());
+        assert!(holder.child.wait().unwrap().success());
+    }
+
+    #[test]
+    fn a_second_process_cannot_take_a_held_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = SingleInstance::lock_path_in(dir.path(), "Test App", None);
+
+        let holder = spawn_holder(&path, false);
+        let err = SingleInstance::acquire(path.clone(), SingleInstance::try_acquire_lock)
+            .err()
+            .expect("lock is held by the other process");
+        // Windows 上独占打开的锁文件读不到 PID
+        assert!(
+            cfg!(windows) || err.contains(&format!("pid {}", holder.child.id())),
+            "{}",
+            err
+        );
+
+        release_holder(holder);
+        let instance =
+            SingleInstance::acquire(path.clone(), SingleInstance::try_acquire_lock).unwrap();
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+        instance.release();
+    }
+
+    #[test]
+    fn profiles_lock_independently() {
+        let dir = tempfile::tempdir().unwrap();
+        let default_profile = SingleInstance::lock_path_in(dir.path(), "Test App", None);
+        let work_profile = SingleInstance::lock_path_in(dir.path(), "Test App", Some("work"));
+        assert_ne!(default_profile, work_profile);
+
+        let holder = spawn_holder(&work_profile, false);
+        // 另一个 profile 可以同时运行
+        let default_instance =
+            SingleInstance::acquire(default_profile, SingleInstance::try_acquire_lock).unwrap();
+        // 同一个 profile 不能运行两次
+        assert!(SingleInstance::acquire(work_profile, SingleInstance::try_acquire_lock).is_err());
+
+        release_holder(holder);
+        default_instance.release();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lock_file_symlinks_are_not_followed() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("target");
+        std::fs::write(&target, "keep").unwrap();
+        let path = SingleInstance::lock_path_in(dir.path(), "Test App", None);
+        std::os::unix::fs::symlink(&target, &path).unwrap();
+
+        assert!(SingleInstance::acquire(path, SingleInstance::try_acquire_lock).is_err());
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "keep");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fallback_dir_must_be_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let private = dir.path().join("private");
+        assert_eq!(SingleInstance::private_dir(&private).unwrap(), private);
+        let mode = std::fs::metadata(&private).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        // 已存在的目录再次使用
+        assert!(SingleInstance::private_dir(&private).is_ok());
+
+        let shared = dir.path().join("shared");
+        std::fs::create_dir(&shared).unwrap();
+        std::fs::set_permissions(&shared, std::fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(SingleInstance::private_dir(&shared).is_err());
+
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&private, &link).unwrap();
+        assert!(SingleInstance::private_dir(&link).is_err());
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn pid_lock_is_refused_while_the_holder_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = SingleInstance::lock_path_in(dir.path(), "Test App", None);
+
+        let holder = spawn_holder(&path, true);
+        assert_eq!(read_pid(&path), Some(holder.child.id()));
+        assert!(
+            SingleInstance::acquire(path.clone(), SingleInstance::try_acquire_pid_lock).is_err()
+        );
+
+        release_holder(holder);
+        let instance =
+            SingleInstance::acquire(path.clone(), SingleInstance::try_acquire_pid_lock).unwrap();
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+        instance.release();
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn pid_lock_left_by_a_killed_process_is_recovered() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = SingleInstance::lock_path_in(dir.path(), "Test App", None);
+
+        let mut holder = spawn_holder(&path, true);
+        let pid = holder.child.id();
+        holder.child.kill().unwrap();
+        holder.child.wait().unwrap();
+        // 被强制结束的进程来不及清理，锁文件里仍是它的 PID
+        assert_eq!(read_pid(&path), Some(pid));
+
+        let instance =
+            SingleInstance::acquire(path.clone(), SingleInstance::try_acquire_pid_lock).unwrap();
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+        instance.release();
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn pid_lock_can_be_taken_again_after_release() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = SingleInstance::lock_path_in(dir.path(), "Test App", None);
+
+        let first =
+            SingleInstance::acquire(path.clone(), SingleInstance::try_acquire_pid_lock).unwrap();
+        first.release();
+        let second =
+            SingleInstance::acquire(path.clone(), SingleInstance::try_acquire_pid_lock).unwrap();
+        second.release();
+    }
 }
@@ -0,0 +1,194 @@
+//! 测试用的本地 HTTP 服务：监听随机端口，每个连接在独立线程中处理一个请求（只支持测试用到的
+//! HTTP/1.1 子集：按 Content-Length 读取请求体，响应后关闭连接）
+
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub path: String,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap_or(Value::Null)
+    }
+
+    /// multipart 请求中普通字段的值
+    pub fn field(&self, name: &str) -> Option<String> {
+        let text = String::from_utf8_lossy(&self.body);
+        let marker = format!("name=\"{}\"\r\n\r\n", name);
+        let start = text.find(&marker)? + marker.len();
+        let end = text[start..].find("\r\n")?;
+        Some(text[start..start + end].to_string())
+    }
+
+    /// multipart 请求中上传的文件名
+    pub fn file_name(&self) -> Option<String> {
+        let text = String::from_utf8_lossy(&self.body);
+        let start = text.find("filename=\"")? + "filename=\"".len();
+        let end = text[start..].find('"')?;
+        Some(text[start..start + end].to_string())
+    }
+
+    /// multipart 请求中上传的文件内容
+    pub fn file(&self) -> Option<&[u8]> {
+        let start = find(&self.body, b"filename=\"")?;
+        let start = start + find(&self.body[start..], b"\r\n\r\n")? + 4;
+        let end = self.body.windows(4).rposition(|window| window == b"\r\n--")?;
+        self.body.get(start..end)
+    }
+
+    /// 请求体中是否包含这段内容
+    pub fn contains(&self, bytes: &[u8]) -> bool {
+        find(&self.body, bytes).is_some()
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    /// 成功响应：`{ code: 200, data }`
+    pub fn ok(data: Value) -> Self {
+        Self {
+            status: 200,
+            body: json!({ "code": 200, "message": "ok", "data": data }),
+        }
+    }
+
+    /// 失败响应：HTTP 状态码和业务 code 相同
+    pub fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: json!({ "code": status, "message": message }),
+        }
+    }
+}
+
+/// 处理函数返回 None 时不响应直接断开连接，模拟网络错误
+type Handler = dyn Fn(&Request) -> Option<Response> + Send + Sync;
+
+pub struct TestServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl TestServer {
+    pub fn start(handler: impl Fn(&Request) -> Option<Response> + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+        let recorded = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let (handler, recorded) = (handler.clone(), recorded.clone());
+                std::thread::spawn(move || serve(stream, &*handler, &recorded));
+            }
+        });
+        Self { url, requests }
+    }
+
+    /// 已收到的完整请求，按到达顺序
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// 路径以 suffix 结尾的请求
+    pub fn requests_to(&self, suffix: &str) -> Vec<Request> {
+        self.requests()
+            .into_iter()
+            .filter(|request| request.path.ends_with(suffix))
+            .collect()
+    }
+}
+
+fn serve(stream: TcpStream, handler: &Handler, recorded: &Mutex<Vec<Request>>) {
+    let Some(request) = read_request(&stream) else {
+        return;
+    };
+    recorded.lock().unwrap().push(request.clone());
+    let Some(response) = handler(&request) else {
+        return;
+    };
+    let body = response.body.to_string();
+    let mut stream = stream;
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {} Test\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        body.len(),
+        body
+    );
+}
+
+fn read_request(stream: &TcpStream) -> Option<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let path = line.split_whitespace().nth(1)?.to_string();
+    let mut length = 0;
+    loop {
+        line.clear();
+        reader.read_line(&mut line).ok()?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().ok()?;
+            }
+        }
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+    Some(Request { path, body })
+}
+
+/// 让处理函数停住，直到测试放行
+#[derive(Default)]
+pub struct Gate {
+    open: Mutex<bool>,
+    changed: Condvar,
+    /// 正在等待放行的请求数
+    waiting: Mutex<usize>,
+}
+
+impl Gate {
+    pub fn wait(&self) {
+        *self.waiting.lock().unwrap() += 1;
+        let open = self.open.lock().unwrap();
+        let _open = self.changed.wait_while(open, |open| !*open).unwrap();
+        *self.waiting.lock().unwrap() -= 1;
+    }
+
+    pub fn open(&self) {
+        *self.open.lock().unwrap() = true;
+        self.changed.notify_all();
+    }
+
+    pub fn waiting(&self) -> usize {
+        *self.waiting.lock().unwrap()
+    }
+}
+
+/// 轮询直到条件成立，超时则失败
+pub fn wait_until(what: &str, mut condition: impl FnMut() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !condition() {
+        assert!(Instant::now() < deadline, "timed out waiting for {}", what);
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
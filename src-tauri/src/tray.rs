@@ -0,0 +1,566 @@
+use image::GenericImageView;
+use serde_json::{json, Map, Value};
+use tauri::image::Image;
+use tauri::menu::{
+    CheckMenuItem, IconMenuItem, IsMenuItem, Menu, MenuEvent, MenuItem, MenuItemKind,
+    PredefinedMenuItem, Submenu,
+};
+use tauri::path::BaseDirectory;
+use tauri::tray::{TrayIcon, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+use crate::config::{AppConfigData, TrayAction, TrayMenuEntry};
+
+pub const TRAY_ID: &str = "main-tray";
+
+/// 构建菜单时需要的上下文：复选项的勾选状态取自 settings，被策略锁定的配置项对应的复选项置灰
+struct MenuContext {
+    settings: Value,
+    locked: Vec<String>,
+}
+
+impl MenuContext {
+    fn checked(&self, setting: &str) -> bool {
+        self.settings
+            .pointer(&setting_pointer(setting))
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    fn is_locked(&self, setting: &str) -> bool {
+        self.locked
+            .iter()
+            .any(|key| setting == key || setting.starts_with(&format!("{}.", key)))
+    }
+}
+
+/// 配置项路径 `a.b.c` 转为 JSON Pointer `/a/b/c`
+fn setting_pointer(setting: &str) -> String {
+    format!("/{}", setting.replace('.', "/"))
+}
+
+/// 菜单节点的平台无关描述，由 build_entry 转换为 tauri 菜单项
+#[derive(Debug, Clone, PartialEq)]
+enum MenuSpec<'a> {
+    Item {
+        id: &'a str,
+        label: &'a str,
+        enabled: bool,
+        icon: Option<&'a str>,
+        accelerator: Option<&'a str>,
+    },
+    Check {
+        id: &'a str,
+        label: &'a str,
+        enabled: bool,
+        checked: bool,
+        accelerator: Option<&'a str>,
+    },
+    Separator,
+    RecentUploads {
+        label: &'a str,
+    },
+    Submenu {
+        label: &'a str,
+        enabled: bool,
+        items: Vec<MenuSpec<'a>>,
+    },
+}
+
+/// 配置节点转换为菜单描述，子菜单递归转换。
+/// id 以「最近上传」保留前缀开头的菜单项会被当作最近上传记录处理，跳过并记录日志
+fn menu_spec<'a>(ctx: &MenuContext, entries: &'a [TrayMenuEntry]) -> Vec<MenuSpec<'a>> {
+    let reserved = |id: &str| {
+        let reserved = id.starts_with(crate::recent_uploads::MENU_ID_PREFIX);
+        if reserved {
+            log::warn!("Skipping tray menu item with reserved id {}", id);
+        }
+        reserved
+    };
+    entries
+        .iter()
+        .filter_map(|entry| {
+            Some(match entry {
+                TrayMenuEntry::Item(item) if reserved(&item.id) => return None,
+                TrayMenuEntry::Item(item) => MenuSpec::Item {
+                    id: &item.id,
+                    label: &item.label,
+                    enabled: !item.disabled,
+                    icon: item.icon.as_deref(),
+                    accelerator: item.accelerator.as_deref(),
+                },
+                TrayMenuEntry::Check(check) if reserved(&check.id) => return None,
+                TrayMenuEntry::Check(check) => MenuSpec::Check {
+                    id: &check.id,
+                    label: &check.label,
+                    enabled: !check.disabled && !ctx.is_locked(&check.setting),
+                    checked: ctx.checked(&check.setting),
+                    accelerator: check.accelerator.as_deref(),
+                },
+                TrayMenuEntry::Separator => MenuSpec::Separator,
+                TrayMenuEntry::RecentUploads(recent) => MenuSpec::RecentUploads {
+                    label: &recent.label,
+                },
+                TrayMenuEntry::Submenu(submenu) => MenuSpec::Submenu {
+                    label: &submenu.label,
+                    enabled: !submenu.disabled,
+                    items: menu_spec(ctx, &submenu.items),
+                },
+            })
+        })
+        .collect()
+}
+
+/// 根据 TrayConfig.menus 构建托盘菜单
+pub fn build_menu(app: &AppHandle, config: &AppConfigData) -> tauri::Result<Menu<Wry>> {
+    let ctx = MenuContext {
+        settings: serde_json::to_value(config).unwrap_or_default(),
+        locked: crate::config::get_app_config().locked,
+    };
+    let items = build_entries(app, &menu_spec(&ctx, &config.tray.menus))?;
+    Menu::with_items(app, &as_refs(&items))
+}
+
+fn as_refs(items: &[MenuItemKind<Wry>]) -> Vec<&dyn IsMenuItem<Wry>> {
+    items.iter().map(|item| item as &dyn IsMenuItem<Wry>).collect()
+}
+
+fn build_entries(app: &AppHandle, specs: &[MenuSpec]) -> tauri::Result<Vec<MenuItemKind<Wry>>> {
+    specs.iter().map(|spec| build_entry(app, spec)).collect()
+}
+
+/// 菜单描述转换为 tauri 菜单项，子菜单递归构建
+fn build_entry(app: &AppHandle, spec: &MenuSpec) -> tauri::Result<MenuItemKind<Wry>> {
+    Ok(match spec {
+        MenuSpec::Item {
+            id,
+            label,
+            enabled,
+            icon,
+            accelerator,
+        } => match icon.and_then(|path| load_icon(app, path)) {
+            Some(icon) => MenuItemKind::Icon(IconMenuItem::with_id(
+                app,
+                id,
+                label,
+                *enabled,
+                Some(icon),
+                *accelerator,
+            )?),
+            None => MenuItemKind::MenuItem(MenuItem::with_id(app, id, label, *enabled, *accelerator)?),
+        },
+        MenuSpec::Check {
+            id,
+            label,
+            enabled,
+            checked,
+            accelerator,
+        } => MenuItemKind::Check(CheckMenuItem::with_id(
+            app,
+            id,
+            label,
+            *enabled,
+            *checked,
+            *accelerator,
+        )?),
+        MenuSpec::Separator => MenuItemKind::Predefined(PredefinedMenuItem::separator(app)?),
+        MenuSpec::RecentUploads { label } => {
+            MenuItemKind::Submenu(crate::recent_uploads::build_submenu(app, label)?)
+        }
+        MenuSpec::Submenu {
+            label,
+            enabled,
+            items,
+        } => {
+            let items = build_entries(app, items)?;
+            MenuItemKind::Submenu(Submenu::with_items(app, label, *enabled, &as_refs(&items))?)
+        }
+    })
+}
+
+fn decode_image(bytes: &[u8]) -> Result<Image<'static>, image::ImageError> {
+    let img = image::load_from_memory(bytes)?;
+    let (width, height) = img.dimensions();
+    Ok(Image::new_owned(img.to_rgba8().into_raw(), width, height))
+}
+
+/// 加载菜单项图标，失败时记录日志并显示为无图标的菜单项
+fn load_icon(app: &AppHandle, path: &str) -> Option<Image<'static>> {
+    let resolved = match app.path().resolve(path, BaseDirectory::Resource) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            log::warn!("Failed to resolve tray icon {}: {}", path, e);
+            return None;
+        }
+    };
+    let bytes = std::fs::read(&resolved)
+        .map_err(|e| log::warn!("Failed to read tray icon {}: {}", resolved.display(), e))
+        .ok()?;
+    decode_image(&bytes)
+        .map_err(|e| log::warn!("Invalid tray icon {}: {}", resolved.display(), e))
+        .ok()
+}
+
+/// 创建系统托盘
+pub fn create(app: &AppHandle, config: &AppConfigData) -> tauri::Result<TrayIcon> {
+    let menu = build_menu(app, config)?;
+
+    // 使用配置中的 tooltip 和 title
+    let tooltip = config.tray.tooltip.as_deref().unwrap_or(&config.name);
+    let title = config.tray.title.as_deref().unwrap_or("");
+
+    let tray = TrayIconBuilder::with_id(TRAY_ID)
+        .icon(crate::tray_status::base_icon())
+        .icon_as_template(true) // macOS 模板图标
+        .tooltip(tooltip)
+        .title(title)
+        .menu(&menu)
+        .show_menu_on_left_click(true) // macOS 上左键点击显示菜单
+        .on_menu_event(handle_menu_event)
+        .on_tray_icon_event(handle_tray_icon_event)
+        .build(app)?;
+
+    // 托盘被移除后重新创建时恢复进行中的任务状态
+    crate::tray_status::apply(app, &tray, config)?;
+    Ok(tray)
+}
+
+/// 配置变化后重建托盘：按 enabled 创建或移除，并刷新菜单、tooltip 和 title
+pub fn apply_config(app: &AppHandle, config: &AppConfigData) -> tauri::Result<()> {
+    let existing = app.tray_by_id(TRAY_ID);
+
+    if !config.tray.enabled {
+        if existing.is_some() {
+            app.remove_tray_by_id(TRAY_ID);
+            log::info!("System tray removed");
+        }
+        return Ok(());
+    }
+
+    match existing {
+        Some(tray) => {
+            let menu = build_menu(app, config)?;
+            tray.set_menu(Some(menu))?;
+            crate::tray_status::apply(app, &tray, config)?;
+            log::info!("System tray updated");
+        }
+        None => {
+            create(app, config)?;
+            log::info!("System tray created");
+        }
+    }
+    Ok(())
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    let id = event.id.as_ref();
+    log::info!("Tray menu event: {}", id);
+    if crate::recent_uploads::handle_menu_event(app, id) {
+        return;
+    }
+
+    // 每次点击读取当前配置，热重载后新增的菜单项无需重启即可使用
+    let config = AppConfigData::load();
+    match config.tray.find(id) {
+        Some(TrayMenuEntry::Item(item)) => run_action(app, id, &item.action),
+        Some(TrayMenuEntry::Check(check)) => {
+            if let Err(e) = toggle_setting(app, &config, &check.setting) {
+                log::error!("Failed to toggle {}: {}", check.setting, e);
+                // 原生菜单已经切换了勾选状态，重建菜单以恢复为实际值
+                if let Err(e) = apply_config(app, &config) {
+                    log::error!("Failed to rebuild system tray: {:?}", e);
+                }
+            }
+        }
+        _ => log::warn!("No tray action bound to menu item {}", id),
+    }
+}
+
+/// 复选菜单项：把绑定的布尔配置项取反后写入用户配置，托盘随配置热重载一起刷新
+fn toggle_setting(app: &AppHandle, config: &AppConfigData, setting: &str) -> Result<(), String> {
+    let settings = serde_json::to_value(config).map_err(|e| e.to_string())?;
+    let current = settings
+        .pointer(&setting_pointer(setting))
+        .and_then(Value::as_bool)
+        .ok_or_else(|| format!("{} is not a boolean setting", setting))?;
+
+    let patch = setting.rsplit('.').fold(Value::Bool(!current), |value, key| {
+        let mut map = Map::new();
+        map.insert(key.to_string(), value);
+        Value::Object(map)
+    });
+    crate::config::patch_app_config(app.clone(), patch)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// 执行菜单项绑定的动作
+fn run_action(app: &AppHandle, id: &str, action: &TrayAction) {
+    let result = match action {
+        TrayAction::ShowWindow { label } => crate::window::show_window(app, label),
+        TrayAction::OpenRoute { route } => open_route(app, route),
+        TrayAction::Emit { event, payload } => {
+            let payload = payload.clone().unwrap_or_else(|| json!({ "id": id }));
+            app.emit(event, payload).map_err(|e| e.to_string())
+        }
+        TrayAction::ToggleFloatBall => {
+            crate::commands::is_float_ball_visible(app.clone())
+                .and_then(|visible| crate::commands::toggle_float_ball(app.clone(), !visible))
+        }
+        // 在后台上传，不需要打开主窗口
+        TrayAction::UploadClipboard => {
+            crate::upload::spawn_upload_clipboard(app);
+            Ok(())
+        }
+        TrayAction::OpenUrl { url } => open_url(url),
+        TrayAction::Quit => {
+            app.exit(0);
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        log::error!("Tray action for {} failed: {}", id, e);
+    }
+}
+
+/// 显示主窗口并通知前端跳转路由
+fn open_route(app: &AppHandle, route: &str) -> Result<(), String> {
+    crate::window::show_window(app, "main")?;
+    app.emit_to("main", "route:navigate", json!({ "route": route }))
+        .map_err(|e| e.to_string())
+}
+
+/// 用系统默认浏览器打开链接（URL 作为独立参数传入，不经过 shell）
+fn open_url(url: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = std::process::Command::new("xdg-open");
+
+    command
+        .arg(url)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open {}: {}", url, e))
+}
+
+fn handle_tray_icon_event(tray: &TrayIcon, event: TrayIconEvent) {
+    log::info!("Tray icon event: {:?}", event);
+
+    // 处理所有点击事件（Click, DoubleClick, Enter 等）：按登录状态激活主窗口或登录窗口
+    crate::window::focus_main_window(tray.app_handle());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{TrayCheckItem, TrayMenuItem, TrayRecentUploads, TraySubmenu};
+
+    fn ctx(settings: Value, locked: &[&str]) -> MenuContext {
+        MenuContext {
+            settings,
+            locked: locked.iter().map(|key| key.to_string()).collect(),
+        }
+    }
+
+    fn item(id: &str) -> TrayMenuItem {
+        TrayMenuItem {
+            id: id.to_string(),
+            label: id.to_uppercase(),
+            action: TrayAction::Quit,
+            icon: None,
+            accelerator: None,
+            disabled: false,
+        }
+    }
+
+    fn check(id: &str, setting: &str) -> TrayCheckItem {
+        TrayCheckItem {
+            id: id.to_string(),
+            label: id.to_uppercase(),
+            setting: setting.to_string(),
+            accelerator: None,
+            disabled: false,
+        }
+    }
+
+    fn submenu(label: &str, items: Vec<TrayMenuEntry>) -> TrayMenuEntry {
+        TrayMenuEntry::Submenu(TraySubmenu {
+            label: label.to_string(),
+            disabled: false,
+            items,
+        })
+    }
+
+    #[test]
+    fn items_keep_their_fields() {
+        let entries = vec![TrayMenuEntry::Item(TrayMenuItem {
+            icon: Some("icons/quit.png".to_string()),
+            accelerator: Some("CmdOrCtrl+Q".to_string()),
+            ..item("quit")
+        })];
+        assert_eq!(
+            menu_spec(&ctx(json!({}), &[]), &entries),
+            vec![MenuSpec::Item {
+                id: "quit",
+                label: "QUIT",
+                enabled: true,
+                icon: Some("icons/quit.png"),
+                accelerator: Some("CmdOrCtrl+Q"),
+            }]
+        );
+    }
+
+    #[test]
+    fn disabled_items_and_submenus_are_not_enabled() {
+        let entries = vec![
+            TrayMenuEntry::Item(TrayMenuItem {
+                disabled: true,
+                ..item("about")
+            }),
+            TrayMenuEntry::Submenu(TraySubmenu {
+                label: "more".to_string(),
+                disabled: true,
+                items: vec![],
+            }),
+        ];
+        assert_eq!(
+            menu_spec(&ctx(json!({}), &[]), &entries),
+            vec![
+                MenuSpec::Item {
+                    id: "about",
+                    label: "ABOUT",
+                    enabled: false,
+                    icon: None,
+                    accelerator: None,
+                },
+                MenuSpec::Submenu {
+                    label: "more",
+                    enabled: false,
+                    items: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn check_items_follow_their_setting() {
+        let settings = json!({ "app": { "quit_on_close": true, "single_instance": false } });
+        let entries = vec![
+            TrayMenuEntry::Check(check("quit-on-close", "app.quit_on_close")),
+            TrayMenuEntry::Check(check("single", "app.single_instance")),
+            TrayMenuEntry::Check(check("missing", "app.nothing")),
+            TrayMenuEntry::Check(TrayCheckItem {
+                disabled: true,
+                ..check("disabled", "app.quit_on_close")
+            }),
+        ];
+        let checks: Vec<(bool, bool)> = menu_spec(&ctx(settings, &[]), &entries)
+            .into_iter()
+            .map(|spec| match spec {
+                MenuSpec::Check { enabled, checked, .. } => (enabled, checked),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(checks, vec![(true, true), (true, false), (true, false), (false, true)]);
+    }
+
+    #[test]
+    fn check_items_bound_to_locked_settings_are_disabled() {
+        let settings = json!({ "app": { "quit_on_close": true } });
+        let entries = vec![TrayMenuEntry::Check(check("quit-on-close", "app.quit_on_close"))];
+        for locked in ["app.quit_on_close", "app"] {
+            assert_eq!(
+                menu_spec(&ctx(settings.clone(), &[locked]), &entries),
+                vec![MenuSpec::Check {
+                    id: "quit-on-close",
+                    label: "QUIT-ON-CLOSE",
+                    enabled: false,
+                    checked: true,
+                    accelerator: None,
+                }],
+                "{}",
+                locked
+            );
+        }
+        // 只是前缀相同的其他配置项不算锁定
+        assert!(matches!(
+            menu_spec(&ctx(settings, &["app.quit"]), &entries)[0],
+            MenuSpec::Check { enabled: true, .. }
+        ));
+    }
+
+    #[test]
+    fn submenus_nest_and_keep_separators() {
+        let entries = vec![
+            submenu(
+                "outer",
+                vec![
+                    TrayMenuEntry::Item(item("a")),
+                    TrayMenuEntry::Separator,
+                    submenu("inner", vec![TrayMenuEntry::Item(item("b"))]),
+                ],
+            ),
+            TrayMenuEntry::RecentUploads(TrayRecentUploads {
+                label: "recent".to_string(),
+            }),
+            TrayMenuEntry::Separator,
+        ];
+        let spec = menu_spec(&ctx(json!({}), &[]), &entries);
+        let MenuSpec::Submenu { label: "outer", enabled: true, items } = &spec[0] else {
+            panic!("unexpected {:?}", spec[0]);
+        };
+        assert!(matches!(items[0], MenuSpec::Item { id: "a", .. }));
+        assert_eq!(items[1], MenuSpec::Separator);
+        let MenuSpec::Submenu { label: "inner", items: inner, .. } = &items[2] else {
+            panic!("unexpected {:?}", items[2]);
+        };
+        assert!(matches!(inner[..], [MenuSpec::Item { id: "b", .. }]));
+        assert_eq!(spec[1], MenuSpec::RecentUploads { label: "recent" });
+        assert_eq!(spec[2], MenuSpec::Separator);
+        assert_eq!(spec.len(), 3);
+    }
+
+    #[test]
+    fn reserved_recent_upload_ids_are_skipped_at_any_depth() {
+        let entries = vec![
+            TrayMenuEntry::Item(item("recent-uploads:0")),
+            TrayMenuEntry::Check(check("recent-uploads:clear", "app.quit_on_close")),
+            submenu(
+                "outer",
+                vec![
+                    TrayMenuEntry::Item(item("recent-uploads:1")),
+                    TrayMenuEntry::Item(item("recent-uploadsx")),
+                ],
+            ),
+        ];
+        assert_eq!(
+            menu_spec(&ctx(json!({}), &[]), &entries),
+            vec![MenuSpec::Submenu {
+                label: "outer",
+                enabled: true,
+                items: vec![MenuSpec::Item {
+                    id: "recent-uploadsx",
+                    label: "RECENT-UPLOADSX",
+                    enabled: true,
+                    icon: None,
+                    accelerator: None,
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn default_menu_maps_every_entry() {
+        let config = AppConfigData::default();
+        let settings = serde_json::to_value(&config).unwrap();
+        let spec = menu_spec(&ctx(settings, &[]), &config.tray.menus);
+        assert_eq!(spec.len(), config.tray.menus.len());
+    }
+}
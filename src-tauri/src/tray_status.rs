@@ -0,0 +1,335 @@
+//! 托盘实时状态：汇总各任务来源（上传、AI 打标等）上报的进度，显示在托盘 tooltip/title 上，
+//! 有未读消息或失败任务时在图标右上角绘制角标
+
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::image::Image;
+use tauri::tray::TrayIcon;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::config::AppConfigData;
+
+/// 托盘基础图标
+const BASE_ICON: &[u8] = include_bytes!("../icons/32x32.png");
+
+const FAILED_BADGE: Rgba<u8> = Rgba([229, 57, 53, 255]);
+const UNREAD_BADGE: Rgba<u8> = Rgba([251, 140, 0, 255]);
+
+/// 单个任务来源的当前状态，由来源整体覆盖上报
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskStatus {
+    /// 显示在提示中的动词，如 `uploading`、`tagging`
+    #[serde(default)]
+    pub label: String,
+    /// 正在进行的任务数
+    #[serde(default)]
+    pub active: u32,
+    /// 排队中的任务数
+    #[serde(default)]
+    pub queued: u32,
+    /// 进行中任务的整体进度（0.0 ~ 1.0）
+    #[serde(default)]
+    pub progress: Option<f64>,
+    /// 失败且未处理的任务数
+    #[serde(default)]
+    pub failed: u32,
+    /// 未读消息数
+    #[serde(default)]
+    pub unread: u32,
+}
+
+impl TaskStatus {
+    fn is_idle(&self) -> bool {
+        self.active == 0 && self.queued == 0 && self.failed == 0 && self.unread == 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Badge {
+    Failed,
+    Unread,
+}
+
+/// 所有来源汇总后的状态
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TraySummary {
+    pub active: u32,
+    pub queued: u32,
+    /// 按进行中任务数加权的平均进度
+    pub progress: Option<f64>,
+    pub failed: u32,
+    pub unread: u32,
+    /// 状态文本，如 `3 uploading · 42%`；空闲时为空
+    pub text: String,
+}
+
+impl TraySummary {
+    fn badge(&self) -> Option<Badge> {
+        if self.failed > 0 {
+            Some(Badge::Failed)
+        } else if self.unread > 0 {
+            Some(Badge::Unread)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    sources: BTreeMap<String, TaskStatus>,
+    /// 当前托盘图标上的角标，未变化时不重绘图标
+    badge: Option<Badge>,
+    /// 空闲时的 tooltip 和 title，托盘创建或配置变化时更新，刷新进度时不再读取配置
+    tooltip: String,
+    title: String,
+}
+
+/// 托盘状态聚合器（Tauri managed state）
+#[derive(Default)]
+pub struct TrayStatus(Mutex<Inner>);
+
+impl TrayStatus {
+    /// 覆盖某个来源的状态，返回新的汇总；来源完全空闲时被移除
+    fn update(&self, source: &str, status: TaskStatus) -> TraySummary {
+        let mut inner = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if status.is_idle() {
+            inner.sources.remove(source);
+        } else {
+            inner.sources.insert(source.to_string(), status);
+        }
+        summarize(inner.sources.values())
+    }
+
+    fn summary(&self) -> TraySummary {
+        let inner = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        summarize(inner.sources.values())
+    }
+
+    /// 记录配置中的 tooltip（未设置时为应用名）和 title
+    fn set_labels(&self, config: &AppConfigData) {
+        let mut inner = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        inner.tooltip = config.tray.tooltip.clone().unwrap_or_else(|| config.name.clone());
+        inner.title = config.tray.title.clone().unwrap_or_default();
+    }
+
+    fn labels(&self) -> (String, String) {
+        let inner = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        (inner.tooltip.clone(), inner.title.clone())
+    }
+
+    /// 记录角标，返回是否与上次不同
+    fn swap_badge(&self, badge: Option<Badge>) -> bool {
+        let mut inner = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        std::mem::replace(&mut inner.badge, badge) != badge
+    }
+}
+
+fn summarize<'a>(sources: impl Iterator<Item = &'a TaskStatus>) -> TraySummary {
+    let mut summary = TraySummary::default();
+    let mut parts = Vec::new();
+    let (mut weighted, mut weight) = (0.0, 0.0);
+
+    for status in sources {
+        summary.active += status.active;
+        summary.queued += status.queued;
+        summary.failed += status.failed;
+        summary.unread += status.unread;
+        if status.active > 0 {
+            let label = if status.label.is_empty() { "running" } else { &status.label };
+            parts.push(format!("{} {}", status.active, label));
+            // 前端可能传来 NaN（如 0/0），这类进度不参与计算
+            if let Some(progress) = status.progress.filter(|p| p.is_finite()) {
+                weighted += progress.clamp(0.0, 1.0) * status.active as f64;
+                weight += status.active as f64;
+            }
+        }
+    }
+
+    if weight > 0.0 {
+        let progress = weighted / weight;
+        summary.progress = Some(progress);
+        parts.push(format!("{:.0}%", progress * 100.0));
+    }
+    if summary.queued > 0 {
+        parts.push(format!("{} queued", summary.queued));
+    }
+    if summary.failed > 0 {
+        parts.push(format!("{} failed", summary.failed));
+    }
+    if summary.unread > 0 {
+        parts.push(format!("{} unread", summary.unread));
+    }
+    summary.text = parts.join(" · ");
+    summary
+}
+
+/// 托盘图标；有角标时在右上角绘制带白边的圆点
+fn render_icon(badge: Option<Badge>) -> Image<'static> {
+    let mut img = image::load_from_memory(BASE_ICON)
+        .expect("Failed to load icon")
+        .to_rgba8();
+    if let Some(badge) = badge {
+        let color = match badge {
+            Badge::Failed => FAILED_BADGE,
+            Badge::Unread => UNREAD_BADGE,
+        };
+        draw_badge(&mut img, color);
+    }
+    let (width, height) = img.dimensions();
+    Image::new_owned(img.into_raw(), width, height)
+}
+
+fn draw_badge(img: &mut RgbaImage, color: Rgba<u8>) {
+    let (width, height) = img.dimensions();
+    let radius = width.min(height) as f32 * 0.22;
+    let border = (radius * 0.3).max(1.0);
+    let (cx, cy) = (width as f32 - radius - 1.0, radius + 1.0);
+
+    for (x, y, pixel) in img.enumerate_pixels_mut() {
+        let distance = ((x as f32 + 0.5 - cx).powi(2) + (y as f32 + 0.5 - cy).powi(2)).sqrt();
+        if distance <= radius - border {
+            *pixel = color;
+        } else if distance <= radius {
+            *pixel = Rgba([255, 255, 255, 255]);
+        }
+    }
+}
+
+/// 托盘的基础图标（无角标）
+pub fn base_icon() -> Image<'static> {
+    render_icon(None)
+}
+
+/// 按当前状态刷新托盘的 tooltip、title 和图标（托盘创建或配置变化后调用）
+pub fn apply(app: &AppHandle, tray: &TrayIcon, config: &AppConfigData) -> tauri::Result<()> {
+    let Some(state) = app.try_state::<TrayStatus>() else {
+        return Ok(());
+    };
+    state.set_labels(config);
+    let summary = state.summary();
+    render(tray, &state, &summary, true)
+}
+
+fn render<R: Runtime>(
+    tray: &TrayIcon<R>,
+    state: &TrayStatus,
+    summary: &TraySummary,
+    force_icon: bool,
+) -> tauri::Result<()> {
+    let (tooltip, title) = state.labels();
+    if summary.text.is_empty() {
+        tray.set_tooltip(Some(tooltip))?;
+        tray.set_title(Some(title))?;
+    } else {
+        tray.set_tooltip(Some(format!("{}\n{}", tooltip, summary.text)))?;
+        tray.set_title(Some(&summary.text))?;
+    }
+
+    let badge = summary.badge();
+    if state.swap_badge(badge) || force_icon {
+        tray.set_icon(Some(render_icon(badge)))?;
+        // macOS 模板图标会被渲染成单色，带角标时需要关闭模板模式
+        tray.set_icon_as_template(badge.is_none())?;
+    }
+    Ok(())
+}
+
+/// 上报某个来源的状态并刷新托盘（供 Rust 侧的上传引擎等调用）
+pub fn report<R: Runtime>(app: &AppHandle<R>, source: &str, status: TaskStatus) -> TraySummary {
+    let Some(state) = app.try_state::<TrayStatus>() else {
+        return TraySummary::default();
+    };
+    let summary = state.update(source, status);
+    if let Some(tray) = app.tray_by_id(crate::tray::TRAY_ID) {
+        if let Err(e) = render(&tray, &state, &summary, false) {
+            log::warn!("Failed to update tray status: {:?}", e);
+        }
+    }
+    summary
+}
+
+/// 前端上报任务状态（source 如 `upload`、`ai-tagging`），返回汇总后的状态
+#[tauri::command]
+pub fn update_tray_status(app: AppHandle, source: String, status: TaskStatus) -> TraySummary {
+    report(&app, &source, status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_are_taken_from_the_config_they_were_set_with() {
+        let state = TrayStatus::default();
+        let mut config = AppConfigData::default();
+        config.tray.tooltip = None;
+        config.tray.title = Some("PP".to_string());
+        state.set_labels(&config);
+        assert_eq!(state.labels(), (config.name.clone(), "PP".to_string()));
+
+        config.tray.tooltip = Some("PixelPunk".to_string());
+        config.tray.title = None;
+        state.set_labels(&config);
+        assert_eq!(state.labels(), ("PixelPunk".to_string(), String::new()));
+    }
+
+    #[test]
+    fn summary_text_combines_every_source() {
+        let state = TrayStatus::default();
+        state.update(
+            "upload",
+            TaskStatus {
+                label: "uploading".to_string(),
+                active: 3,
+                queued: 2,
+                progress: Some(0.5),
+                ..Default::default()
+            },
+        );
+        let summary = state.update(
+            "ai-tagging",
+            TaskStatus {
+                active: 1,
+                progress: Some(1.0),
+                failed: 1,
+                ..Default::default()
+            },
+        );
+        assert_eq!(summary.text, "1 running · 3 uploading · 62% · 2 queued · 1 failed");
+        assert_eq!(summary.badge(), Some(Badge::Failed));
+
+        let summary = state.update("ai-tagging", TaskStatus::default());
+        assert_eq!(summary.text, "3 uploading · 50% · 2 queued");
+        assert_eq!(summary.badge(), None);
+    }
+
+    #[test]
+    fn non_finite_progress_is_ignored() {
+        let state = TrayStatus::default();
+        state.update(
+            "upload",
+            TaskStatus {
+                active: 1,
+                progress: Some(f64::NAN),
+                ..Default::default()
+            },
+        );
+        let summary = state.update(
+            "ai-tagging",
+            TaskStatus {
+                active: 1,
+                progress: Some(0.25),
+                ..Default::default()
+            },
+        );
+        assert_eq!(summary.text, "1 running · 1 running · 25%");
+        assert_eq!(summary.progress, Some(0.25));
+
+        let summary = state.update("ai-tagging", TaskStatus::default());
+        assert_eq!(summary.text, "1 running");
+        assert_eq!(summary.progress, None);
+    }
+}
@@ -0,0 +1,421 @@
+//! 托盘直接上传：读取剪贴板中的图片或文件加入上传队列，不依赖主窗口；
+//! 全部结束后复制链接并发送系统通知。另有上传队列、分片上传和秒传共用的请求辅助函数
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_http::reqwest;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::clipboard::ClipboardContent;
+use crate::config::{AppConfigData, FloatBallUploadConfig, ServerProfile};
+use crate::recent_uploads::{self, RecentUpload};
+use crate::upload_queue::{self, JobStatus, UploadOptions};
+
+/// 剪贴板图片在缓存目录中的子目录，文件保留到对应的上传任务结束
+const CLIPBOARD_DIR: &str = "clipboard";
+/// 前端持久化存储文件，登录 token 保存在其中的 `token` 字段
+const STORAGE_FILE: &str = "app-data.json";
+const TOKEN_KEY: &str = "token";
+
+#[derive(Debug, Deserialize)]
+struct ApiResponse {
+    code: i64,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    data: Option<Value>,
+}
+
+/// 按扩展名推断 MIME 类型，未知类型使用 application/octet-stream
+pub fn mime_type(name: &str) -> &'static str {
+    let ext = name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "tif" | "tiff" => "image/tiff",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "txt" => "text/plain",
+        "json" => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+/// allowed_types 为空表示不限制；条目可以是 MIME 类型、`image/*` 通配或扩展名（`.png` / `png`）
+pub fn is_allowed(config: &FloatBallUploadConfig, name: &str) -> bool {
+    if config.allowed_types.is_empty() {
+        return true;
+    }
+    let mime = mime_type(name);
+    let ext = name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    config.allowed_types.iter().any(|allowed| {
+        let allowed = allowed.trim().to_ascii_lowercase();
+        if let Some(prefix) = allowed.strip_suffix("/*") {
+            mime.split('/').next() == Some(prefix)
+        } else if allowed.contains('/') {
+            allowed == mime
+        } else {
+            allowed.trim_start_matches('.') == ext
+        }
+    })
+}
+
+pub fn check_size(config: &FloatBallUploadConfig, name: &str, size: u64) -> Result<(), String> {
+    if size > config.max_file_size {
+        return Err(format!(
+            "{} 超过大小限制（{:.1} MB）",
+            name,
+            config.max_file_size as f64 / 1024.0 / 1024.0
+        ));
+    }
+    Ok(())
+}
+
+/// api_url 为完整地址时直接使用，否则拼接到当前服务端地址后
+pub fn upload_url(server: &ServerProfile, api_url: &str) -> String {
+    if api_url.starts_with("http://") || api_url.starts_with("https://") {
+        api_url.to_string()
+    } else {
+        format!(
+            "{}/{}",
+            server.base_url.trim_end_matches('/'),
+            api_url.trim_start_matches('/')
+        )
+    }
+}
+
+/// 文件的修改时间（Unix 毫秒），读取失败时为 0
+pub fn modified_ms(path: &Path) -> i64 {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_millis() as i64)
+}
+
+/// 读取前端保存的登录 token（与前端存储一样按 profile 隔离）
+pub fn read_token<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    let dir = app.path().app_data_dir().ok()?;
+    let text = std::fs::read_to_string(crate::profile::scope(&dir).join(STORAGE_FILE)).ok()?;
+    let data: Value = serde_json::from_str(&text).ok()?;
+    data.get(TOKEN_KEY)
+        .and_then(Value::as_str)
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+}
+
+/// 每个请求使用不同的 multipart 分隔符
+pub fn multipart_boundary() -> String {
+    format!(
+        "----PixelPunkBoundary{:x}",
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0)
+    )
+}
+
+/// multipart/form-data 请求体中文件内容之前的部分：普通字段在前，文件字段名为 `file`（与前端上传一致）
+pub fn multipart_head(boundary: &str, file_name: &str, fields: &[(&str, String)]) -> Vec<u8> {
+    let mut head = String::new();
+    for (key, value) in fields {
+        head.push_str(&format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+            boundary, key, value
+        ));
+    }
+    head.push_str(&format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+        boundary,
+        file_name.replace(['"', '\r', '\n'], "_"),
+        mime_type(file_name)
+    ));
+    head.into_bytes()
+}
+
+/// multipart/form-data 请求体中文件内容之后的部分
+pub fn multipart_tail(boundary: &str) -> Vec<u8> {
+    format!("\r\n--{}--\r\n", boundary).into_bytes()
+}
+
+/// 上传请求失败的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadError {
+    /// 网络中断或服务端暂时不可用（5xx、408、429），之后可以从中断处继续
+    Interrupted(String),
+    /// 服务端拒绝了请求或本地文件不可用，继续也不会成功
+    Rejected(String),
+}
+
+impl UploadError {
+    /// 请求没有得到响应（连接失败、超时或连接中断）
+    pub fn network(name: &str, e: reqwest::Error) -> Self {
+        Self::Interrupted(format!("{}: {}", name, e))
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Interrupted(message) | Self::Rejected(message) => message,
+        }
+    }
+
+    pub fn into_message(self) -> String {
+        match self {
+            Self::Interrupted(message) | Self::Rejected(message) => message,
+        }
+    }
+}
+
+/// 本地检查（文件读取、大小限制等）失败，视为不可继续
+impl From<String> for UploadError {
+    fn from(message: String) -> Self {
+        Self::Rejected(message)
+    }
+}
+
+/// 服务端暂时不可用的状态码，稍后重试可能成功
+fn is_transient(status: reqwest::StatusCode) -> bool {
+    status.is_server_error()
+        || status == reqwest::StatusCode::REQUEST_TIMEOUT
+        || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// 解析接口响应（`{ code, message, data }`），code 不为 200 时返回 message
+pub async fn parse_data(response: reqwest::Response, name: &str) -> Result<Value, String> {
+    read_data(response, name).await.map_err(UploadError::into_message)
+}
+
+/// 解析上传接口的响应，返回文件地址（优先 full_url）
+pub async fn parse_response(response: reqwest::Response, name: &str) -> Result<String, String> {
+    read_url(response, name).await.map_err(UploadError::into_message)
+}
+
+/// 同 parse_data，失败时区分网络中断和服务端拒绝
+pub async fn read_data(response: reqwest::Response, name: &str) -> Result<Value, UploadError> {
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| UploadError::network(name, e))?;
+    let failed = |message: String| {
+        if is_transient(status) {
+            UploadError::Interrupted(message)
+        } else {
+            UploadError::Rejected(message)
+        }
+    };
+    let body: ApiResponse =
+        serde_json::from_str(&text).map_err(|_| failed(format!("{}: HTTP {}", name, status)))?;
+    if !status.is_success() || body.code != 200 {
+        let message = if body.message.is_empty() { status.to_string() } else { body.message };
+        return Err(failed(format!("{}: {}", name, message)));
+    }
+    Ok(body.data.unwrap_or_default())
+}
+
+/// 同 parse_response，失败时区分网络中断和服务端拒绝
+pub async fn read_url(response: reqwest::Response, name: &str) -> Result<String, UploadError> {
+    let data = read_data(response, name).await?;
+    ["full_url", "url"]
+        .iter()
+        .find_map(|key| data.get(*key).and_then(Value::as_str).filter(|url| !url.is_empty()))
+        .map(str::to_string)
+        .ok_or_else(|| UploadError::Rejected(format!("{}: 响应中没有文件地址", name)))
+}
+
+fn notify(app: &AppHandle, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::warn!("Failed to show notification: {}", e);
+    }
+}
+
+/// 把剪贴板图片写入缓存目录，返回文件路径；先清理已不被任何任务引用的旧图片
+fn save_clipboard_image(app: &AppHandle, bytes: &[u8]) -> Result<PathBuf, String> {
+    let dir = crate::profile::scope(&app.path().app_cache_dir().map_err(|e| e.to_string())?)
+        .join(CLIPBOARD_DIR);
+    if let Ok(entries) = std::fs::read_dir(&dir) {
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if !upload_queue::in_use(app, &path) {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let stem = format!("clipboard-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let mut path = dir.join(format!("{}.png", stem));
+    let mut index = 1;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.png", stem, index));
+        index += 1;
+    }
+    std::fs::write(&path, bytes).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// 上传剪贴板中的图片或文件，全部结束后复制链接并通知；返回上传成功的记录
+pub async fn upload_from_clipboard(app: &AppHandle) -> Result<Vec<RecentUpload>, String> {
+    // 剪贴板工具是外部进程，放到阻塞线程池中执行
+    let content = tauri::async_runtime::spawn_blocking(crate::clipboard::read)
+        .await
+        .map_err(|e| e.to_string())??;
+    let paths = match content {
+        ClipboardContent::Empty => Vec::new(),
+        ClipboardContent::Files(paths) => paths,
+        ClipboardContent::Image(bytes) => vec![save_clipboard_image(app, &bytes)?],
+    };
+    if paths.is_empty() {
+        let message = "剪贴板中没有图片或文件".to_string();
+        notify(app, "上传失败", &message);
+        return Err(message);
+    }
+
+    // 类型、大小检查、并发、分片和秒传都由上传队列处理
+    let ids: Vec<String> = upload_queue::enqueue(app, paths, &UploadOptions::default())?
+        .into_iter()
+        .map(|job| job.id)
+        .collect();
+    let jobs = upload_queue::wait(app, &ids).await;
+
+    let mut uploaded = Vec::new();
+    let mut errors = Vec::new();
+    for job in jobs {
+        match (job.status, job.url) {
+            (JobStatus::Done, Some(url)) => uploaded.push(RecentUpload {
+                name: job.name,
+                url,
+                uploaded_at: job
+                    .finished_at
+                    .unwrap_or_else(|| chrono::Utc::now().timestamp()),
+            }),
+            (JobStatus::Paused, _) => errors.push(match job.error {
+                Some(e) => format!("{} 已暂停（{}）", job.name, e),
+                None => format!("{} 已暂停", job.name),
+            }),
+            (JobStatus::Cancelled, _) => errors.push(format!("{} 已取消", job.name)),
+            _ => errors.push(job.error.unwrap_or(job.name)),
+        }
+    }
+
+    for e in &errors {
+        log::warn!("Clipboard upload incomplete: {}", e);
+    }
+    if uploaded.is_empty() {
+        let message = errors
+            .first()
+            .cloned()
+            .unwrap_or_else(|| "上传任务已被移除".to_string());
+        notify(app, "上传失败", &message);
+        return Err(message);
+    }
+
+    let format = AppConfigData::load().tray.recent_uploads.copy_format;
+    let links: Vec<String> = uploaded
+        .iter()
+        .map(|upload| recent_uploads::format_link(upload, format))
+        .collect();
+    let copied = crate::clipboard::write_text(&links.join("\n"));
+    if let Err(e) = &copied {
+        log::error!("Failed to copy uploaded links: {}", e);
+    }
+
+    let mut body = if uploaded.len() == 1 {
+        uploaded[0].url.clone()
+    } else {
+        format!("已上传 {} 个文件", uploaded.len())
+    };
+    if !errors.is_empty() {
+        body.push_str(&format!("，{} 个失败", errors.len()));
+    }
+    let title = if copied.is_ok() { "上传成功，链接已复制" } else { "上传成功" };
+    notify(app, title, &body);
+    Ok(uploaded)
+}
+
+/// 托盘菜单动作：在后台上传剪贴板内容
+pub fn spawn_upload_clipboard(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = upload_from_clipboard(&app).await {
+            log::error!("Clipboard upload failed: {}", e);
+        }
+    });
+}
+
+/// 上传剪贴板内容（前端或全局快捷键调用）
+#[tauri::command]
+pub async fn upload_clipboard(app: AppHandle) -> Result<Vec<RecentUpload>, String> {
+    upload_from_clipboard(&app).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfigData;
+
+    fn allowing(types: &[&str]) -> FloatBallUploadConfig {
+        let mut config = AppConfigData::default().float_ball.upload;
+        config.allowed_types = types.iter().map(|t| t.to_string()).collect();
+        config
+    }
+
+    #[test]
+    fn allowed_types_match_mime_wildcards_and_extensions() {
+        assert!(is_allowed(&allowing(&[]), "anything.bin"));
+
+        let images = allowing(&["image/*"]);
+        assert!(is_allowed(&images, "a.PNG"));
+        assert!(is_allowed(&images, "b.webp"));
+        assert!(!is_allowed(&images, "c.mp4"));
+        assert!(!is_allowed(&images, "no-extension"));
+
+        let listed = allowing(&[".png", " JPG ", "video/mp4"]);
+        assert!(is_allowed(&listed, "a.png"));
+        assert!(is_allowed(&listed, "b.jpg"));
+        assert!(is_allowed(&listed, "c.mp4"));
+        assert!(!is_allowed(&listed, "d.gif"));
+    }
+
+    #[test]
+    fn relative_api_urls_are_joined_to_the_server() {
+        let mut server = AppConfigData::default().servers.profiles[0].clone();
+        server.base_url = "https://img.example.com/".to_string();
+        assert_eq!(
+            upload_url(&server, "/api/v1/files/upload"),
+            "https://img.example.com/api/v1/files/upload"
+        );
+        assert_eq!(upload_url(&server, "api/upload"), "https://img.example.com/api/upload");
+        assert_eq!(
+            upload_url(&server, "https://cdn.example.com/upload"),
+            "https://cdn.example.com/upload"
+        );
+    }
+
+    #[test]
+    fn file_names_cannot_break_the_multipart_head() {
+        let head = multipart_head("xyz", "a\"b\r\nc.png", &[("folder_id", "7".to_string())]);
+        let head = String::from_utf8(head).unwrap();
+        assert_eq!(
+            head,
+            "--xyz\r\nContent-Disposition: form-data; name=\"folder_id\"\r\n\r\n7\r\n\
+             --xyz\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a_b__c.png\"\r\n\
+             Content-Type: image/png\r\n\r\n"
+        );
+    }
+}
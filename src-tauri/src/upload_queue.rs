@@ -0,0 +1,1189 @@
+//! 上传队列：在 Rust 侧按 float_ball.upload 的限制上传本地文件，队列保存在应用数据目录（按 profile 隔离），
+//! 关闭窗口不影响进行中的上传，重启后继续未完成的任务（大文件分片上传，从已确认的分片之后继续）。
+//! 开启秒传时，新加入的一批文件先计算 MD5 并整批询问服务端，已存在的文件不再上传内容。
+//! 同时上传的文件数不超过 concurrent，进度通过 upload:progress 广播，任务结束（成功、失败或取消）时广播 upload:done
+
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_http::reqwest;
+
+use crate::chunk_upload::{self, ChunkSession, ChunkUpload};
+use crate::config::AppConfigData;
+use crate::instant_upload::{self, Candidate, InstantUpload};
+use crate::tray_status::{self, TaskStatus};
+use crate::upload::{self, UploadError};
+
+const FILE_NAME: &str = "upload-queue.json";
+/// 托盘状态中的来源名
+const STATUS_SOURCE: &str = "upload-queue";
+/// 已结束的任务最多保留这么多条
+const MAX_FINISHED: usize = 200;
+/// 每次从文件读取的字节数
+const READ_CHUNK: usize = 256 * 1024;
+/// upload:progress 的最短间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// 正在计算 MD5 并询问服务端是否已存在（秒传）
+    Checking,
+    Queued,
+    Uploading,
+    /// 用户暂停，或网络中断（error 中有原因），继续时接着上传
+    Paused,
+    Done,
+    /// 服务端拒绝或文件不可用
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    fn is_finished(self) -> bool {
+        matches!(self, Self::Done | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// 随文件提交的上传参数（与前端 UploadOptions 一致）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UploadOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_level: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimize: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_duration: Option<String>,
+}
+
+impl UploadOptions {
+    /// 转为 multipart 普通字段
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = Vec::new();
+        if let Some(folder_id) = &self.folder_id {
+            fields.push(("folder_id", folder_id.clone()));
+        }
+        if let Some(access_level) = &self.access_level {
+            fields.push(("access_level", access_level.clone()));
+        }
+        if let Some(optimize) = self.optimize {
+            fields.push(("optimize", optimize.to_string()));
+        }
+        if let Some(storage_duration) = &self.storage_duration {
+            fields.push(("storage_duration", storage_duration.clone()));
+        }
+        fields
+    }
+}
+
+/// 一个上传任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadJob {
+    pub id: String,
+    pub path: PathBuf,
+    pub name: String,
+    pub size: u64,
+    #[serde(default)]
+    pub options: UploadOptions,
+    pub status: JobStatus,
+    /// 已发送的字节数
+    #[serde(default)]
+    pub sent: u64,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// 加入队列时间（Unix 秒）
+    pub created_at: i64,
+    #[serde(default)]
+    pub finished_at: Option<i64>,
+    /// 分片上传的会话，保存已确认的分片
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunked: Option<ChunkSession>,
+    /// 服务端已存在该文件，通过秒传完成
+    #[serde(default)]
+    pub instant: bool,
+}
+
+impl UploadJob {
+    /// 重新开始上传时已确认的字节数（分片上传保留已确认的分片）
+    fn confirmed(&self) -> u64 {
+        self.chunked.as_ref().map_or(0, |session| session.confirmed(self.size))
+    }
+}
+
+/// upload:progress 事件载荷
+#[derive(Debug, Clone, Serialize)]
+struct UploadProgress<'a> {
+    id: &'a str,
+    name: &'a str,
+    status: JobStatus,
+    sent: u64,
+    total: u64,
+}
+
+/// upload:instant 事件载荷：一批文件中通过秒传跳过的文件
+#[derive(Debug, Clone, Serialize)]
+struct InstantSummary {
+    ids: Vec<String>,
+    files: usize,
+    bytes: u64,
+}
+
+/// 进行中的任务
+struct RunningTask {
+    handle: JoinHandle<()>,
+    /// 暂停或取消时置为 false：请求体可能仍由连接任务读取，之后读到的内容不再计入进度
+    active: Arc<AtomicBool>,
+}
+
+/// 上传队列（Tauri managed state）
+pub struct UploadQueue {
+    path: PathBuf,
+    jobs: Mutex<Vec<UploadJob>>,
+    /// 进行中的任务，暂停或取消时中止
+    running: Mutex<HashMap<String, RunningTask>>,
+}
+
+impl UploadQueue {
+    /// 从数据目录读取队列；上次退出时正在检测或上传的任务重新排队（分片上传从已确认的分片之后继续）
+    pub fn load(data_dir: PathBuf) -> Self {
+        let path = data_dir.join(FILE_NAME);
+        let mut jobs: Vec<UploadJob> = match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                log::warn!("Ignoring corrupted {}: {}", path.display(), e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        for job in jobs
+            .iter_mut()
+            .filter(|job| matches!(job.status, JobStatus::Checking | JobStatus::Uploading))
+        {
+            job.status = JobStatus::Queued;
+            job.sent = job.confirmed();
+        }
+        Self {
+            path,
+            jobs: Mutex::new(jobs),
+            running: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn jobs(&self) -> std::sync::MutexGuard<'_, Vec<UploadJob>> {
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn running(&self) -> std::sync::MutexGuard<'_, HashMap<String, RunningTask>> {
+        self.running.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn get(&self, id: &str) -> Option<UploadJob> {
+        self.jobs().iter().find(|job| job.id == id).cloned()
+    }
+
+    /// 修改任务并返回修改后的副本，任务不存在时返回 None
+    fn update(&self, id: &str, f: impl FnOnce(&mut UploadJob)) -> Option<UploadJob> {
+        let mut jobs = self.jobs();
+        let job = jobs.iter_mut().find(|job| job.id == id)?;
+        f(job);
+        Some(job.clone())
+    }
+
+    /// 只保留最近 MAX_FINISHED 条已结束的任务，先写临时文件再重命名
+    fn save(&self) -> Result<(), String> {
+        let text = {
+            let mut jobs = self.jobs();
+            let finished = jobs.iter().filter(|job| job.status.is_finished()).count();
+            if finished > MAX_FINISHED {
+                let mut excess = finished - MAX_FINISHED;
+                jobs.retain(|job| {
+                    let drop = excess > 0 && job.status.is_finished();
+                    if drop {
+                        excess -= 1;
+                    }
+                    !drop
+                });
+            }
+            serde_json::to_string_pretty(&*jobs).map_err(|e| e.to_string())?
+        };
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, text).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, &self.path).map_err(|e| e.to_string())
+    }
+}
+
+fn save(queue: &UploadQueue) {
+    if let Err(e) = queue.save() {
+        log::warn!("Failed to save upload queue: {}", e);
+    }
+}
+
+fn emit_progress<R: Runtime>(app: &AppHandle<R>, job: &UploadJob) {
+    let payload = UploadProgress {
+        id: &job.id,
+        name: &job.name,
+        status: job.status,
+        sent: job.sent,
+        total: job.size,
+    };
+    if let Err(e) = app.emit("upload:progress", payload) {
+        log::warn!("Failed to emit upload:progress: {}", e);
+    }
+}
+
+fn emit_done<R: Runtime>(app: &AppHandle<R>, job: &UploadJob) {
+    if let Err(e) = app.emit("upload:done", job) {
+        log::warn!("Failed to emit upload:done: {}", e);
+    }
+}
+
+/// 把队列的整体情况同步到托盘
+fn report_status<R: Runtime>(app: &AppHandle<R>, queue: &UploadQueue) {
+    let jobs = queue.jobs();
+    let uploading: Vec<&UploadJob> = jobs
+        .iter()
+        .filter(|job| job.status == JobStatus::Uploading)
+        .collect();
+    let (sent, total) = uploading
+        .iter()
+        .fold((0, 0), |(sent, total), job| (sent + job.sent, total + job.size));
+    let status = TaskStatus {
+        label: "uploading".to_string(),
+        active: uploading.len() as u32,
+        queued: jobs
+            .iter()
+            .filter(|job| matches!(job.status, JobStatus::Checking | JobStatus::Queued))
+            .count() as u32,
+        progress: (total > 0).then(|| sent as f64 / total as f64),
+        // 队列空闲后不再提示历史失败任务
+        failed: if uploading.is_empty()
+            && !jobs
+                .iter()
+                .any(|job| matches!(job.status, JobStatus::Checking | JobStatus::Queued))
+        {
+            0
+        } else {
+            jobs.iter().filter(|job| job.status == JobStatus::Failed).count() as u32
+        },
+        unread: 0,
+    };
+    drop(jobs);
+    tray_status::report(app, STATUS_SOURCE, status);
+}
+
+/// 检查文件并生成任务；不满足限制的文件生成已失败的任务
+fn new_job(config: &AppConfigData, path: PathBuf, options: &UploadOptions, id: String) -> UploadJob {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    let metadata = std::fs::metadata(&path);
+    let size = metadata.as_ref().map_or(0, |m| m.len());
+    let checked = match &metadata {
+        Err(e) => Err(format!("{}: {}", name, e)),
+        Ok(m) if !m.is_file() => Err(format!("{} 不是文件", name)),
+        Ok(_) if !upload::is_allowed(&config.float_ball.upload, &name) => {
+            Err(format!("{} 的文件类型不允许上传", name))
+        }
+        Ok(_) => chunk_upload::check_size(&config.float_ball.upload, &name, size),
+    };
+    let now = chrono::Utc::now().timestamp();
+    UploadJob {
+        id,
+        path,
+        name,
+        size,
+        options: options.clone(),
+        status: if checked.is_ok() { JobStatus::Queued } else { JobStatus::Failed },
+        sent: 0,
+        url: None,
+        finished_at: checked.is_err().then_some(now),
+        error: checked.err(),
+        created_at: now,
+        chunked: None,
+        instant: false,
+    }
+}
+
+/// 按 concurrent 启动排队中的任务（每次读取当前配置，热重载后立即生效）
+pub fn pump<R: Runtime>(app: &AppHandle<R>) {
+    let Some(queue) = app.try_state::<UploadQueue>() else {
+        return;
+    };
+    let limit = AppConfigData::load().float_ball.upload.concurrent.max(1) as usize;
+    {
+        let mut running = queue.running();
+        let started: Vec<UploadJob> = {
+            let free = limit.saturating_sub(running.len());
+            queue
+                .jobs()
+                .iter_mut()
+                .filter(|job| job.status == JobStatus::Queued)
+                .take(free)
+                .map(|job| {
+                    job.status = JobStatus::Uploading;
+                    job.sent = job.confirmed();
+                    job.error = None;
+                    job.clone()
+                })
+                .collect()
+        };
+        for job in started {
+            log::info!("Uploading {} ({} bytes)", job.name, job.size);
+            emit_progress(app, &job);
+            let app = app.clone();
+            let id = job.id.clone();
+            let active = Arc::new(AtomicBool::new(true));
+            let reporter = ProgressReporter {
+                app: app.clone(),
+                id: job.id.clone(),
+                active: active.clone(),
+                last: Instant::now(),
+            };
+            let handle = tauri::async_runtime::spawn(async move {
+                let result = send(&app, &job, reporter).await;
+                finish(&app, &job.id, result);
+            });
+            running.insert(id, RunningTask { handle, active });
+        }
+    }
+    save(&queue);
+    report_status(app, &queue);
+}
+
+/// 任务结束：记录结果、广播 upload:done，并启动下一个任务。
+/// 网络中断的任务转为暂停（保留已确认的分片），继续时接着上传，不算失败
+fn finish<R: Runtime>(app: &AppHandle<R>, id: &str, result: Result<String, UploadError>) {
+    let Some(queue) = app.try_state::<UploadQueue>() else {
+        return;
+    };
+    queue.running().remove(id);
+    // 结束前已被暂停或取消的任务保持原状态
+    if queue.get(id).map_or(true, |job| job.status != JobStatus::Uploading) {
+        pump(app);
+        return;
+    }
+    let job = queue.update(id, |job| match result {
+        Ok(url) => {
+            job.status = JobStatus::Done;
+            job.sent = job.size;
+            job.url = Some(url);
+            job.chunked = None;
+            job.finished_at = Some(chrono::Utc::now().timestamp());
+        }
+        Err(UploadError::Interrupted(e)) => {
+            job.status = JobStatus::Paused;
+            job.sent = job.confirmed();
+            job.error = Some(e);
+        }
+        Err(UploadError::Rejected(e)) => {
+            job.status = JobStatus::Failed;
+            job.error = Some(e);
+            job.finished_at = Some(chrono::Utc::now().timestamp());
+        }
+    });
+    if let Some(job) = job {
+        match (job.status, &job.url, &job.error) {
+            (_, Some(url), _) => {
+                log::info!("Uploaded {}", job.name);
+                crate::recent_uploads::record(app, &job.name, url);
+            }
+            (JobStatus::Paused, _, Some(e)) => log::warn!("Upload interrupted: {}", e),
+            (_, None, Some(e)) => log::warn!("Upload failed: {}", e),
+            _ => {}
+        }
+        if job.status == JobStatus::Paused {
+            emit_progress(app, &job);
+        } else {
+            emit_done(app, &job);
+        }
+    }
+    pump(app);
+}
+
+/// 记录已发送的字节数，按间隔广播 upload:progress
+struct ProgressReporter<R: Runtime> {
+    app: AppHandle<R>,
+    id: String,
+    active: Arc<AtomicBool>,
+    last: Instant,
+}
+
+impl<R: Runtime> ProgressReporter<R> {
+    fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    fn add(&mut self, bytes: u64, size: u64) {
+        if !self.is_active() {
+            return;
+        }
+        let Some(queue) = self.app.try_state::<UploadQueue>() else {
+            return;
+        };
+        let Some(job) = queue.update(&self.id, |job| job.sent = (job.sent + bytes).min(job.size)) else {
+            return;
+        };
+        if self.last.elapsed() >= PROGRESS_INTERVAL || job.sent >= size {
+            self.last = Instant::now();
+            emit_progress(&self.app, &job);
+            report_status(&self.app, &queue);
+        }
+    }
+}
+
+/// 记录分片上传的会话并写入磁盘，保证中断后能从已确认的分片继续
+fn save_chunk<R: Runtime>(app: &AppHandle<R>, id: &str, session: Option<&ChunkSession>) {
+    let Some(queue) = app.try_state::<UploadQueue>() else {
+        return;
+    };
+    let job = queue.update(id, |job| {
+        job.chunked = session.cloned();
+        job.sent = job.confirmed();
+    });
+    save(&queue);
+    if let Some(job) = job {
+        emit_progress(app, &job);
+    }
+    report_status(app, &queue);
+}
+
+/// 发送文件：超过分片阈值时分片上传，否则以流的方式边读边上传
+async fn send<R: Runtime>(
+    app: &AppHandle<R>,
+    job: &UploadJob,
+    reporter: ProgressReporter<R>,
+) -> Result<String, UploadError> {
+    let config = AppConfigData::load();
+    let server = config
+        .servers
+        .active_profile()
+        .cloned()
+        .ok_or_else(|| "No active server".to_string())?;
+    let client = crate::config::servers::client_for(&server)?;
+    let url = upload::upload_url(&server, &config.float_ball.upload.api_url);
+
+    // 加入队列后文件可能被修改，重新检查大小
+    let size = std::fs::metadata(&job.path)
+        .map_err(|e| format!("{}: {}", job.name, e))?
+        .len();
+    chunk_upload::check_size(&config.float_ball.upload, &job.name, size)?;
+    if let Some(queue) = app.try_state::<UploadQueue>() {
+        queue.update(&job.id, |job| job.size = size);
+    }
+    let token = upload::read_token(app);
+    if chunk_upload::is_chunked(&config.float_ball.upload, size) {
+        let chunk = ChunkUpload {
+            client: &client,
+            server: &server,
+            config: &config.float_ball.upload,
+            token: token.as_deref(),
+            path: &job.path,
+            name: &job.name,
+            size,
+            fields: job.options.fields(),
+        };
+        return chunk.run(job.chunked.clone(), |session| save_chunk(app, &job.id, session)).await;
+    }
+    let file = std::fs::File::open(&job.path).map_err(|e| format!("{}: {}", job.name, e))?;
+
+    let boundary = upload::multipart_boundary();
+    let head = upload::multipart_head(&boundary, &job.name, &job.options.fields());
+    let tail = upload::multipart_tail(&boundary);
+    let length = head.len() as u64 + size + tail.len() as u64;
+    let content = futures_util::stream::try_unfold((file, reporter), move |(file, mut reporter)| async move {
+        if !reporter.is_active() {
+            return Err(std::io::Error::other("upload paused or cancelled"));
+        }
+        // 文件读取放到阻塞线程池中执行，不占用异步运行时的线程
+        let (file, mut buf, read) = tauri::async_runtime::spawn_blocking(move || {
+            let mut file = file;
+            let mut buf = vec![0; READ_CHUNK];
+            let read = file.read(&mut buf);
+            (file, buf, read)
+        })
+        .await
+        .map_err(std::io::Error::other)?;
+        let read = read?;
+        if read == 0 {
+            return Ok(None);
+        }
+        buf.truncate(read);
+        reporter.add(read as u64, size);
+        Ok(Some((buf, (file, reporter))))
+    });
+    let body = futures_util::stream::once(async move { Ok::<_, std::io::Error>(head) })
+        .chain(content)
+        .chain(futures_util::stream::once(async move { Ok(tail) }));
+
+    let mut request = client
+        .post(&url)
+        .header(
+            "Content-Type",
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .header("Content-Length", length)
+        .body(reqwest::Body::wrap_stream(body));
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| UploadError::network(&job.name, e))?;
+    upload::read_url(response, &job.name).await
+}
+
+/// 秒传检测：计算一批任务的 MD5，一次请求询问服务端哪些文件已存在，
+/// 已存在的文件直接创建记录并结束，其余任务（包括检测失败的）转为排队上传
+async fn check_instant<R: Runtime>(app: AppHandle<R>, ids: Vec<String>) {
+    let Some(queue) = app.try_state::<UploadQueue>() else {
+        return;
+    };
+    let jobs: Vec<UploadJob> = ids.iter().filter_map(|id| queue.get(id)).collect();
+    let paths: Vec<PathBuf> = jobs.iter().map(|job| job.path.clone()).collect();
+    let hashes = {
+        let app = app.clone();
+        tauri::async_runtime::spawn_blocking(move || instant_upload::hash_files(&app, &paths))
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("Hashing failed: {}", e);
+                Vec::new()
+            })
+    };
+    let hashed: Vec<(&UploadJob, &String)> = jobs
+        .iter()
+        .zip(&hashes)
+        .filter_map(|(job, md5)| Some((job, md5.as_ref()?)))
+        .collect();
+
+    let mut skipped = InstantSummary {
+        ids: Vec::new(),
+        files: 0,
+        bytes: 0,
+    };
+    if let Err(e) = upload_instant(&app, &queue, &hashed, &mut skipped).await {
+        log::warn!("Instant upload check failed: {}", e);
+    }
+    log::info!(
+        "Instant upload skipped {} of {} file(s), {} bytes",
+        skipped.files,
+        jobs.len(),
+        skipped.bytes
+    );
+    if let Err(e) = app.emit("upload:instant", &skipped) {
+        log::warn!("Failed to emit upload:instant: {}", e);
+    }
+
+    // 检测期间被暂停或取消的任务保持原状态
+    for id in &ids {
+        queue.update(id, |job| {
+            if job.status == JobStatus::Checking {
+                job.status = JobStatus::Queued;
+            }
+        });
+    }
+    pump(&app);
+}
+
+/// 询问服务端并秒传已存在的文件，结果记入 skipped
+async fn upload_instant<R: Runtime>(
+    app: &AppHandle<R>,
+    queue: &UploadQueue,
+    hashed: &[(&UploadJob, &String)],
+    skipped: &mut InstantSummary,
+) -> Result<(), String> {
+    if hashed.is_empty() {
+        return Ok(());
+    }
+    let config = AppConfigData::load();
+    let server = config
+        .servers
+        .active_profile()
+        .cloned()
+        .ok_or("No active server")?;
+    let client = crate::config::servers::client_for(&server)?;
+    let token = upload::read_token(app);
+    let instant = InstantUpload {
+        client: &client,
+        server: &server,
+        config: &config.float_ball.upload,
+        token: token.as_deref(),
+    };
+    let candidates: Vec<Candidate> = hashed
+        .iter()
+        .map(|(job, md5)| Candidate {
+            name: &job.name,
+            size: job.size,
+            md5,
+        })
+        .collect();
+    let duplicates = instant.check(&candidates).await?;
+
+    for ((job, _), file) in hashed.iter().zip(&candidates) {
+        if !duplicates.contains(file.md5) {
+            continue;
+        }
+        // 检测期间可能已被暂停或取消，这类任务不再秒传
+        if !queue
+            .get(&job.id)
+            .is_some_and(|job| job.status == JobStatus::Checking)
+        {
+            continue;
+        }
+        let url = match instant.upload(file, &job.options.fields()).await {
+            Ok(url) => url,
+            Err(e) => {
+                log::warn!("Instant upload failed, uploading normally: {}", e);
+                continue;
+            }
+        };
+        let done = queue.update(&job.id, |job| {
+            if job.status != JobStatus::Checking {
+                return;
+            }
+            job.status = JobStatus::Done;
+            job.sent = job.size;
+            job.url = Some(url.clone());
+            job.instant = true;
+            job.finished_at = Some(chrono::Utc::now().timestamp());
+        });
+        let Some(done) = done.filter(|job| job.instant) else {
+            continue;
+        };
+        log::info!("Instant uploaded {}", done.name);
+        crate::recent_uploads::record(app, &done.name, &url);
+        emit_done(app, &done);
+        skipped.ids.push(done.id.clone());
+        skipped.files += 1;
+        skipped.bytes += done.size;
+    }
+    save(queue);
+    Ok(())
+}
+
+/// 中止进行中的任务（暂停或取消时）
+fn abort(queue: &UploadQueue, id: &str) {
+    if let Some(task) = queue.running().remove(id) {
+        task.active.store(false, Ordering::SeqCst);
+        task.handle.abort();
+    }
+}
+
+/// 选择要操作的任务：指定 id 时只选该任务，否则选出所有满足条件的任务
+fn select(queue: &UploadQueue, id: Option<&str>, filter: impl Fn(&UploadJob) -> bool) -> Vec<String> {
+    queue
+        .jobs()
+        .iter()
+        .filter(|job| id.map_or(true, |id| job.id == id) && filter(job))
+        .map(|job| job.id.clone())
+        .collect()
+}
+
+fn queue_state<R: Runtime>(app: &AppHandle<R>) -> Result<tauri::State<'_, UploadQueue>, String> {
+    app.try_state::<UploadQueue>()
+        .ok_or_else(|| "Upload queue not initialized".to_string())
+}
+
+/// 把本地文件加入上传队列，返回新建的任务（不满足类型、大小限制的文件直接标记为失败）
+pub fn enqueue<R: Runtime>(
+    app: &AppHandle<R>,
+    paths: Vec<PathBuf>,
+    options: &UploadOptions,
+) -> Result<Vec<UploadJob>, String> {
+    let queue = queue_state(app)?;
+    let config = AppConfigData::load();
+    let prefix = chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0);
+    let instant = config.float_ball.upload.instant.enabled;
+    let jobs: Vec<UploadJob> = paths
+        .into_iter()
+        .enumerate()
+        .map(|(index, path)| {
+            let mut job = new_job(&config, path, options, format!("{:x}-{}", prefix, index));
+            if instant && job.status == JobStatus::Queued {
+                job.status = JobStatus::Checking;
+            }
+            job
+        })
+        .collect();
+    queue.jobs().extend(jobs.iter().cloned());
+    for job in jobs.iter().filter(|job| job.status == JobStatus::Failed) {
+        log::warn!("Upload rejected: {}", job.error.as_deref().unwrap_or_default());
+        emit_done(app, job);
+    }
+    log::info!("Enqueued {} upload(s)", jobs.len());
+    let checking: Vec<String> = jobs
+        .iter()
+        .filter(|job| job.status == JobStatus::Checking)
+        .map(|job| job.id.clone())
+        .collect();
+    if !checking.is_empty() {
+        tauri::async_runtime::spawn(check_instant(app.clone(), checking));
+    }
+    pump(app);
+    Ok(jobs)
+}
+
+/// 等待指定的任务结束或暂停，返回它们当时的状态（已从队列中移除的任务不再返回）
+pub async fn wait<R: Runtime>(app: &AppHandle<R>, ids: &[String]) -> Vec<UploadJob> {
+    loop {
+        let Ok(queue) = queue_state(app) else {
+            return Vec::new();
+        };
+        let jobs: Vec<UploadJob> = ids.iter().filter_map(|id| queue.get(id)).collect();
+        if jobs
+            .iter()
+            .all(|job| job.status.is_finished() || job.status == JobStatus::Paused)
+        {
+            return jobs;
+        }
+        tokio::time::sleep(PROGRESS_INTERVAL).await;
+    }
+}
+
+/// 文件是否还被未完成的任务引用（暂停或失败的任务之后还可能继续）
+pub fn in_use<R: Runtime>(app: &AppHandle<R>, path: &Path) -> bool {
+    queue_state(app).is_ok_and(|queue| {
+        queue.jobs().iter().any(|job| {
+            job.path == path && !matches!(job.status, JobStatus::Done | JobStatus::Cancelled)
+        })
+    })
+}
+
+/// 把本地文件加入上传队列（前端调用），返回新建的任务
+#[tauri::command]
+pub fn enqueue_upload<R: Runtime>(
+    app: AppHandle<R>,
+    paths: Vec<PathBuf>,
+    options: Option<UploadOptions>,
+) -> Result<Vec<UploadJob>, String> {
+    enqueue(&app, paths, &options.unwrap_or_default())
+}
+
+/// 暂停任务；不指定 id 时暂停所有检测、排队和上传中的任务。继续时普通上传从头开始，分片上传从已确认的分片之后继续
+#[tauri::command]
+pub fn pause_upload<R: Runtime>(app: AppHandle<R>, id: Option<String>) -> Result<(), String> {
+    let queue = queue_state(&app)?;
+    let ids = select(&queue, id.as_deref(), |job| {
+        matches!(
+            job.status,
+            JobStatus::Checking | JobStatus::Queued | JobStatus::Uploading
+        )
+    });
+    for id in ids {
+        abort(&queue, &id);
+        if let Some(job) = queue.update(&id, |job| {
+            job.status = JobStatus::Paused;
+            job.sent = job.confirmed();
+        }) {
+            emit_progress(&app, &job);
+        }
+    }
+    pump(&app);
+    Ok(())
+}
+
+/// 继续暂停的任务或重试失败的任务；不指定 id 时对所有这类任务生效
+#[tauri::command]
+pub fn resume_upload<R: Runtime>(app: AppHandle<R>, id: Option<String>) -> Result<(), String> {
+    let queue = queue_state(&app)?;
+    let ids = select(&queue, id.as_deref(), |job| {
+        matches!(job.status, JobStatus::Paused | JobStatus::Failed)
+    });
+    let config = AppConfigData::load();
+    for id in ids {
+        let Some(job) = queue.get(&id) else {
+            continue;
+        };
+        // 失败的任务可能是文件被删除或超出限制，重新检查
+        let checked = new_job(&config, job.path.clone(), &job.options, job.id.clone());
+        if let Some(job) = queue.update(&id, |job| {
+            job.status = checked.status;
+            job.size = checked.size;
+            job.error = checked.error;
+            job.finished_at = checked.finished_at;
+            // 普通上传从头开始，进度归零；分片上传保留已确认的分片
+            job.sent = job.confirmed();
+        }) {
+            emit_progress(&app, &job);
+        }
+    }
+    pump(&app);
+    Ok(())
+}
+
+/// 取消任务；不指定 id 时取消所有未结束的任务
+#[tauri::command]
+pub fn cancel_upload<R: Runtime>(app: AppHandle<R>, id: Option<String>) -> Result<(), String> {
+    let queue = queue_state(&app)?;
+    let ids = select(&queue, id.as_deref(), |job| !job.status.is_finished());
+    for id in ids {
+        abort(&queue, &id);
+        if let Some(job) = queue.update(&id, |job| {
+            job.status = JobStatus::Cancelled;
+            job.finished_at = Some(chrono::Utc::now().timestamp());
+            job.chunked = None;
+        }) {
+            emit_done(&app, &job);
+        }
+    }
+    pump(&app);
+    Ok(())
+}
+
+/// 队列中的所有任务，按加入顺序
+#[tauri::command]
+pub fn list_uploads<R: Runtime>(app: AppHandle<R>) -> Result<Vec<UploadJob>, String> {
+    Ok(queue_state(&app)?.jobs().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::{wait_until, Gate, Response, TestServer};
+    use serde_json::json;
+    use tauri::test::{mock_app, MockRuntime};
+
+    /// 队列读取全局配置，测试逐个运行
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    /// 上传接口按文件名返回地址；gate 不为空时请求停在 gate 上直到放行
+    fn upload_server(gate: Option<Arc<Gate>>) -> TestServer {
+        TestServer::start(move |request| {
+            let name = request.file_name()?;
+            if let Some(gate) = &gate {
+                if name != "done.txt" {
+                    gate.wait();
+                }
+            }
+            Some(Response::ok(json!({ "url": format!("https://files.test/{}", name) })))
+        })
+    }
+
+    struct Harness {
+        app: tauri::App<MockRuntime>,
+        dir: tempfile::TempDir,
+        _serial: std::sync::MutexGuard<'static, ()>,
+    }
+
+    impl Harness {
+        fn new(server: &TestServer, concurrent: u32) -> Self {
+            Self::with_instant(server, concurrent, false)
+        }
+
+        fn with_instant(server: &TestServer, concurrent: u32, instant: bool) -> Self {
+            let serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+            let mut config = AppConfigData::default();
+            config.servers.profiles[0].base_url = server.url.clone();
+            config.float_ball.upload.concurrent = concurrent;
+            config.float_ball.upload.instant.enabled = instant;
+            AppConfigData::set_current(config);
+
+            let dir = tempfile::tempdir().unwrap();
+            let app = mock_app();
+            app.manage(UploadQueue::load(dir.path().join("data")));
+            app.manage(instant_upload::HashCache::load(dir.path().join("cache")));
+            Self {
+                app,
+                dir,
+                _serial: serial,
+            }
+        }
+
+        fn handle(&self) -> &AppHandle<MockRuntime> {
+            self.app.handle()
+        }
+
+        fn file(&self, name: &str, content: &str) -> PathBuf {
+            let path = self.dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            path
+        }
+
+        fn enqueue(&self, paths: Vec<PathBuf>, options: &UploadOptions) -> Vec<String> {
+            enqueue(self.handle(), paths, options)
+                .unwrap()
+                .into_iter()
+                .map(|job| job.id)
+                .collect()
+        }
+
+        fn job(&self, id: &str) -> UploadJob {
+            self.handle().state::<UploadQueue>().get(id).unwrap()
+        }
+
+        fn status(&self, id: &str) -> JobStatus {
+            self.job(id).status
+        }
+
+        fn wait(&self, ids: &[String]) -> Vec<UploadJob> {
+            tauri::async_runtime::block_on(async {
+                tokio::time::timeout(Duration::from_secs(10), wait(self.handle(), ids)).await
+            })
+            .expect("uploads did not finish")
+        }
+    }
+
+    #[test]
+    fn enqueued_files_are_uploaded_and_missing_files_rejected() {
+        let server = upload_server(None);
+        let harness = Harness::new(&server, 3);
+        let options = UploadOptions {
+            folder_id: Some("7".to_string()),
+            ..Default::default()
+        };
+        let paths = vec![
+            harness.file("a.txt", "alpha"),
+            harness.file("b.png", "bravo"),
+            harness.dir.path().join("missing.txt"),
+        ];
+        let ids = harness.enqueue(paths, &options);
+
+        let jobs = harness.wait(&ids);
+        let statuses: Vec<JobStatus> = jobs.iter().map(|job| job.status).collect();
+        assert_eq!(statuses, [JobStatus::Done, JobStatus::Done, JobStatus::Failed]);
+        assert_eq!(jobs[0].url.as_deref(), Some("https://files.test/a.txt"));
+        assert_eq!(jobs[0].sent, 5);
+        assert!(jobs[2].error.as_deref().unwrap().contains("missing.txt"));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        let a = requests.iter().find(|r| r.file_name().as_deref() == Some("a.txt")).unwrap();
+        assert!(a.contains(b"alpha"));
+        assert_eq!(a.field("folder_id").as_deref(), Some("7"));
+    }
+
+    #[test]
+    fn no_more_than_concurrent_uploads_run_at_once() {
+        let gate = Arc::new(Gate::default());
+        let server = upload_server(Some(gate.clone()));
+        let harness = Harness::new(&server, 2);
+        let paths = (0..5)
+            .map(|i| harness.file(&format!("{}.txt", i), "data"))
+            .collect();
+        let ids = harness.enqueue(paths, &UploadOptions::default());
+
+        wait_until("two uploads to reach the server", || gate.waiting() == 2);
+        std::thread::sleep(Duration::from_millis(300));
+        assert_eq!(gate.waiting(), 2);
+        let statuses: Vec<JobStatus> = ids.iter().map(|id| harness.status(id)).collect();
+        assert_eq!(
+            statuses,
+            [
+                JobStatus::Uploading,
+                JobStatus::Uploading,
+                JobStatus::Queued,
+                JobStatus::Queued,
+                JobStatus::Queued
+            ]
+        );
+
+        gate.open();
+        let jobs = harness.wait(&ids);
+        assert!(jobs.iter().all(|job| job.status == JobStatus::Done));
+        assert_eq!(server.requests().len(), 5);
+    }
+
+    #[test]
+    fn paused_uploads_restart_and_cancelled_uploads_stop() {
+        let gate = Arc::new(Gate::default());
+        let server = upload_server(Some(gate.clone()));
+        let harness = Harness::new(&server, 1);
+        let ids = harness.enqueue(
+            vec![harness.file("a.txt", "alpha"), harness.file("b.txt", "bravo")],
+            &UploadOptions::default(),
+        );
+        let (a, b) = (ids[0].clone(), ids[1].clone());
+        wait_until("a to reach the server", || gate.waiting() == 1);
+        assert_eq!(harness.status(&b), JobStatus::Queued);
+
+        // 暂停 a 后空出的位置交给 b
+        pause_upload(harness.handle().clone(), Some(a.clone())).unwrap();
+        assert_eq!(harness.status(&a), JobStatus::Paused);
+        assert_eq!(harness.job(&a).sent, 0);
+        assert_eq!(harness.status(&b), JobStatus::Uploading);
+
+        cancel_upload(harness.handle().clone(), Some(b.clone())).unwrap();
+        let cancelled = harness.job(&b);
+        assert_eq!(cancelled.status, JobStatus::Cancelled);
+        assert!(cancelled.finished_at.is_some());
+
+        resume_upload(harness.handle().clone(), Some(a.clone())).unwrap();
+        assert_eq!(harness.status(&a), JobStatus::Uploading);
+
+        gate.open();
+        let jobs = harness.wait(&ids);
+        assert_eq!(jobs[0].status, JobStatus::Done);
+        // 重新上传时进度从零计起，被中止的请求不再累加
+        assert_eq!(jobs[0].sent, 5);
+        assert_eq!(jobs[1].status, JobStatus::Cancelled);
+        assert_eq!(jobs[1].url, None);
+        // a 暂停前发出的请求被丢弃，继续时重新上传
+        let names: Vec<String> = server
+            .requests()
+            .iter()
+            .filter_map(|request| request.file_name())
+            .collect();
+        assert_eq!(names.iter().filter(|name| *name == "a.txt").count(), 2);
+    }
+
+    #[test]
+    fn interrupted_uploads_pause_and_rejected_uploads_fail() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let server = {
+            let dropped = dropped.clone();
+            TestServer::start(move |request| {
+                let name = request.file_name()?;
+                match name.as_str() {
+                    // 第一次上传时连接中断
+                    "flaky.txt" if !dropped.swap(true, Ordering::SeqCst) => None,
+                    "denied.txt" => Some(Response::error(403, "forbidden")),
+                    _ => Some(Response::ok(json!({ "url": format!("https://files.test/{}", name) }))),
+                }
+            })
+        };
+        let harness = Harness::new(&server, 2);
+        let ids = harness.enqueue(
+            vec![harness.file("flaky.txt", "flaky"), harness.file("denied.txt", "denied")],
+            &UploadOptions::default(),
+        );
+
+        let jobs = harness.wait(&ids);
+        assert_eq!(jobs[0].status, JobStatus::Paused);
+        assert!(jobs[0].error.is_some());
+        assert_eq!(jobs[0].finished_at, None);
+        assert_eq!(jobs[1].status, JobStatus::Failed);
+        assert!(jobs[1].error.as_deref().unwrap().contains("forbidden"));
+
+        resume_upload(harness.handle().clone(), Some(ids[0].clone())).unwrap();
+        let job = harness.wait(&ids[..1]).remove(0);
+        assert_eq!(job.status, JobStatus::Done);
+        assert_eq!(job.url.as_deref(), Some("https://files.test/flaky.txt"));
+        assert_eq!(job.error, None);
+    }
+
+    #[test]
+    fn unfinished_jobs_are_requeued_after_a_restart() {
+        let gate = Arc::new(Gate::default());
+        let server = upload_server(Some(gate.clone()));
+        let harness = Harness::new(&server, 1);
+        let done = harness.enqueue(vec![harness.file("done.txt", "done")], &UploadOptions::default());
+        harness.wait(&done);
+
+        let ids = harness.enqueue(
+            vec![harness.file("a.txt", "alpha"), harness.file("b.txt", "bravo")],
+            &UploadOptions::default(),
+        );
+        wait_until("a to reach the server", || gate.waiting() == 1);
+        pause_upload(harness.handle().clone(), Some(ids[1].clone())).unwrap();
+
+        // 模拟退出时 a 仍在上传：重新读取队列文件
+        let reloaded = UploadQueue::load(harness.dir.path().join("data"));
+        let jobs = reloaded.jobs().clone();
+        assert_eq!(jobs.len(), 3);
+        assert_eq!(jobs[0].status, JobStatus::Done);
+        assert_eq!(jobs[0].url.as_deref(), Some("https://files.test/done.txt"));
+        assert_eq!(jobs[1].status, JobStatus::Queued);
+        assert_eq!(jobs[1].sent, 0);
+        assert_eq!(jobs[1].path, harness.dir.path().join("a.txt"));
+        assert_eq!(jobs[2].status, JobStatus::Paused);
+        gate.open();
+    }
+
+    #[test]
+    fn duplicate_files_are_instant_uploaded_without_their_content() {
+        use md5::{Digest, Md5};
+        use tauri::Listener;
+
+        let duplicate = format!("{:x}", Md5::digest(b"duplicate bytes"));
+        let server = {
+            let duplicate = duplicate.clone();
+            TestServer::start(move |request| {
+                if request.path.ends_with("/check-duplicate") {
+                    return Some(Response::ok(json!({ "duplicates": [duplicate.to_uppercase()] })));
+                }
+                let name = if request.path.ends_with("/instant-upload") {
+                    request.json()["file_name"].as_str()?.to_string()
+                } else {
+                    request.file_name()?
+                };
+                Some(Response::ok(json!({ "url": format!("https://files.test/{}", name) })))
+            })
+        };
+        let harness = Harness::with_instant(&server, 2, true);
+        let (sender, summaries) = std::sync::mpsc::channel();
+        harness.handle().listen_any("upload:instant", move |event| {
+            let summary: serde_json::Value = serde_json::from_str(event.payload()).unwrap();
+            let _ = sender.send(summary);
+        });
+        let paths = vec![
+            harness.file("dup.png", "duplicate bytes"),
+            harness.file("new.txt", "fresh bytes"),
+        ];
+
+        let ids = harness.enqueue(paths.clone(), &UploadOptions::default());
+        let jobs = harness.wait(&ids);
+        assert_eq!(jobs[0].status, JobStatus::Done);
+        assert!(jobs[0].instant);
+        assert_eq!(jobs[0].url.as_deref(), Some("https://files.test/dup.png"));
+        assert_eq!(jobs[0].sent, 15);
+        assert_eq!(jobs[1].status, JobStatus::Done);
+        assert!(!jobs[1].instant);
+
+        let summary = summaries.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(summary["ids"], json!([ids[0]]));
+        assert_eq!(summary["files"], 1);
+        assert_eq!(summary["bytes"], 15);
+
+        // 整批文件只检测一次，重复文件只创建记录、不发送内容
+        let checks = server.requests_to("/check-duplicate");
+        assert_eq!(checks.len(), 1);
+        let files = checks[0].json()["files"].clone();
+        assert_eq!(files[0]["md5"], json!(duplicate));
+        assert_eq!(files[0]["size"], 15);
+        assert_eq!(files[1]["file_name"], "new.txt");
+        let instant = server.requests_to("/instant-upload");
+        assert_eq!(instant.len(), 1);
+        assert_eq!(instant[0].json()["md5"], json!(duplicate));
+        assert!(!server.requests().iter().any(|request| request.contains(b"duplicate bytes")));
+        assert_eq!(
+            server
+                .requests()
+                .iter()
+                .filter(|request| request.file() == Some(&b"fresh bytes"[..]))
+                .count(),
+            1
+        );
+
+        // 大小和修改时间不变时沿用缓存的 MD5，不再读取文件：改写内容后检测的仍是原来的摘要
+        let modified = std::fs::metadata(&paths[0]).unwrap().modified().unwrap();
+        std::fs::write(&paths[0], "DUPLICATE BYTES").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&paths[0])
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        let ids = harness.enqueue(paths, &UploadOptions::default());
+        let jobs = harness.wait(&ids);
+        assert!(jobs[0].instant);
+        let checks = server.requests_to("/check-duplicate");
+        assert_eq!(checks.len(), 2);
+        assert_eq!(checks[1].json()["files"][0]["md5"], json!(duplicate));
+        let summary = summaries.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(summary["files"], 1);
+    }
+}
@@ -0,0 +1,422 @@
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+use tauri::ipc::CapabilityBuilder;
+use tauri::{
+    AppHandle, Emitter, LogicalPosition, LogicalSize, Manager, Position, Url, WebviewUrl,
+    WebviewWindow, WebviewWindowBuilder,
+};
+
+use crate::config::{AppConfigData, FloatBallConfig, WindowConfig, WindowDefinition};
+use crate::geometry::{self, Rect, Screen};
+use crate::placement;
+
+/// 把 WindowConfig 中的尺寸限制和窗口行为应用到主窗口（不含 center，仅启动时居中）
+pub fn apply_window_config(window: &WebviewWindow, window_config: &WindowConfig) {
+    // 应用窗口尺寸限制
+    let min_size = match (window_config.min_width, window_config.min_height) {
+        (Some(min_w), Some(min_h)) => Some(LogicalSize::new(min_w, min_h)),
+        _ => None,
+    };
+    let max_size = match (window_config.max_width, window_config.max_height) {
+        (Some(max_w), Some(max_h)) => Some(LogicalSize::new(max_w, max_h)),
+        _ => None,
+    };
+    let _ = window.set_min_size(min_size);
+    let _ = window.set_max_size(max_size);
+
+    // 应用窗口行为
+    let _ = window.set_resizable(window_config.resizable);
+    let _ = window.set_maximizable(window_config.maximizable);
+    let _ = window.set_minimizable(window_config.minimizable);
+    let _ = window.set_closable(window_config.closable);
+    let _ = window.set_always_on_top(window_config.always_on_top);
+    let _ = window.set_skip_taskbar(window_config.skip_taskbar);
+}
+
+/// 激活当前登录状态对应的窗口：已登录时为主窗口，否则为登录窗口
+pub fn focus_main_window(app: &AppHandle) {
+    let result = if crate::auth_state::is_authenticated(app) {
+        show_main(app)
+    } else {
+        show_login(app)
+    };
+    if let Err(e) = result {
+        log::warn!("Failed to focus window: {}", e);
+    }
+}
+
+/// 显示并聚焦主窗口，隐藏登录窗口（不检查登录状态，由调用方保证）
+pub fn show_main(app: &AppHandle) -> Result<(), String> {
+    let window = app
+        .get_webview_window(MAIN_WINDOW)
+        .ok_or_else(|| "Main window not found".to_string())?;
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())?;
+    window.unminimize().map_err(|e| e.to_string())?;
+
+    // 开发模式下打开 DevTools
+    #[cfg(debug_assertions)]
+    {
+        if AppConfigData::load().dev.open_dev_tools {
+            window.open_devtools();
+            log::info!("DevTools opened for main window");
+        }
+    }
+
+    if let Some(login) = app.get_webview_window(LOGIN_WINDOW) {
+        let _ = login.hide();
+    }
+    Ok(())
+}
+
+/// 显示并聚焦登录窗口（不存在时创建），隐藏主窗口
+pub fn show_login(app: &AppHandle) -> Result<(), String> {
+    open_window(app, LOGIN_WINDOW, &Map::new())?;
+    if let Some(main) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = main.hide();
+    }
+    Ok(())
+}
+
+/// 主窗口 label（由 tauri.conf.json 创建）
+pub const MAIN_WINDOW: &str = "main";
+pub const LOGIN_WINDOW: &str = "login";
+pub const FLOAT_BALL_WINDOW: &str = "float-ball";
+
+/// 已注册过额外能力的窗口 label；能力在整个运行期间有效，窗口关闭后重新创建时不再重复注册
+static CAPABILITY_LABELS: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// 首次为 label 注册能力时返回 true；之后的调用返回 false
+fn claim_capability(label: &str) -> bool {
+    CAPABILITY_LABELS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(label.to_string())
+}
+
+/// 注册失败时撤回，下次创建窗口时重试
+fn release_capability(label: &str) {
+    CAPABILITY_LABELS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(label);
+}
+
+/// 窗口地址：`/xxx` 为 hash 路由，其他为随前端打包的页面（如 `about.html`）；
+/// 开发模式下有 dev_url 时指向开发服务器，否则使用打包的资源。params 附加为查询参数
+pub fn window_url(route: &str, dev_url: Option<&Url>, params: &Map<String, Value>) -> WebviewUrl {
+    let query = query_string(params);
+    let path = match route.strip_prefix('/') {
+        Some(route) => format!("index.html#/{}{}", route, query),
+        None => format!("{}{}", route, query),
+    };
+    match dev_url {
+        Some(dev_url) => {
+            // index.html 由开发服务器在根路径提供
+            let relative = path.strip_prefix("index.html").unwrap_or(&path);
+            match dev_url.join(relative) {
+                Ok(url) => WebviewUrl::External(url),
+                Err(_) => WebviewUrl::App(path.into()),
+            }
+        }
+        None => WebviewUrl::App(path.into()),
+    }
+}
+
+/// 参数编码为 `?a=1&b=2`，字符串不带引号，其他值按 JSON 输出
+fn query_string(params: &Map<String, Value>) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    let mut url = Url::parse("http://localhost/").expect("static url");
+    url.query_pairs_mut().extend_pairs(params.iter().map(|(key, value)| {
+        let value = match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        (key.clone(), value)
+    }));
+    format!("?{}", url.query().unwrap_or_default())
+}
+
+fn dev_url(app: &AppHandle) -> Option<Url> {
+    if cfg!(debug_assertions) {
+        app.config().build.dev_url.clone()
+    } else {
+        None
+    }
+}
+
+/// 显示并聚焦窗口；注册的窗口不存在时按 windows 配置创建，已存在时把 params 通过 window:params 事件发给它
+pub fn open_window(
+    app: &AppHandle,
+    label: &str,
+    params: &Map<String, Value>,
+) -> Result<WebviewWindow, String> {
+    if let Some(window) = app.get_webview_window(label) {
+        log::info!("Showing window {}, visible: {}", label, window.is_visible().unwrap_or(false));
+        let _ = window.unminimize();
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+        if !params.is_empty() {
+            window
+                .emit_to(label, "window:params", params)
+                .map_err(|e| e.to_string())?;
+        }
+        return Ok(window);
+    }
+
+    let config = AppConfigData::load();
+    let definition = config
+        .window_definition(label)
+        .ok_or_else(|| format!("Window not found: {}", label))?;
+    let window = build_window(app, &config, definition, params)?;
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())?;
+    log::info!("Window {} created", label);
+    Ok(window)
+}
+
+fn build_window(
+    app: &AppHandle,
+    config: &AppConfigData,
+    definition: &WindowDefinition,
+    params: &Map<String, Value>,
+) -> Result<WebviewWindow, String> {
+    let label = definition.label.as_str();
+    if !definition.capabilities.is_empty() && claim_capability(label) {
+        let capability = definition.capabilities.iter().fold(
+            CapabilityBuilder::new(format!("{}-window", label)).window(label),
+            |capability, permission| capability.permission(permission),
+        );
+        if let Err(e) = app.add_capability(capability) {
+            release_capability(label);
+            return Err(e.to_string());
+        }
+    }
+
+    let url = window_url(&definition.route, dev_url(app).as_ref(), params);
+    let mut builder = WebviewWindowBuilder::new(app, label, url)
+        .title(&definition.title)
+        .inner_size(definition.width, definition.height)
+        .resizable(definition.resizable)
+        .maximizable(definition.maximizable)
+        .minimizable(definition.minimizable)
+        .decorations(definition.decorations)
+        .always_on_top(definition.always_on_top)
+        .skip_taskbar(definition.skip_taskbar)
+        .visible(false);
+    builder = crate::profile::scope_webview(app, builder);
+    // macOS 的透明窗口在创建后通过原生接口设置
+    #[cfg(not(target_os = "macos"))]
+    {
+        builder = builder.transparent(definition.transparent);
+    }
+    let float_ball = label == FLOAT_BALL_WINDOW;
+    if float_ball {
+        let float_config = &config.float_ball;
+        builder = builder
+            .inner_size(float_config.width, float_config.height)
+            .always_on_top(float_config.always_on_top);
+    } else if definition.center && definition.placement.is_none() {
+        builder = builder.center();
+    }
+
+    let window = builder.build().map_err(|e| e.to_string())?;
+    if definition.transparent {
+        crate::macos::set_window_transparent(&window);
+    }
+    // 放置位置可能在其他缩放比例的显示器上，按物理像素在创建后设置
+    let position = if float_ball {
+        Some(float_ball_position(app, &config.float_ball))
+    } else {
+        definition.placement.as_ref().and_then(|window_placement| {
+            placement::position(app, window_placement, definition.width, definition.height)
+                .map(Position::from)
+        })
+    };
+    if let Some(position) = position {
+        let _ = window.set_position(position);
+    }
+    // 窗口以隐藏状态创建，恢复的位置和大小在显示前生效
+    crate::window_state::restore(&window);
+    crate::window_state::track(&window);
+    Ok(window)
+}
+
+/// 显示并聚焦指定窗口（托盘 show_window 动作等使用）；主窗口和登录窗口按登录状态切换
+pub fn show_window(app: &AppHandle, label: &str) -> Result<(), String> {
+    match label {
+        MAIN_WINDOW => crate::commands::show_main_window(app.clone()),
+        LOGIN_WINDOW => show_login(app),
+        _ => open_window(app, label, &Map::new()).map(|_| ()),
+    }
+}
+
+/// 关闭窗口，窗口不存在时忽略
+pub fn close_window(app: &AppHandle, label: &str) -> Result<(), String> {
+    match app.get_webview_window(label) {
+        Some(window) => window.close().map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// 可见时隐藏，否则显示（必要时创建）；返回切换后是否可见。主窗口和登录窗口按登录状态显示
+pub fn toggle_window(
+    app: &AppHandle,
+    label: &str,
+    params: &Map<String, Value>,
+) -> Result<bool, String> {
+    if let Some(window) = app.get_webview_window(label) {
+        if window.is_visible().unwrap_or(false) {
+            window.hide().map_err(|e| e.to_string())?;
+            return Ok(false);
+        }
+    }
+    match label {
+        MAIN_WINDOW | LOGIN_WINDOW => show_window(app, label).map(|_| true),
+        _ => open_window(app, label, params).map(|_| true),
+    }
+}
+
+/// 悬浮球位置：指定了 default_x/default_y 时使用固定位置，否则按 placement 放置
+pub fn float_ball_position(app: &AppHandle, float_config: &FloatBallConfig) -> Position {
+    if float_config.default_x != 0.0 || float_config.default_y != 0.0 {
+        return LogicalPosition::new(float_config.default_x, float_config.default_y).into();
+    }
+    match placement::position(app, &float_config.placement, float_config.width, float_config.height) {
+        Some(position) => position.into(),
+        None => LogicalPosition::new(100.0, 100.0).into(),
+    }
+}
+
+/// 把 FloatBallConfig 应用到已存在的悬浮球窗口；reposition 为 false 时保留用户拖动后的位置
+pub fn apply_float_ball_config(app: &AppHandle, float_config: &FloatBallConfig, reposition: bool) {
+    let Some(window) = app.get_webview_window(FLOAT_BALL_WINDOW) else {
+        return;
+    };
+
+    let _ = window.set_size(LogicalSize::new(float_config.width, float_config.height));
+    let _ = window.set_always_on_top(float_config.always_on_top);
+
+    if reposition {
+        let _ = window.set_position(float_ball_position(app, float_config));
+    }
+    log::info!("Float ball window updated");
+}
+
+/// 显示器布局变化后重新放置悬浮球（未指定固定位置时）和设置了 placement 的已打开窗口：
+/// 停在贴靠位置的窗口在所在屏幕的缩放比例或可用区域变化后重新贴靠，
+/// 其他窗口（包括用户拖动过的）只在当前位置在新布局下不可用时移动
+pub fn reanchor(app: &AppHandle, previous: &[Screen], screens: &[Screen]) {
+    let config = AppConfigData::load();
+    let float_config = &config.float_ball;
+    let float_ball = (float_config.default_x == 0.0 && float_config.default_y == 0.0).then_some((
+        FLOAT_BALL_WINDOW,
+        &float_config.placement,
+        (float_config.width, float_config.height),
+    ));
+    let others = config
+        .windows
+        .iter()
+        .filter(|w| w.label != FLOAT_BALL_WINDOW)
+        .filter_map(|w| Some((w.label.as_str(), w.placement.as_ref()?, (w.width, w.height))));
+
+    for (label, window_placement, default_size) in float_ball.into_iter().chain(others) {
+        let Some(window) = app.get_webview_window(label) else {
+            continue;
+        };
+        let (Ok(position), Ok(outer), Ok(inner), Ok(scale)) = (
+            window.outer_position(),
+            window.outer_size(),
+            window.inner_size(),
+            window.scale_factor(),
+        ) else {
+            continue;
+        };
+        let bounds = Rect::new(position.x, position.y, outer.width, outer.height);
+        // 悬浮球大小固定为配置值，其他窗口按当前大小（用户可能调整过）
+        let size = if label == FLOAT_BALL_WINDOW {
+            default_size
+        } else {
+            (inner.width as f64 / scale, inner.height as f64 / scale)
+        };
+        let fits = crate::window_state::fits_screens(&window, screens);
+        if !geometry::needs_reanchor(previous, screens, &bounds, window_placement, size, fits) {
+            continue;
+        }
+        if let Some(position) = placement::position(app, window_placement, size.0, size.1) {
+            log::info!("Re-anchoring window {}", label);
+            let _ = window.set_position(position);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, Value)]) -> Map<String, Value> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect()
+    }
+
+    fn external(url: &str) -> WebviewUrl {
+        WebviewUrl::External(Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn routes_load_index_html_in_release_builds() {
+        assert_eq!(
+            window_url("/login", None, &Map::new()),
+            WebviewUrl::App("index.html#/login".into())
+        );
+        assert_eq!(
+            window_url("about.html", None, &Map::new()),
+            WebviewUrl::App("about.html".into())
+        );
+    }
+
+    #[test]
+    fn routes_load_the_dev_server_in_debug_builds() {
+        let dev_url = Url::parse("http://localhost:5173").unwrap();
+        assert_eq!(
+            window_url("/login", Some(&dev_url), &Map::new()),
+            external("http://localhost:5173/#/login")
+        );
+        assert_eq!(
+            window_url("about.html", Some(&dev_url), &Map::new()),
+            external("http://localhost:5173/about.html")
+        );
+    }
+
+    #[test]
+    fn params_become_query_parameters() {
+        let params = params(&[
+            ("fileId", Value::from("4 2")),
+            ("page", Value::from(3)),
+        ]);
+        assert_eq!(
+            window_url("/preview", None, &params),
+            WebviewUrl::App("index.html#/preview?fileId=4+2&page=3".into())
+        );
+        let dev_url = Url::parse("http://localhost:5173/").unwrap();
+        assert_eq!(
+            window_url("/preview", Some(&dev_url), &params),
+            external("http://localhost:5173/#/preview?fileId=4+2&page=3")
+        );
+    }
+
+    #[test]
+    fn capabilities_are_claimed_once_per_label() {
+        assert!(claim_capability("test-claim"));
+        assert!(!claim_capability("test-claim"));
+        assert!(claim_capability("test-claim-other"));
+
+        release_capability("test-claim");
+        assert!(claim_capability("test-claim"));
+    }
+}
@@ -0,0 +1,210 @@
+//! 记住窗口状态（app.remember_window_state）：在 Rust 侧监听窗口事件保存每个窗口的位置、大小、
+//! 最大化状态和所在显示器，窗口首次显示前恢复；保存时的显示器已断开时把窗口放回当前连接的屏幕
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewWindow, WindowEvent};
+
+use crate::config::AppConfigData;
+use crate::geometry::{self, Rect, Screen};
+
+const FILE_NAME: &str = "window-state.json";
+/// 移动、缩放时事件很密集，合并后再写文件
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// 单个窗口的状态（物理像素）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowState {
+    /// 非最大化时的外框位置和内容区大小
+    pub bounds: Rect,
+    pub maximized: bool,
+    /// 所在显示器名称
+    pub monitor: Option<String>,
+}
+
+/// 所有窗口的状态（Tauri managed state），按窗口 label 保存
+pub struct WindowStates {
+    path: PathBuf,
+    states: Mutex<BTreeMap<String, WindowState>>,
+    save_pending: AtomicBool,
+}
+
+impl WindowStates {
+    /// 从数据目录读取，文件不存在或损坏时从空开始
+    pub fn load(data_dir: PathBuf) -> Self {
+        let path = data_dir.join(FILE_NAME);
+        let states = match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                log::warn!("Ignoring corrupted {}: {}", path.display(), e);
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+        Self {
+            path,
+            states: Mutex::new(states),
+            save_pending: AtomicBool::new(false),
+        }
+    }
+
+    fn get(&self, label: &str) -> Option<WindowState> {
+        self.states
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(label)
+            .cloned()
+    }
+
+    fn set(&self, label: &str, state: WindowState) {
+        self.states
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(label.to_string(), state);
+    }
+
+    /// 先写临时文件再重命名，避免写入中断留下半个文件
+    fn save(&self) -> Result<(), String> {
+        let text = {
+            let states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+            serde_json::to_string_pretty(&*states).map_err(|e| e.to_string())?
+        };
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, text).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, &self.path).map_err(|e| e.to_string())
+    }
+}
+
+fn is_enabled() -> bool {
+    AppConfigData::load().app.remember_window_state
+}
+
+/// 窗口首次显示前恢复保存的状态；返回是否恢复了位置（调用方据此决定是否居中）
+pub fn restore(window: &WebviewWindow) -> bool {
+    if !is_enabled() {
+        return false;
+    }
+    let Some(state) = window
+        .try_state::<WindowStates>()
+        .and_then(|states| states.get(window.label()))
+    else {
+        return false;
+    };
+    if state.bounds.width == 0 || state.bounds.height == 0 {
+        return false;
+    }
+
+    let bounds = geometry::fit_to_screens(
+        state.bounds,
+        state.monitor.as_deref(),
+        &crate::placement::screens(window.app_handle()),
+    );
+    // 不可调整大小的窗口（如悬浮球）尺寸以配置为准，只恢复位置
+    if window.is_resizable().unwrap_or(false) {
+        let _ = window.set_size(PhysicalSize::new(bounds.width, bounds.height));
+    }
+    let _ = window.set_position(PhysicalPosition::new(bounds.x, bounds.y));
+    if state.maximized {
+        let _ = window.maximize();
+    }
+    log::info!("Restored state of window {}", window.label());
+    true
+}
+
+/// 窗口当前的位置在给定的屏幕上是否仍然可用：保存时的显示器仍连接（或未记录）且标题栏可见，
+/// 即 fit_to_screens 原样返回
+pub fn fits_screens(window: &WebviewWindow, screens: &[Screen]) -> bool {
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+        return false;
+    };
+    let bounds = Rect::new(position.x, position.y, size.width, size.height);
+    let monitor = window
+        .try_state::<WindowStates>()
+        .and_then(|states| states.get(window.label()))
+        .and_then(|state| state.monitor);
+    geometry::fit_to_screens(bounds, monitor.as_deref(), screens) == bounds
+}
+
+/// 读取窗口当前状态；最小化时不记录，最大化时保留之前的常规位置和大小
+fn capture(window: &WebviewWindow, previous: Option<WindowState>) -> Option<WindowState> {
+    if window.is_minimized().unwrap_or(false) {
+        return None;
+    }
+    let maximized = window.is_maximized().unwrap_or(false);
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|m| m.name().cloned());
+    let bounds = match (maximized, previous) {
+        (true, Some(previous)) => previous.bounds,
+        _ => {
+            let position = window.outer_position().ok()?;
+            let size = window.inner_size().ok()?;
+            Rect::new(position.x, position.y, size.width, size.height)
+        }
+    };
+    Some(WindowState {
+        bounds,
+        maximized,
+        monitor,
+    })
+}
+
+fn record(window: &WebviewWindow, immediately: bool) {
+    let app = window.app_handle();
+    let Some(states) = app.try_state::<WindowStates>() else {
+        return;
+    };
+    let Some(state) = capture(window, states.get(window.label())) else {
+        return;
+    };
+    states.set(window.label(), state);
+
+    if immediately {
+        if let Err(e) = states.save() {
+            log::warn!("Failed to save window state: {}", e);
+        }
+    } else if !states.save_pending.swap(true, Ordering::AcqRel) {
+        let app = app.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(SAVE_DELAY);
+            if let Some(states) = app.try_state::<WindowStates>() {
+                states.save_pending.store(false, Ordering::Release);
+                if let Err(e) = states.save() {
+                    log::warn!("Failed to save window state: {}", e);
+                }
+            }
+        });
+    }
+}
+
+/// 监听窗口的移动、缩放和关闭，随时记录状态（每次读取当前配置，关闭该选项后立即停止记录）
+pub fn track(window: &WebviewWindow) {
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        if !is_enabled() {
+            return;
+        }
+        match event {
+            WindowEvent::Moved(_) | WindowEvent::Resized(_) => record(&handle, false),
+            WindowEvent::CloseRequested { .. } => record(&handle, true),
+            _ => {}
+        }
+    });
+}
+
+/// 应用退出前把内存中的状态写入文件
+pub fn flush(app: &AppHandle) {
+    if let Some(states) = app.try_state::<WindowStates>() {
+        if let Err(e) = states.save() {
+            log::warn!("Failed to save window state: {}", e);
+        }
+    }
+}
@@ -1,6 +1,8 @@
-import { ref, computed, type Ref } from "vue";
+import { ref, computed, watch, type Ref } from "vue";
+import { invoke } from "@tauri-apps/api/core";
 import type { UploadFile } from "@/components/Upload/Progress.vue";
 import { uploadFile } from "@/api/upload";
+import { reportTrayStatus } from "@/utils/trayStatus";
 
 interface UploadOptions {
   folderId?: Ref<string>;
@@ -50,6 +52,30 @@ export function useGlobalUpload(options: UploadOptions = {}) {
   const hasUploadingFiles = computed(() => uploadingCount.value > 0);
   const hasSuccessFiles = computed(() => successCount.value > 0);
 
+  // 同步到托盘：上传数、整体进度和失败数
+  watch(
+    () => {
+      const uploading = uploadQueue.value.filter(
+        (file) => file.status === "uploading",
+      );
+      const progress =
+        uploading.length > 0
+          ? uploading.reduce((sum, file) => sum + file.progress, 0) /
+            uploading.length /
+            100
+          : null;
+      return {
+        label: "uploading",
+        active: uploading.length,
+        queued: pendingCount.value,
+        progress,
+        failed: errorCount.value,
+      };
+    },
+    (status) => reportTrayStatus("upload", status),
+    { deep: true },
+  );
+
   // 添加文件到队列（新文件插入到队列开头）
   const addFiles = (files: FileList | File[]) => {
     const fileArray = Array.from(files);
@@ -140,6 +166,12 @@ export function useGlobalUpload(options: UploadOptions = {}) {
         file.statusMessage = "上传成功";
         file.speed = 0;
         file.remainingTime = 0;
+
+        // 加入托盘「最近上传」
+        invoke("add_recent_upload", {
+          name: file.name,
+          url: file.full_url || file.url,
+        }).catch(() => {});
       } else {
         throw new Error(response.message || response.error || "上传失败");
       }
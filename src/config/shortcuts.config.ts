@@ -2,7 +2,7 @@
  * 快捷键配置
  *
  * 使用说明：
- * 1. 在此配置文件中定义快捷键
+ * 1. 在 Rust 默认配置（src-tauri/src/config/mod.rs）或用户配置的 shortcuts 中定义快捷键
  * 2. 使用 shortcutManager.registerHandler() 注册处理函数
  * 3. 调用 shortcutManager.registerAll() 启用所有快捷键
  *
@@ -16,38 +16,10 @@
  * - global: true 表示全局快捷键（应用最小化也能响应）
  */
 
-export interface ShortcutConfig {
-  key: string; // 快捷键组合
-  description: string; // 描述
-  global?: boolean; // 是否全局快捷键
-  handler: string; // 处理函数名（需要通过 registerHandler 注册）
-}
+import type { ShortcutConfig } from "@/types/config";
+import { appConfig } from "./app.config";
 
-export const shortcutsConfig: ShortcutConfig[] = [
-  {
-    key: "CommandOrControl+Shift+F",
-    description: "显示/隐藏悬浮球",
-    global: true,
-    handler: "toggleFloatBall",
-  },
-  {
-    key: "CommandOrControl+,",
-    description: "打开设置",
-    global: false,
-    handler: "openSettings",
-  },
-  {
-    key: "F11",
-    description: "全屏切换",
-    global: false,
-    handler: "toggleFullscreen",
-  },
+export type { ShortcutConfig };
 
-  // 开发者可以在此添加更多快捷键
-  // {
-  //   key: 'CommandOrControl+N',
-  //   description: '新建',
-  //   global: false,
-  //   handler: 'createNew',
-  // },
-];
+// 内置快捷键来自 Rust 默认配置（src-tauri/src/config），运行时配置读取失败时使用
+export const shortcutsConfig: ShortcutConfig[] = appConfig.shortcuts;
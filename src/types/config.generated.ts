@@ -0,0 +1,257 @@
+// 此文件由 src-tauri/examples/config_schema.rs 生成，请勿手动修改
+// 与 src-tauri/src/config 中的 Rust 结构一一对应（snake_case）
+
+export interface AppConfigData {
+  app: AppSettings;
+  author: string;
+  copyright?: string | null;
+  description: string;
+  dev: DevConfig;
+  float_ball: FloatBallConfig;
+  homepage?: string | null;
+  name: string;
+  repository?: string | null;
+  /** 配置结构版本，旧文档加载时按 migrate 模块逐步升级 */
+  schema_version: number;
+  servers: ServersConfig;
+  shortcuts: ShortcutConfig[];
+  tray: TrayConfig;
+  updater: UpdaterSettings;
+  version: string;
+  window: WindowConfig;
+  windows: WindowDefinition[];
+}
+
+/** 窗口贴靠的位置 */
+export type Anchor = "top_left" | "top_right" | "bottom_left" | "bottom_right" | "center";
+
+export interface AppSettings {
+  quit_on_close: boolean;
+  remember_window_state: boolean;
+  single_instance: boolean;
+}
+
+/** 大文件分片上传：init 创建会话，逐片上传（带 SHA-256 校验），complete 合并 */
+export interface ChunkUploadConfig {
+  /** 每片大小（字节） */
+  chunk_size: number;
+  chunk_url: string;
+  complete_url: string;
+  init_url: string;
+  /** 分片上传的文件大小上限（字节），代替 upload.max_file_size */
+  max_file_size: number;
+  /** 单个分片失败后的重试次数 */
+  retries: number;
+  /** 超过该大小（字节）的文件分片上传，0 表示不分片 */
+  threshold: number;
+}
+
+export interface DevConfig {
+  open_dev_tools: boolean;
+}
+
+export interface FloatBallConfig {
+  always_on_top: boolean;
+  /** 固定位置（逻辑像素）；都为 0 时按 placement 放置 */
+  default_x: number;
+  default_y: number;
+  enabled: boolean;
+  height: number;
+  panel: FloatBallPanelConfig;
+  placement: WindowPlacement;
+  upload: FloatBallUploadConfig;
+  width: number;
+}
+
+export interface FloatBallPanelConfig {
+  expand_on_hover: boolean;
+  height: number;
+  hover_delay: number;
+  width: number;
+}
+
+export interface FloatBallUploadConfig {
+  allowed_types: string[];
+  api_url: string;
+  chunk: ChunkUploadConfig;
+  concurrent: number;
+  instant: InstantUploadConfig;
+  max_file_size: number;
+}
+
+/** 秒传：上传前在本地计算 MD5，整批询问服务端哪些文件已存在，已存在的文件不再上传内容 */
+export interface InstantUploadConfig {
+  /** 批量检测已存在的文件 */
+  check_url: string;
+  enabled: boolean;
+  /** 按 MD5 创建文件记录 */
+  instant_url: string;
+}
+
+/** 复制上传链接时使用的格式 */
+export type LinkFormat = "url" | "markdown" | "html";
+
+/** 放置窗口时选择的显示器；找不到时退回主显示器 */
+export type MonitorTarget = {
+    type: "cursor";
+  } | {
+    type: "main_window";
+  } | {
+    type: "primary";
+  } | {
+    name: string;
+    type: "named";
+  };
+
+export interface RecentUploadsConfig {
+  /** 点击记录时复制到剪贴板的格式 */
+  copy_format: LinkFormat;
+  /** 最多保留的记录数 */
+  limit: number;
+}
+
+/** 一个具名的服务端配置 */
+export interface ServerProfile {
+  /** 服务端根地址，如 `http://localhost:9520`；上传等相对路径基于它拼接 */
+  base_url: string;
+  name: string;
+  /** 请求超时（毫秒） */
+  timeout_ms: number;
+  tls: ServerTlsConfig;
+}
+
+export interface ServerTlsConfig {
+  /** 接受无效或自签名证书（仅用于本地调试） */
+  accept_invalid_certs: boolean;
+  /** 额外信任的 CA 证书（PEM 文件路径） */
+  ca_cert?: string | null;
+}
+
+export interface ServersConfig {
+  /** 当前使用的服务端配置名 */
+  active: string;
+  profiles: ServerProfile[];
+}
+
+export interface ShortcutConfig {
+  description: string;
+  /** 全局快捷键（应用最小化也能响应） */
+  global: boolean;
+  /** 处理函数名，前端通过 shortcutManager.registerHandler 注册 */
+  handler: string;
+  /** 快捷键组合，如 `CommandOrControl+Shift+F` */
+  key: string;
+}
+
+/** 托盘菜单项点击后执行的动作 */
+export type TrayAction = {
+    label: string;
+    type: "show_window";
+  } | {
+    route: string;
+    type: "open_route";
+  } | {
+    event: string;
+    payload?: unknown;
+    type: "emit";
+  } | {
+    type: "toggle_float_ball";
+  } | {
+    type: "upload_clipboard";
+  } | {
+    type: "open_url";
+    url: string;
+  } | {
+    type: "quit";
+  };
+
+export interface TrayConfig {
+  enabled: boolean;
+  menus: TrayMenuEntry[];
+  recent_uploads: RecentUploadsConfig;
+  title?: string | null;
+  tooltip?: string | null;
+}
+
+/** 托盘菜单节点；子菜单可以任意嵌套，也可以直接放在顶层 */
+export type TrayMenuEntry = {
+    /** 菜单快捷键，如 `CmdOrCtrl+Q` */
+    accelerator?: string | null;
+    action: TrayAction;
+    disabled?: boolean;
+    /** PNG 图标路径，相对路径按应用资源目录解析 */
+    icon?: string | null;
+    id: string;
+    kind: "item";
+    label: string;
+  } | {
+    accelerator?: string | null;
+    disabled?: boolean;
+    id: string;
+    kind: "check";
+    label: string;
+    /** 绑定的配置项路径，如 `app.quit_on_close` */
+    setting: string;
+  } | {
+    kind: "separator";
+  } | {
+    disabled?: boolean;
+    items: TrayMenuEntry[];
+    kind: "submenu";
+    label: string;
+  } | {
+    kind: "recent_uploads";
+    label: string;
+  };
+
+export interface UpdaterSettings {
+  /** 是否允许检查和安装更新（前端 updater.config.ts 也需启用） */
+  enabled: boolean;
+}
+
+export interface WindowConfig {
+  always_on_top: boolean;
+  center: boolean;
+  closable: boolean;
+  height: number;
+  max_height?: number | null;
+  max_width?: number | null;
+  maximizable: boolean;
+  min_height?: number | null;
+  min_width?: number | null;
+  minimizable: boolean;
+  resizable: boolean;
+  skip_taskbar: boolean;
+  width: number;
+}
+
+/** 注册的窗口，通过 open_window 按 label 创建；主窗口由 tauri.conf.json 创建，不在此列 */
+export interface WindowDefinition {
+  always_on_top?: boolean;
+  /** 创建窗口时额外授予的权限（如 `core:window:allow-set-size`）； tauri.conf.json 中已按 label 声明的能力不需要重复 */
+  capabilities?: string[];
+  /** 创建时居中（设置了 placement 时以 placement 为准） */
+  center?: boolean;
+  decorations?: boolean;
+  height: number;
+  label: string;
+  maximizable?: boolean;
+  minimizable?: boolean;
+  /** 创建时的位置；显示器变化时重新放置 */
+  placement?: WindowPlacement | null;
+  resizable?: boolean;
+  /** 前端路由（如 `/login`，使用 hash 路由）或独立页面（如 `about.html`） */
+  route: string;
+  skip_taskbar?: boolean;
+  title: string;
+  transparent?: boolean;
+  width: number;
+}
+
+/** 窗口在显示器可用区域（不含任务栏、菜单栏）中的位置 */
+export interface WindowPlacement {
+  anchor: Anchor;
+  /** 距可用区域边缘的距离（逻辑像素） */
+  margin: number;
+  monitor: MonitorTarget;
+}
@@ -6,8 +6,8 @@
  * 事件流程：
  * 1. 用户拖动文件到悬浮球
  * 2. Tauri 触发 tauri://drag-drop 事件（DragDropPayload）
- * 3. 悬浮球发送自定义 files-dropped 事件（FilesDroppedPayload）
- * 4. 主窗口监听并处理文件
+ * 3. 悬浮球把文件加入 Rust 上传队列（进度见 upload:progress / upload:done）
+ * 4. 悬浮球发送自定义 files-dropped 事件（FilesDroppedPayload），主窗口监听并处理文件
  */
 
 /**
@@ -33,3 +33,17 @@ export interface FilesDroppedPayload {
   /** 文件路径数组 */
   files: string[];
 }
+
+/**
+ * 重复启动事件 payload
+ *
+ * 事件名：second-instance
+ * 应用已在运行时再次启动（如"打开方式"、拖文件到图标），
+ * 新进程把参数转发给已运行的实例后退出
+ */
+export interface SecondInstancePayload {
+  /** 新进程的命令行参数（第一个是可执行文件路径） */
+  args: string[];
+  /** 新进程的工作目录，用于解析相对路径 */
+  cwd: string;
+}
@@ -1,18 +1,15 @@
-export interface TrayMenuItem {
-  id: string; // 菜单项 ID
-  label: string; // 菜单项文本
-  action?: "about" | "show" | "quit" | "custom"; // 预定义动作
-  customAction?: () => void; // 自定义动作（仅用于前端参考）
-}
+// 托盘菜单类型由 Rust 结构生成（config.generated.ts），这里只做再导出并按 kind 拆出各类节点
+import type { TrayMenuEntry } from "./config.generated";
 
-export interface TrayMenuGroup {
-  label: string; // 菜单分组标题
-  items: TrayMenuItem[]; // 该分组下的菜单项
-}
+export type {
+  LinkFormat,
+  RecentUploadsConfig,
+  TrayAction,
+  TrayConfig,
+  TrayMenuEntry,
+} from "./config.generated";
 
-export interface TrayConfig {
-  enabled: boolean; // 是否启用托盘
-  tooltip?: string; // 托盘悬停提示
-  title?: string; // 托盘标题（macOS 会显示在图标旁）
-  menus: TrayMenuGroup[]; // 菜单分组列表
-}
+export type TrayMenuItem = Extract<TrayMenuEntry, { kind: "item" }>;
+export type TrayCheckItem = Extract<TrayMenuEntry, { kind: "check" }>;
+export type TraySubmenu = Extract<TrayMenuEntry, { kind: "submenu" }>;
+export type TrayRecentUploads = Extract<TrayMenuEntry, { kind: "recent_uploads" }>;
@@ -1,69 +1,48 @@
 /**
- * 认证初始化工具
- * 在应用启动时检查登录状态，决定显示哪个窗口
+ * 登录状态工具
+ * 登录状态由 Rust 侧的状态机维护，并由它决定显示登录窗口还是主窗口；
+ * 前端在登录、登出、会话过期时上报事件
  */
-import { getCurrentWindow } from "@tauri-apps/api/window";
-import { storage } from "./storage";
-import { TOKEN_KEY } from "@/constants/api";
 import { invoke } from "@tauri-apps/api/core";
 import { logger } from "@/utils/logger";
 
-/**
- * 初始化认证状态和窗口显示
- */
-export async function initializeAuth(): Promise<void> {
-  try {
-    const currentWindow = getCurrentWindow();
-    const currentLabel = currentWindow.label;
+export type AuthStatus =
+  | "logged_out"
+  | "logging_in"
+  | "authenticated"
+  | "session_expired";
 
-    // 检查是否有token
-    const token = storage.get<string>(TOKEN_KEY);
-    const isLoggedIn = Boolean(token);
+export type AuthEvent =
+  | "login_started"
+  | "login_succeeded"
+  | "login_failed"
+  | "logout"
+  | "session_expired";
 
-    await logger.debug("[Auth] 初始化状态", {
-      window: currentLabel,
-      loggedIn: isLoggedIn,
-    });
+/** auth:state-changed 事件载荷 */
+export interface AuthStateChangedPayload {
+  from: AuthStatus;
+  to: AuthStatus;
+  event: AuthEvent;
+}
 
-    if (currentLabel === "login") {
-      // 当前是登录窗口
-      if (isLoggedIn) {
-        // 已登录，应该显示主窗口
-        await logger.debug("[Auth] 已登录，切换到主窗口 via backend");
-        await currentWindow.hide();
-        try {
-          await invoke("show_main_window");
-        } catch (error) {
-          await logger.error("[Auth] Failed to show main window via backend", {
-            error: String(error),
-          });
-        }
-      } else {
-        // 未登录，保持登录窗口显示
-        await logger.debug("[Auth] 未登录，显示登录窗口");
-        await currentWindow.show();
-        await currentWindow.setFocus();
-      }
-    } else if (currentLabel === "main") {
-      // 当前是主窗口
-      if (isLoggedIn) {
-        // 已登录，显示主窗口
-        await logger.debug("[Auth] 已登录，显示主窗口");
-        await currentWindow.show();
-        await currentWindow.setFocus();
-      } else {
-        // 未登录，切换到登录窗口（后端处理）
-        await logger.debug("[Auth] 未登录，切换到登录窗口 via backend");
-        try {
-          await invoke("show_login_window");
-        } catch (error) {
-          await logger.error("[Auth] Failed to show login window via backend", {
-            error: String(error),
-          });
-        }
-      }
-    }
+/**
+ * 获取当前登录状态
+ */
+export async function getAuthState(): Promise<AuthStatus> {
+  return invoke<AuthStatus>("get_auth_state");
+}
+
+/**
+ * 上报登录事件，返回转换后的状态；当前状态不允许该事件时返回 null（状态不变）
+ */
+export async function updateAuthState(
+  event: AuthEvent,
+): Promise<AuthStatus | null> {
+  try {
+    return await invoke<AuthStatus>("update_auth_state", { event });
   } catch (error) {
-    await logger.error("[Auth] 初始化失败", { error: String(error) });
+    await logger.warn("[Auth] 状态转换被拒绝", { event, error: String(error) });
+    return null;
   }
 }
@@ -4,6 +4,7 @@ import { storage } from "@/utils/storage";
 import { invoke } from "@tauri-apps/api/core";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { logger } from "@/utils/logger";
+import { updateAuthState } from "@/utils/auth";
 import message from "@/components/Message/message";
 import type {
   ApiError,
@@ -55,8 +56,10 @@ async function handleUnauthorized() {
     // 显示提示
     message.warning("登录已过期，请重新登录");
 
-    // 跳转到登录窗口
-    await invoke("show_login_window");
+    // 标记会话过期（Rust 侧随之显示登录窗口）；已不在登录状态时直接显示登录窗口
+    if (!(await updateAuthState("session_expired"))) {
+      await invoke("show_login_window");
+    }
     await logger.info("[HTTP] Switched to login window");
   } catch (error) {
     await logger.error("[HTTP] Failed to handle unauthorized", {
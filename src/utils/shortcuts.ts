@@ -33,12 +33,14 @@
  * ```
  */
 
+import { invoke } from "@tauri-apps/api/core";
 import { register, unregister } from "@tauri-apps/plugin-global-shortcut";
 import { logger } from "@/utils/logger";
 import {
   shortcutsConfig,
   type ShortcutConfig,
 } from "@/config/shortcuts.config";
+import type { AppConfigData } from "@/types/config.generated";
 
 export class ShortcutManager {
   private handlers = new Map<string, () => void | Promise<void>>();
@@ -54,14 +56,32 @@ export class ShortcutManager {
     this.handlers.set(name, handler);
   }
 
+  /**
+   * 读取运行时配置中的快捷键（可被用户配置或导入覆盖），失败时使用内置配置
+   */
+  private async loadShortcuts(): Promise<ShortcutConfig[]> {
+    try {
+      const { config } = await invoke<{ config: AppConfigData }>(
+        "get_app_config",
+      );
+      return config.shortcuts;
+    } catch (error) {
+      logger.warn("Failed to read runtime shortcuts config", {
+        error: String(error),
+      });
+      return shortcutsConfig;
+    }
+  }
+
   /**
    * 注册所有配置的快捷键
    */
   async registerAll(): Promise<void> {
-    for (const shortcut of shortcutsConfig) {
+    const shortcuts = await this.loadShortcuts();
+    for (const shortcut of shortcuts) {
       await this.registerShortcut(shortcut);
     }
-    logger.info(`Registered ${shortcutsConfig.length} shortcuts`);
+    logger.info(`Registered ${shortcuts.length} shortcuts`);
   }
 
   /**
@@ -35,6 +35,7 @@ import {
   exists,
   mkdir,
 } from "@tauri-apps/plugin-fs";
+import { invoke } from "@tauri-apps/api/core";
 import { storageConfig } from "@/config/storage.config";
 import { logger } from "@/utils/logger";
 
@@ -43,6 +44,7 @@ export class Storage {
   private autoSaveTimer: number | null = null;
   private initialized = false;
   private dirty = false; // 数据是否被修改
+  private dir = ""; // 以 --profile 启动时为 profiles/<name>，各 profile 数据互相隔离
 
   /**
    * 初始化存储
@@ -50,15 +52,22 @@ export class Storage {
   async init(): Promise<void> {
     if (this.initialized) return;
 
+    try {
+      const profile = await invoke<string | null>("get_launch_profile");
+      this.dir = profile ? `profiles/${profile}` : "";
+    } catch {
+      this.dir = "";
+    }
+
     try {
       // 检查文件是否存在
-      const fileExists = await exists(storageConfig.file.filename, {
+      const fileExists = await exists(this.path, {
         baseDir: BaseDirectory[storageConfig.file.dir],
       });
 
       if (fileExists) {
         // 读取文件
-        const content = await readTextFile(storageConfig.file.filename, {
+        const content = await readTextFile(this.path, {
           baseDir: BaseDirectory[storageConfig.file.dir],
         });
         this.data = JSON.parse(content);
@@ -84,17 +93,26 @@ export class Storage {
     // logger.debug("Storage initialized");
   }
 
+  /**
+   * 存储文件相对 baseDir 的路径
+   */
+  private get path(): string {
+    return this.dir
+      ? `${this.dir}/${storageConfig.file.filename}`
+      : storageConfig.file.filename;
+  }
+
   /**
    * 从磁盘强制重新加载（用于多窗口间同步）
    */
   async reload(): Promise<void> {
     try {
-      const fileExists = await exists(storageConfig.file.filename, {
+      const fileExists = await exists(this.path, {
         baseDir: BaseDirectory[storageConfig.file.dir],
       });
 
       if (fileExists) {
-        const content = await readTextFile(storageConfig.file.filename, {
+        const content = await readTextFile(this.path, {
           baseDir: BaseDirectory[storageConfig.file.dir],
         });
         this.data = JSON.parse(content);
@@ -213,13 +231,13 @@ export class Storage {
       // 先确保目录存在
       const baseDir = BaseDirectory[storageConfig.file.dir];
       try {
-        await mkdir("", { baseDir, recursive: true });
+        await mkdir(this.dir, { baseDir, recursive: true });
       } catch {
         // 目录可能已存在，忽略错误
       }
 
       await writeTextFile(
-        storageConfig.file.filename,
+        this.path,
         JSON.stringify(this.data, null, 2),
         { baseDir },
       );
@@ -0,0 +1,52 @@
+/**
+ * 托盘状态上报
+ *
+ * 各任务来源（上传、AI 打标等）整体上报自己的状态，Rust 端汇总后
+ * 显示在托盘 tooltip/title 上，有失败或未读时在图标上绘制角标。
+ */
+import { invoke } from "@tauri-apps/api/core";
+import { logger } from "@/utils/logger";
+
+export interface TaskStatus {
+  label?: string; // 提示中的动词，如 "uploading"、"tagging"
+  active?: number; // 进行中的任务数
+  queued?: number; // 排队中的任务数
+  progress?: number | null; // 进行中任务的整体进度（0 ~ 1）
+  failed?: number; // 失败且未处理的任务数
+  unread?: number; // 未读消息数
+}
+
+// 进度变化频繁，同一来源最多每 THROTTLE_MS 上报一次（最后一次一定会发出）
+const THROTTLE_MS = 500;
+const pending = new Map<string, TaskStatus>();
+const timers = new Map<string, ReturnType<typeof setTimeout>>();
+
+async function send(source: string, status: TaskStatus): Promise<void> {
+  try {
+    await invoke("update_tray_status", { source, status });
+  } catch (error) {
+    await logger.warn("[TrayStatus] Failed to update tray status", {
+      source,
+      error: String(error),
+    });
+  }
+}
+
+/**
+ * 上报某个来源的状态；全部为 0 时该来源从托盘状态中移除
+ */
+export function reportTrayStatus(source: string, status: TaskStatus): void {
+  pending.set(source, status);
+  if (timers.has(source)) return;
+
+  void send(source, status);
+  pending.delete(source);
+  timers.set(
+    source,
+    setTimeout(() => {
+      timers.delete(source);
+      const latest = pending.get(source);
+      if (latest) reportTrayStatus(source, latest);
+    }, THROTTLE_MS),
+  );
+}
@@ -25,10 +25,12 @@
  * ```
  */
 
+import { invoke } from "@tauri-apps/api/core";
 import { check } from "@tauri-apps/plugin-updater";
 import { relaunch } from "@tauri-apps/plugin-process";
 import { updaterConfig } from "@/config/updater.config";
 import { logger } from "@/utils/logger";
+import type { AppConfigData } from "@/types/config.generated";
 
 export interface UpdateInfo {
   available: boolean;
@@ -47,11 +49,29 @@ export class Updater {
   private checkInterval: number | null = null;
   private progressCallbacks: ((progress: UpdateProgress) => void)[] = [];
 
+  /**
+   * 构建配置与运行时配置（可被管理员策略锁定）都启用时才允许更新
+   */
+  private async isEnabled(): Promise<boolean> {
+    if (!updaterConfig.enabled) return false;
+    try {
+      const { config } = await invoke<{ config: AppConfigData }>(
+        "get_app_config",
+      );
+      return config.updater.enabled;
+    } catch (error) {
+      logger.warn("Failed to read runtime updater config", {
+        error: String(error),
+      });
+      return true;
+    }
+  }
+
   /**
    * 检查是否有可用更新
    */
   async checkForUpdates(): Promise<UpdateInfo> {
-    if (!updaterConfig.enabled) {
+    if (!(await this.isEnabled())) {
       logger.warn("Updater is disabled in config");
       return { available: false };
     }
@@ -131,8 +151,8 @@ export class Updater {
   /**
    * 启动定时自动检查
    */
-  startAutoCheck(): void {
-    if (!updaterConfig.enabled) return;
+  async startAutoCheck(): Promise<void> {
+    if (!(await this.isEnabled())) return;
 
     // 启动时检查
     if (updaterConfig.checkOnStartup) {
@@ -0,0 +1,113 @@
+/**
+ * Rust 上传队列
+ *
+ * 本地文件（拖放、对话框选择等能拿到路径的场景）交给 Rust 端上传：
+ * 队列保存在磁盘上，关闭窗口不会中断上传，重启后继续未完成的任务。
+ * 开启秒传时，Rust 端先计算 MD5 并整批询问服务端，已存在的文件不再上传内容。
+ */
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { UploadOptions } from "@/api/types";
+
+export type UploadJobStatus =
+  | "checking" // 正在计算 MD5 并检测服务端是否已存在
+  | "queued"
+  | "uploading"
+  | "paused" // 用户暂停，或网络中断（error 中有原因），继续时接着上传
+  | "done"
+  | "failed" // 服务端拒绝或文件不可用
+  | "cancelled";
+
+export interface UploadJob {
+  id: string;
+  path: string;
+  name: string;
+  size: number;
+  options: UploadOptions;
+  status: UploadJobStatus;
+  sent: number; // 已发送的字节数
+  url: string | null;
+  error: string | null;
+  created_at: number; // Unix 秒
+  finished_at: number | null;
+  chunked?: ChunkSession; // 分片上传的会话（大文件）
+  instant: boolean; // 服务端已存在该文件，通过秒传完成
+}
+
+/** 分片上传会话，保存已被服务端确认的分片 */
+export interface ChunkSession {
+  upload_id: string;
+  chunk_size: number;
+  total_chunks: number;
+  next_chunk: number; // 下一个要发送的分片序号
+  modified: number; // 开始上传时文件的修改时间（Unix 毫秒）
+}
+
+/** upload:progress 事件载荷 */
+export interface UploadProgressPayload {
+  id: string;
+  name: string;
+  status: UploadJobStatus;
+  sent: number;
+  total: number;
+}
+
+/** upload:instant 事件载荷：一批文件中通过秒传跳过的文件 */
+export interface UploadInstantPayload {
+  ids: string[];
+  files: number;
+  bytes: number; // 跳过上传的字节数
+}
+
+/**
+ * 把本地文件加入上传队列；不满足类型、大小限制的文件直接标记为失败
+ */
+export async function enqueueUpload(
+  paths: string[],
+  options?: UploadOptions,
+): Promise<UploadJob[]> {
+  return invoke<UploadJob[]>("enqueue_upload", { paths, options });
+}
+
+/** 暂停任务，不传 id 时暂停全部 */
+export async function pauseUpload(id?: string): Promise<void> {
+  await invoke("pause_upload", { id });
+}
+
+/** 继续暂停的任务或重试失败的任务，不传 id 时对全部生效 */
+export async function resumeUpload(id?: string): Promise<void> {
+  await invoke("resume_upload", { id });
+}
+
+/** 取消任务，不传 id 时取消全部未结束的任务 */
+export async function cancelUpload(id?: string): Promise<void> {
+  await invoke("cancel_upload", { id });
+}
+
+export async function listUploads(): Promise<UploadJob[]> {
+  return invoke<UploadJob[]>("list_uploads");
+}
+
+export function onUploadProgress(
+  handler: (payload: UploadProgressPayload) => void,
+): Promise<UnlistenFn> {
+  return listen<UploadProgressPayload>("upload:progress", (event) =>
+    handler(event.payload),
+  );
+}
+
+/** 任务结束（成功、失败或取消）时触发 */
+export function onUploadDone(
+  handler: (job: UploadJob) => void,
+): Promise<UnlistenFn> {
+  return listen<UploadJob>("upload:done", (event) => handler(event.payload));
+}
+
+/** 一批文件秒传检测结束时触发（包括没有可跳过的文件时） */
+export function onUploadInstant(
+  handler: (payload: UploadInstantPayload) => void,
+): Promise<UnlistenFn> {
+  return listen<UploadInstantPayload>("upload:instant", (event) =>
+    handler(event.payload),
+  );
+}
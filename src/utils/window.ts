@@ -63,3 +63,31 @@ export async function hideAndShowMain(): Promise<void> {
     throw error;
   }
 }
+
+/**
+ * 显示并聚焦注册的窗口（不存在时按配置创建）
+ * params 作为查询参数传给新窗口；窗口已存在时通过 window:params 事件收到
+ */
+export async function openWindow(
+  label: string,
+  params?: Record<string, unknown>,
+): Promise<void> {
+  await invoke("open_window", { label, params });
+}
+
+/**
+ * 关闭窗口（不存在时忽略）
+ */
+export async function closeWindow(label: string): Promise<void> {
+  await invoke("close_window", { label });
+}
+
+/**
+ * 切换窗口显示状态，返回切换后是否可见
+ */
+export async function toggleWindow(
+  label: string,
+  params?: Record<string, unknown>,
+): Promise<boolean> {
+  return invoke<boolean>("toggle_window", { label, params });
+}